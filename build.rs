@@ -0,0 +1,35 @@
+use std::{env, fs, path::Path};
+
+// Read the locked libcosmic revision, as libcosmic does not expose its version at runtime
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let lock_path = Path::new(&manifest_dir).join("Cargo.lock");
+    println!("cargo:rerun-if-changed={}", lock_path.display());
+    let lock = match fs::read_to_string(&lock_path) {
+        Ok(ok) => ok,
+        Err(_) => return,
+    };
+    let mut in_libcosmic = false;
+    let mut version_opt = None;
+    for line in lock.lines() {
+        if line == "[[package]]" {
+            in_libcosmic = false;
+        } else if line == "name = \"libcosmic\"" {
+            in_libcosmic = true;
+        } else if in_libcosmic {
+            if let Some(version) = line.strip_prefix("version = ") {
+                version_opt = Some(version.trim_matches('"').to_string());
+            } else if let Some(source) = line.strip_prefix("source = ") {
+                // Git sources end with the locked commit
+                if let Some((_, commit)) = source.trim_matches('"').rsplit_once('#') {
+                    if let Some(version) = &mut version_opt {
+                        version.push_str(&format!(" ({})", &commit[..commit.len().min(7)]));
+                    }
+                }
+            }
+        }
+    }
+    if let Some(version) = version_opt {
+        println!("cargo:rustc-env=LIBCOSMIC_VERSION={}", version);
+    }
+}
@@ -5,20 +5,118 @@ check-for-updates = Check for updates
 checking-for-updates = Checking for updates...
 install = Install
 no-installed-applications = No installed applications.
+installed-total-size = Total size: {$size}
+sort-by-size = Sort by size
 no-updates = All installed applications are up to date.
 no-results = No results for "{$search}".
+no-apps-in-category = No apps found in this category.
+no-apps-found = No apps found.
+backend-errors = Failed to load software sources: {$errors}
+config-save-failed = Failed to save settings
+metadata-refreshing = Downloading the latest software catalog from {$source}...
+metadata-refresh-failed = Failed to download the latest software catalog from {$source}
+switch-branch-failed = Failed to switch to branch {$branch}
+uninstalled = Uninstalled {$names}
+undo = Undo
+no-backends-enabled = All software sources are disabled. Enable a source in settings to browse applications.
 notification-in-progress = Installations and updates are in progress.
+notification-updates = {$count ->
+    [one] 1 update is available.
+    *[other] {$count} updates are available.
+}
+view-updates = View updates
+configuring = Configuring
+downloading = Downloading
+installing = Installing
+queued = Queued
+removing = Removing
+screenshot-failed = Failed to load screenshot
+package-not-found = {$name} is no longer available. Refresh and try again.
+backend-not-found = {$backend} is no longer loaded. Refresh and try again.
+retry = Retry
+ok = OK
 open = Open
+remove = Remove
 see-all = See all
+browse = Browse:
+kind-apps = Apps
+kind-addons = Add-ons
+kind-fonts = Fonts
 uninstall = Uninstall
+add-favorite = Add to favorites
+remove-favorite = Remove from favorites
 update = Update
+update-size = Update ({$size})
 update-all = Update all
+update-all-warning = Update {$count} apps and packages? System packages are updated first.
+update-all-progress = Updated {$done} of {$total}
+update-all-complete = Updated {$updated} apps and packages.
+update-all-failed = Updated {$updated} apps and packages, {$failed} failed.
+hold-updates = Hold updates
+release-hold = Release hold
+permissions = Permissions
+held = Held, not updated by update all
+restart-required-session = Log out and back in to finish applying updates.
+restart-required-system = Restart the system to finish applying updates.
+restart-now = Restart now
+restart-failed = Failed to restart.
+
+# Search
+search-installed = Search installed
+installed-only = Installed only
+search-results-count = {$count ->
+    [one] 1 result
+    *[other] {$count} results
+}
+
+# Tooltips
+search = Search
+refresh = Refresh
+previous-screenshot = Previous screenshot
+next-screenshot = Next screenshot
+close-screenshot = Close screenshot
+previous-featured = Previous featured app
+next-featured = Next featured app
+release-notes = Release notes
+move-up = Move up
+move-down = Move down
 
 # Uninstall Dialog
 uninstall-app = Uninstall {$name}
 uninstall-app-warning = Are you sure you want to uninstall {$name}?
+uninstall-app-dependents-warning = {$name} is required by {$apps}, which may stop working. Are you sure you want to uninstall {$name}?
+uninstall-selected-count = Uninstall selected ({$count})
+
+# Review Dialog
+review-changes = Review changes
+review-details = Changes including dependencies
+review-download-size = Download size: {$size}
+review-loading = Checking for additional changes...
+also-install = Also install
+also-install-description = Optional apps suggested by the developer, not required to use this app
+apply = Apply
+accept = Accept
+agreement = Agreement
+agreement-eula = License agreement
+agreement-privacy = Privacy policy
+agreement-required = You must accept this agreement to install {$name}.
+language-supported = Translated into your language ({$percentage}% complete)
+language-unsupported = Not translated into your language
+show-languages = {$count ->
+    [one] Show 1 language
+    *[other] Show {$count} languages
+}
+hide-languages = Hide languages
+filter-languages = Filter languages
+
+# Remove Unused Dialog
+remove-unused-runtimes = Remove unused runtimes
+remove-unused-runtimes-warning = Remove {$count} unused runtimes and free {$size} of disk space?
 
 # Nav Pages
+discover = Discover
+categories = Categories
+library = Library
 explore = Explore
 create = Create
 work = Work
@@ -32,9 +130,12 @@ installed-apps = Installed apps
 updates = Updates
 
 # Explore Pages
+recently-viewed = Recently Viewed
+favorites = Favorites
 editors-choice = Editor's Choice
 popular-apps = Popular Apps
 new-apps = New Apps
+new-in-distro = New in Your Distribution
 recently-updated = Recently Updated
 development-tools = Development Tools
 scientific-tools = Scientific Tools
@@ -50,15 +151,83 @@ source-installed = {$source} (installed)
 developer = Developer
 app-developers = {$app} Developers
 monthly-downloads = Flathub Monthly Downloads
+content-rating = Content Rating
+not-rated = Not rated
+all-ages = All ages
+ages = Ages {$age}+
+content-rating-contains = Contains: {$categories}
+required-by = Required by {$apps}
+similar-apps = You might also like
+loading = Loading...
+read-more = Read more
+read-less = Read less
+source-signed = Signatures from {$source} are verified.
+source-unsigned-warning = Signatures from {$source} are not verified. Only install software from sources you trust.
+runtime = Runtime: {$runtime}
+runtime-installed = Runtime: {$runtime} (installed)
+branch-default = Default branch
+branch = {$branch} ({$count} branches)
+eol-warning = This app is no longer maintained and will not receive updates.
+eol-warning-reason = This app is no longer maintained and will not receive updates: {$reason}
+get-replacement = Get replacement
+requires-memory = Requires {$memory} of memory, which is more than this system has
+recommends-memory = Recommends {$memory} of memory, which is more than this system has
+requires-display = Requires a display at least {$length} pixels across
+recommends-display = Recommends a display at least {$length} pixels across
+content-drugs = Drugs
+content-language = Language
+content-money = Money
+content-sex = Sex
+content-social = Social interaction
+content-violence = Violence
+homepage = Homepage
+bug-tracker = Report an issue
+help = Help
+support-project = Support this project
+support-project-description = Donate directly to the developers of {$app}
+source-details = Source
+source-verified = Published by the verified developer.
+source-unverified = Publisher is not verified by this source.
+source-view = View this source
+package-backend = Backend: {$backend}
+package-source = Source: {$source}
+package-name = Package: {$package}
 
 # Context Pages
 
+## About
+about = About
+version = Version {$version}
+libcosmic-version = libcosmic {$version}
+license = License: GPL-3.0-only
+
+## Operations
+operations = Operations
+operations-running = {$count ->
+    [one] 1 operation
+    *[other] {$count} operations
+} ({$percent}%)
+no-operations = No operations running
+
 ## Settings
 settings = Settings
 
+## Metadata
+metadata = Metadata
+copy-metadata = Copy
+no-metadata = No app selected
+view-metadata = View metadata
+
 ## Release notes
 latest-version = Latest version
 no-description = No description available.
+today = Today
+yesterday = Yesterday
+days-ago = {$days} days ago
+weeks-ago = {$weeks ->
+    [one] Last week
+    *[other] {$weeks} weeks ago
+}
 
 ### Appearance
 appearance = Appearance
@@ -66,3 +235,77 @@ theme = Theme
 match-desktop = Match desktop
 dark = Dark
 light = Light
+density = Density
+comfortable = Comfortable
+compact = Compact
+grid-columns = Maximum columns
+automatic = Automatic
+badges = Show new and updated badges
+badges-off = Never
+badge-days = {$days ->
+    [one] Within 1 day
+    *[other] Within {$days} days
+}
+badge-new = New
+badge-updated = Updated
+badge-beta = Beta
+hide-installed = Hide installed apps from search and browse
+
+### Explore
+explore-first = Show first
+explore-first-default = Default order
+
+### Network
+network = Network
+proxy = Proxy
+proxy-description = Used for screenshots and other downloads. Leave empty to use the system proxy.
+proxy-placeholder = http://proxy.example.com:8080
+pause-metered = Pause downloads on metered connections
+pause-metered-description = Screenshots, icons, and update checks are not downloaded in the background.
+prefetch-screenshots = Prefetch screenshots
+prefetch-screenshots-description = Download the first screenshot of an app when hovering over it, so details open faster.
+downloads-paused-metered = Background downloads are paused on this metered connection.
+metered-operation-warning = This connection is metered, downloads may incur charges.
+instance-running = Another instance of COSMIC Store is running, operations may conflict.
+
+### Software sources
+software-sources = Software sources
+backend-flatpak = Flatpak
+backend-packagekit = System packages
+preferred-source = Preferred source
+update-notifications = Check for updates and notify in the background
+prerelease = Show beta apps
+prerelease-description = Include beta and development versions that may be unstable.
+prerelease-warning = This is a beta version, which may be unstable.
+install-scope = Install Flatpak apps for
+install-scope-description = Installing for all users asks for administrator authorization.
+install-scope-user = Current user
+install-scope-system = All users
+
+### Flatpak remotes
+flatpak-remotes = Flatpak remotes
+add-remote = Add remote
+add-remote-description = URL of a .flatpakrepo file, which includes the key the remote is signed with
+add-remote-placeholder = https://dl.flathub.org/repo/flathub.flatpakrepo
+add = Add
+remote-change-failed = Failed to change Flatpak remotes
+disable-remote = Disable remote
+disable-remote-warning = Apps from {$remote} will no longer be shown or updated until it is enabled again.
+disable = Disable
+
+### Maintenance
+maintenance = Maintenance
+clear = Clear
+unused-runtimes = {$count} unused runtimes ({$size})
+export-installed = Export installed apps to Documents
+import-installed = Install apps from exported list
+export = Export
+import = Import
+export-installed-done = Exported installed apps to {$path}
+export-installed-failed = Failed to export installed apps: {$error}
+export-installed-loading = Installed apps are still loading
+import-installed-failed = Failed to read exported app list: {$error}
+import-installed-none = All apps from the exported list are already installed
+installed-list-path-failed = Failed to find the Documents folder
+developer-mode = Developer mode
+developer-mode-description = Show the parsed metadata of apps, to check how it is displayed.
@@ -1,4 +1,7 @@
-use std::hash::{Hash, Hasher};
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
 
 /// Normalize app IDs
 fn normalize_id(id_raw: &str) -> &str {
@@ -47,3 +50,10 @@ impl Hash for AppId {
         self.normalized().hash(state);
     }
 }
+
+// Display using the normalized ID
+impl fmt::Display for AppId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.normalized())
+    }
+}
@@ -1,30 +1,68 @@
 use appstream::{
-    enums::{Bundle, Icon, ImageKind, Launchable},
+    enums::{
+        Bundle, ComponentKind, ContentAttribute, ContentState, Icon, ImageKind, Launchable,
+        ProjectUrl, ReleaseKind,
+    },
     xmltree, Component,
 };
-use std::{error::Error, fmt::Write};
+use std::{collections::BTreeMap, error::Error, fmt::Write};
 
-fn get_translatable<'a>(translatable: &'a appstream::TranslatableString, locale: &str) -> &'a str {
-    match translatable.get_for_locale(locale) {
+// Custom metadata key set by repositories that verify the publisher of an app
+pub const CUSTOM_VERIFIED: &'static str = "flathub::verification::verified";
+// Flatpak branches that contain pre-release versions of apps
+pub const PRERELEASE_BRANCHES: &'static [&'static str] = &["beta", "devel", "master", "nightly"];
+
+/// Locales to try in order, like pt-BR, pt_BR, then pt. The default (C) locale is tried last by callers
+pub fn locale_fallbacks(locale: &str) -> Vec<String> {
+    // Strip encoding and modifier, like .UTF-8 or @euro
+    let locale = locale
+        .split(|c| c == '.' || c == '@')
+        .next()
+        .unwrap_or(locale);
+    let mut locales = Vec::with_capacity(3);
+    let mut push = |x: String| {
+        if !x.is_empty() && !locales.contains(&x) {
+            locales.push(x);
+        }
+    };
+    push(locale.to_string());
+    // Appstream uses underscores, while system locales may use hyphens
+    push(locale.replace('-', "_"));
+    push(locale.replace('_', "-"));
+    if let Some(language) = locale.split(|c| c == '-' || c == '_').next() {
+        push(language.to_string());
+    }
+    locales
+}
+
+fn get_translatable<'a>(
+    translatable: &'a appstream::TranslatableString,
+    locales: &[String],
+) -> &'a str {
+    for locale in locales.iter() {
+        if let Some(some) = translatable.get_for_locale(locale) {
+            return some.as_str();
+        }
+    }
+    match translatable.get_default() {
         Some(some) => some.as_str(),
-        None => match translatable.get_default() {
-            Some(some) => some.as_str(),
-            None => "",
-        },
+        None => "",
     }
 }
 
 //TODO: handle p tags with xml:lang
 fn get_markup_translatable<'a>(
     translatable: &'a appstream::MarkupTranslatableString,
-    locale: &str,
+    locales: &[String],
 ) -> &'a str {
-    match translatable.get_for_locale(locale) {
+    for locale in locales.iter() {
+        if let Some(some) = translatable.get_for_locale(locale) {
+            return some.as_str();
+        }
+    }
+    match translatable.get_default() {
         Some(some) => some.as_str(),
-        None => match translatable.get_default() {
-            Some(some) => some.as_str(),
-            None => "",
-        },
+        None => "",
     }
 }
 
@@ -86,7 +124,7 @@ fn write_node(
     Ok(())
 }
 
-fn convert_markup(markup: &str) -> Result<String, Box<dyn Error>> {
+pub fn convert_markup(markup: &str) -> Result<String, Box<dyn Error>> {
     let mut s = String::new();
     for node in xmltree::Element::parse_all(markup.as_bytes())? {
         write_node(&mut s, &node, 0)?;
@@ -117,10 +155,320 @@ pub struct AppRelease {
 pub struct AppScreenshot {
     pub caption: String,
     pub url: String,
+    pub theme_opt: Option<AppScreenshotTheme>,
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppScreenshotTheme {
+    Dark,
+    Light,
+}
+
+impl AppScreenshotTheme {
+    /// Parse from the color scheme part of a screenshot environment, like gnome:dark
+    pub fn from_environment(environment: &str) -> Option<Self> {
+        match environment.split_once(':')?.1 {
+            "dark" => Some(Self::Dark),
+            "light" => Some(Self::Light),
+            _ => None,
+        }
+    }
+}
+
+/// Supported appstream component kinds
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppKind {
+    #[default]
+    App,
+    Addon,
+    Font,
+}
+
+impl AppKind {
+    pub fn all() -> &'static [Self] {
+        &[Self::App, Self::Addon, Self::Font]
+    }
+
+    pub fn from_component_kind(kind: &ComponentKind) -> Option<Self> {
+        match kind {
+            ComponentKind::DesktopApplication => Some(Self::App),
+            ComponentKind::Addon => Some(Self::Addon),
+            ComponentKind::Font => Some(Self::Font),
+            _ => None,
+        }
+    }
+}
+
+// Replaced ProjectUrl due to skip_field not supported in bitcode
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppUrl {
+    BugTracker(String),
+    Donation(String),
+    Help(String),
+    Homepage(String),
+}
+
+// Replaced ContentState due to skip_field not supported in bitcode
+#[derive(
+    Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd, bitcode::Decode, bitcode::Encode,
+)]
+pub enum AppContentLevel {
+    None,
+    Mild,
+    Moderate,
+    Intense,
+}
+
+impl From<&ContentState> for AppContentLevel {
+    fn from(state: &ContentState) -> Self {
+        match state {
+            ContentState::None => Self::None,
+            ContentState::Mild => Self::Mild,
+            ContentState::Moderate => Self::Moderate,
+            ContentState::Intense => Self::Intense,
+        }
+    }
+}
+
+// Replaced ContentRating due to skip_field not supported in bitcode
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub struct AppContentRating {
+    /// OARS attribute IDs and their levels
+    pub attributes: Vec<(String, AppContentLevel)>,
+}
+
+impl AppContentRating {
+    /// Minimum age for an OARS attribute at a given level, using the Common Sense Media mapping
+    /// From https://github.com/ximion/appstream/blob/main/src/as-content-rating.c
+    fn attribute_age(id: &str, level: AppContentLevel) -> u32 {
+        let ages: [u32; 4] = match id {
+            "violence-cartoon" => [0, 3, 4, 6],
+            "violence-fantasy" => [0, 3, 7, 8],
+            "violence-realistic" => [0, 4, 9, 14],
+            "violence-bloodshed" => [0, 9, 11, 18],
+            "violence-sexual" => [0, 18, 18, 18],
+            "violence-desecration" => [0, 3, 7, 13],
+            "violence-slavery" => [0, 13, 15, 18],
+            "violence-worship" => [0, 13, 15, 18],
+            "drugs-alcohol" => [0, 11, 13, 16],
+            "drugs-narcotics" => [0, 12, 14, 17],
+            "drugs-tobacco" => [0, 10, 13, 13],
+            "sex-nudity" => [0, 12, 14, 14],
+            "sex-themes" => [0, 13, 14, 15],
+            "sex-homosexuality" => [0, 13, 14, 15],
+            "sex-prostitution" => [0, 12, 14, 18],
+            "sex-adultery" => [0, 8, 10, 18],
+            "sex-appearance" => [0, 10, 10, 15],
+            "language-profanity" => [0, 8, 11, 14],
+            "language-humor" => [0, 3, 8, 14],
+            "language-discrimination" => [0, 9, 10, 11],
+            "money-advertising" => [0, 0, 0, 0],
+            "money-purchasing" => [0, 12, 14, 18],
+            "money-gambling" => [0, 0, 13, 13],
+            "social-chat" => [0, 4, 10, 13],
+            "social-info" => [0, 0, 13, 13],
+            "social-audio" => [0, 15, 15, 15],
+            "social-location" => [0, 13, 13, 13],
+            "social-contacts" => [0, 12, 12, 12],
+            _ => {
+                log::debug!("unknown OARS attribute {:?}", id);
+                [0, 0, 0, 0]
+            }
+        };
+        ages[level as usize]
+    }
+
+    /// Minimum age required by all attributes
+    pub fn age(&self) -> u32 {
+        self.attributes
+            .iter()
+            .map(|(id, level)| Self::attribute_age(id, *level))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// OARS categories (such as violence or language) with content above the none level
+    pub fn categories(&self) -> Vec<&str> {
+        let mut categories = Vec::new();
+        for (id, level) in self.attributes.iter() {
+            if *level == AppContentLevel::None {
+                continue;
+            }
+            let category = id.split('-').next().unwrap_or(id);
+            if !categories.contains(&category) {
+                categories.push(category);
+            }
+        }
+        categories
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppRelationKind {
+    Recommends,
+    Requires,
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppRelationItem {
+    /// Minimum display length in logical pixels
+    DisplayLength(u32),
+    /// Minimum memory in MiB
+    Memory(u64),
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub struct AppRelation {
+    pub kind: AppRelationKind,
+    pub item: AppRelationItem,
+}
+
+impl AppRelation {
+    /// Parse from relation kind and item name and value, like requires, memory, and 2048
+    pub fn parse(kind: &str, name: &str, value: &str, compare_opt: Option<&str>) -> Option<Self> {
+        let kind = match kind {
+            "recommends" => AppRelationKind::Recommends,
+            "requires" => AppRelationKind::Requires,
+            _ => return None,
+        };
+        //TODO: support other comparisons
+        if !matches!(compare_opt, None | Some("ge")) {
+            return None;
+        }
+        let item = match name {
+            "display_length" => AppRelationItem::DisplayLength(match value.trim() {
+                // From https://www.freedesktop.org/software/appstream/docs/chap-Metadata.html#tag-relations-display_length
+                "xsmall" => 360,
+                "small" => 420,
+                "medium" => 760,
+                "large" => 900,
+                "xlarge" => 1200,
+                other => other.parse().ok()?,
+            }),
+            "memory" => AppRelationItem::Memory(value.trim().parse().ok()?),
+            _ => return None,
+        };
+        Some(Self { kind, item })
+    }
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppProvide {
+    Binary(String),
+    Font(String),
+    Library(String),
+    MediaType(String),
+}
+
+impl AppProvide {
+    /// Parse from provided item kind and value, like mediatype and text/plain
+    pub fn parse(kind: &str, value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+        // Plural kinds are used by DEP-11 YAML, mimetype by legacy metadata
+        Some(match kind {
+            "binary" | "binaries" => Self::Binary(value.to_string()),
+            "font" | "fonts" => Self::Font(value.to_string()),
+            "library" | "libraries" => Self::Library(value.to_string()),
+            "mediatype" | "mediatypes" | "mimetype" | "mimetypes" => {
+                Self::MediaType(value.to_string())
+            }
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppAgreementKind {
+    Generic,
+    Eula,
+    Privacy,
+}
+
+/// Agreement from appstream, with sections flattened into text
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub struct AppAgreement {
+    pub kind: AppAgreementKind,
+    pub version_opt: Option<String>,
+    pub text: String,
+}
+
+impl AppAgreement {
+    /// Parse from an agreement kind attribute and its sections as names and paragraphs
+    pub fn parse(
+        kind: Option<&str>,
+        version_opt: Option<String>,
+        sections: Vec<(Option<String>, Vec<String>)>,
+    ) -> Option<Self> {
+        let kind = match kind {
+            Some("eula") => AppAgreementKind::Eula,
+            Some("privacy") => AppAgreementKind::Privacy,
+            _ => AppAgreementKind::Generic,
+        };
+        let mut text = String::new();
+        for (name_opt, paragraphs) in sections {
+            for part in name_opt.into_iter().chain(paragraphs) {
+                let part = part.trim();
+                if part.is_empty() {
+                    continue;
+                }
+                if !text.is_empty() {
+                    text.push_str("\n\n");
+                }
+                text.push_str(part);
+            }
+        }
+        if text.is_empty() {
+            return None;
+        }
+        Some(Self {
+            kind,
+            version_opt,
+            text,
+        })
+    }
+
+    /// If the agreement must be accepted before installing
+    pub fn required(&self) -> bool {
+        self.kind == AppAgreementKind::Eula
+    }
+}
+
+/// Locale the app is translated into, with the percentage of strings translated
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub struct AppLanguage {
+    pub locale: String,
+    pub percentage: u32,
+}
+
+impl AppLanguage {
+    /// Parse from a locale and percentage, which is fully translated if not provided
+    pub fn parse(locale: &str, percentage_opt: Option<u64>) -> Option<Self> {
+        let locale = locale.trim();
+        if locale.is_empty() {
+            return None;
+        }
+        Some(Self {
+            locale: locale.to_string(),
+            percentage: percentage_opt.map_or(100, |x| x.min(100) as u32),
+        })
+    }
+}
+
+/// Ways an app can be started, from appstream launchables
+#[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
+pub enum AppLaunchable {
+    DesktopId(String),
+    Service(String),
+    CockpitManifest(String),
+    Url(String),
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, bitcode::Decode, bitcode::Encode)]
 pub struct AppInfo {
+    pub kind: AppKind,
     pub source_id: String,
     pub source_name: String,
     pub origin_opt: Option<String>,
@@ -130,11 +478,32 @@ pub struct AppInfo {
     pub description: String,
     pub pkgnames: Vec<String>,
     pub categories: Vec<String>,
-    pub desktop_ids: Vec<String>,
+    /// Search keywords for the current locale
+    pub keywords: Vec<String>,
+    pub launchables: Vec<AppLaunchable>,
     pub flatpak_refs: Vec<String>,
+    /// Runtime used by a flatpak app, in the form NAME/ARCH/BRANCH
+    pub flatpak_runtime_opt: Option<String>,
     pub icons: Vec<AppIcon>,
     pub releases: Vec<AppRelease>,
     pub screenshots: Vec<AppScreenshot>,
+    pub urls: Vec<AppUrl>,
+    pub content_rating: Option<AppContentRating>,
+    pub relations: Vec<AppRelation>,
+    pub provides: Vec<AppProvide>,
+    /// Agreements like license terms and privacy policies
+    pub agreements: Vec<AppAgreement>,
+    pub languages: Vec<AppLanguage>,
+    /// Component IDs of optional apps that are suggested to install along with this one
+    pub suggests: Vec<String>,
+    /// End-of-life reason, which may be empty, if the app is no longer maintained
+    pub eol_opt: Option<String>,
+    /// ID of the app replacing this end-of-life app
+    pub eol_rebase_opt: Option<String>,
+    /// Custom key and value pairs, used by integrations like flathub verification
+    pub custom: BTreeMap<String, String>,
+    /// If this is a beta or development version of the app
+    pub prerelease: bool,
     pub monthly_downloads: u64,
 }
 
@@ -144,22 +513,23 @@ impl AppInfo {
         source_name: &str,
         origin_opt: Option<&str>,
         component: Component,
-        locale: &str,
+        locales: &[String],
         monthly_downloads: u64,
+        screenshot_environments: &[Option<String>],
     ) -> Self {
-        let name = get_translatable(&component.name, locale);
+        let name = get_translatable(&component.name, locales);
         let summary = component
             .summary
             .as_ref()
-            .map_or("", |x| get_translatable(x, locale));
+            .map_or("", |x| get_translatable(x, locales));
         let developer_name = component
             .developer_name
             .as_ref()
-            .map_or("", |x| get_translatable(x, locale));
+            .map_or("", |x| get_translatable(x, locales));
         let description_markup = component
             .description
             .as_ref()
-            .map_or("", |x| get_markup_translatable(x, locale));
+            .map_or("", |x| get_markup_translatable(x, locales));
         let description = match convert_markup(description_markup) {
             Ok(ok) => ok,
             Err(err) => {
@@ -177,22 +547,43 @@ impl AppInfo {
             .into_iter()
             .map(|category| category.to_string())
             .collect();
-        let desktop_ids = component
+        let keywords = component
+            .keywords
+            .as_ref()
+            .and_then(|keywords| {
+                locales
+                    .iter()
+                    .find_map(|locale| keywords.get_for_locale(locale))
+                    .or_else(|| keywords.get_default())
+            })
+            .cloned()
+            .unwrap_or_default();
+        let launchables = component
             .launchables
             .into_iter()
             .filter_map(|launchable| match launchable {
-                Launchable::DesktopId(desktop_id) => Some(desktop_id),
-                _ => None,
-            })
-            .collect();
-        let flatpak_refs = component
-            .bundles
-            .into_iter()
-            .filter_map(|bundle| match bundle {
-                Bundle::Flatpak { reference, .. } => Some(reference),
+                Launchable::DesktopId(desktop_id) => Some(AppLaunchable::DesktopId(desktop_id)),
+                Launchable::Service(service) => Some(AppLaunchable::Service(service)),
+                Launchable::CockpitManifest(manifest) => {
+                    Some(AppLaunchable::CockpitManifest(manifest))
+                }
+                Launchable::Url(url) => Some(AppLaunchable::Url(url.to_string())),
                 _ => None,
             })
             .collect();
+        let mut flatpak_refs = Vec::new();
+        let mut flatpak_runtime_opt = None;
+        for bundle in component.bundles {
+            if let Bundle::Flatpak {
+                runtime, reference, ..
+            } = bundle
+            {
+                flatpak_refs.push(reference);
+                if flatpak_runtime_opt.is_none() {
+                    flatpak_runtime_opt = runtime;
+                }
+            }
+        }
         let icons = component
             .icons
             .into_iter()
@@ -228,12 +619,24 @@ impl AppInfo {
                 )),
             })
             .collect();
+        // Apps with only development releases, or from a beta branch, are pre-releases
+        let prerelease = (!component.releases.is_empty()
+            && component
+                .releases
+                .iter()
+                .all(|release| release.kind == ReleaseKind::Development))
+            || flatpak_refs.iter().any(|reference| {
+                reference
+                    .split('/')
+                    .nth(3)
+                    .map_or(false, |branch| PRERELEASE_BRANCHES.contains(&branch))
+            });
         let releases = component
             .releases
             .into_iter()
             .filter_map(|release| {
                 let description = release.description.as_ref().and_then(|x| {
-                    match convert_markup(get_markup_translatable(x, locale)) {
+                    match convert_markup(get_markup_translatable(x, locales)) {
                         Ok(ok) => Some(ok),
                         Err(err) => {
                             //TODO: better handling of release description
@@ -256,8 +659,22 @@ impl AppInfo {
                 })
             })
             .collect();
+        // The default screenshot is shown first, the rest keep their declared order
+        let mut component_screenshots: Vec<_> = component
+            .screenshots
+            .into_iter()
+            .enumerate()
+            .map(|(screenshot_i, screenshot)| {
+                let environment_opt = screenshot_environments
+                    .get(screenshot_i)
+                    .and_then(|x| x.as_deref());
+                (screenshot, environment_opt)
+            })
+            .collect();
+        component_screenshots.sort_by_key(|(screenshot, _)| !screenshot.is_default);
         let mut screenshots = Vec::new();
-        for screenshot in component.screenshots.into_iter() {
+        for (screenshot, environment_opt) in component_screenshots {
+            let theme_opt = environment_opt.and_then(AppScreenshotTheme::from_environment);
             //TODO: better handle multiple images per screenshot
             for image in screenshot.images.into_iter() {
                 if matches!(image.kind, ImageKind::Source) {
@@ -265,16 +682,81 @@ impl AppInfo {
                         caption: screenshot
                             .caption
                             .as_ref()
-                            .map_or("", |x| get_translatable(x, locale))
+                            .map_or("", |x| get_translatable(x, locales))
                             .to_string(),
                         url: image.url.into(),
+                        theme_opt,
                     });
                     break;
                 }
             }
         }
+        let urls = component
+            .urls
+            .into_iter()
+            .filter_map(|url| match url {
+                ProjectUrl::BugTracker(url) => Some(AppUrl::BugTracker(url.into())),
+                ProjectUrl::Donation(url) => Some(AppUrl::Donation(url.into())),
+                ProjectUrl::Help(url) => Some(AppUrl::Help(url.into())),
+                ProjectUrl::Homepage(url) => Some(AppUrl::Homepage(url.into())),
+                _ => None,
+            })
+            .collect();
+        let content_rating = component
+            .content_rating
+            .map(|content_rating| AppContentRating {
+                attributes: content_rating
+                    .attributes
+                    .iter()
+                    .map(|attribute| {
+                        let (id, state) = match attribute {
+                            ContentAttribute::ViolenceCartoon(state) => ("violence-cartoon", state),
+                            ContentAttribute::ViolenceFantasy(state) => ("violence-fantasy", state),
+                            ContentAttribute::ViolenceRealistic(state) => {
+                                ("violence-realistic", state)
+                            }
+                            ContentAttribute::ViolenceBloodshed(state) => {
+                                ("violence-bloodshed", state)
+                            }
+                            ContentAttribute::ViolenceSexual(state) => ("violence-sexual", state),
+                            ContentAttribute::ViolenceDesecration(state) => {
+                                ("violence-desecration", state)
+                            }
+                            ContentAttribute::ViolenceSlavery(state) => ("violence-slavery", state),
+                            ContentAttribute::ViolenceWorship(state) => ("violence-worship", state),
+                            ContentAttribute::DrugsAlcohol(state) => ("drugs-alcohol", state),
+                            ContentAttribute::DrugsNarcotics(state) => ("drugs-narcotics", state),
+                            ContentAttribute::DrugsTobacco(state) => ("drugs-tobacco", state),
+                            ContentAttribute::SexNudity(state) => ("sex-nudity", state),
+                            ContentAttribute::SexThemes(state) => ("sex-themes", state),
+                            ContentAttribute::SexHomosexuality(state) => {
+                                ("sex-homosexuality", state)
+                            }
+                            ContentAttribute::SexProstitution(state) => ("sex-prostitution", state),
+                            ContentAttribute::SexAdultery(state) => ("sex-adultery", state),
+                            ContentAttribute::SexAppearance(state) => ("sex-appearance", state),
+                            ContentAttribute::LanguageProfanity(state) => {
+                                ("language-profanity", state)
+                            }
+                            ContentAttribute::LanguageHumor(state) => ("language-humor", state),
+                            ContentAttribute::LanguageDiscrimination(state) => {
+                                ("language-discrimination", state)
+                            }
+                            ContentAttribute::SocialChat(state) => ("social-chat", state),
+                            ContentAttribute::SocialInfo(state) => ("social-info", state),
+                            ContentAttribute::SocialAudio(state) => ("social-audio", state),
+                            ContentAttribute::SocialLocation(state) => ("social-location", state),
+                            ContentAttribute::SocialContacts(state) => ("social-contacts", state),
+                            ContentAttribute::MoneyPurchasing(state) => ("money-purchasing", state),
+                            ContentAttribute::MoneyGambling(state) => ("money-gambling", state),
+                        };
+                        (id.to_string(), AppContentLevel::from(state))
+                    })
+                    .collect(),
+            });
 
         Self {
+            kind: AppKind::from_component_kind(&component.kind).unwrap_or_default(),
             source_id: source_id.to_string(),
             source_name: source_name.to_string(),
             origin_opt: origin_opt.map(|x| x.to_string()),
@@ -284,12 +766,118 @@ impl AppInfo {
             description,
             pkgnames: component.pkgname.map_or(Vec::new(), |x| vec![x]),
             categories,
-            desktop_ids,
+            keywords,
+            launchables,
             flatpak_refs,
+            flatpak_runtime_opt,
             icons,
             releases,
             screenshots,
+            urls,
+            content_rating,
+            relations: Vec::new(),
+            provides: Vec::new(),
+            agreements: Vec::new(),
+            languages: Vec::new(),
+            suggests: Vec::new(),
+            eol_opt: None,
+            eol_rebase_opt: None,
+            custom: BTreeMap::new(),
+            prerelease,
             monthly_downloads,
         }
     }
+
+    /// Themed icon name for when the app has no icon, based on its primary category
+    pub fn fallback_icon_name(&self) -> &'static str {
+        // From https://specifications.freedesktop.org/icon-naming-spec/latest/ar01s04.html
+        for category in self.categories.iter() {
+            match category.as_str() {
+                "AudioVideo" | "Audio" | "Video" => return "applications-multimedia",
+                "Development" => return "applications-development",
+                "Education" => return "applications-education",
+                "Game" => return "applications-games",
+                "Graphics" => return "applications-graphics",
+                "Network" => return "applications-internet",
+                "Office" => return "applications-office",
+                "Science" => return "applications-science",
+                "Settings" => return "preferences-desktop",
+                "System" => return "applications-system",
+                "Utility" => return "applications-utilities",
+                _ => {}
+            }
+        }
+        "package-x-generic"
+    }
+
+    /// Translation percentage for a locale, if the app is translated into it
+    pub fn language_percentage(&self, locale: &str) -> Option<u32> {
+        locale_fallbacks(locale).iter().find_map(|locale| {
+            self.languages
+                .iter()
+                .find(|language| &language.locale == locale)
+                .map(|language| language.percentage)
+        })
+    }
+
+    /// Launchable used to open the app, preferring desktop entries
+    pub fn launchable(&self) -> Option<&AppLaunchable> {
+        self.launchables
+            .iter()
+            .find(|x| matches!(x, AppLaunchable::DesktopId(_)))
+            .or_else(|| self.launchables.first())
+    }
+
+    /// If the source has verified that the developer publishes this app
+    pub fn verified(&self) -> bool {
+        self.custom.get(CUSTOM_VERIFIED).map(|x| x.as_str()) == Some("true")
+    }
+
+    /// URL for donating to the developers, if provided
+    pub fn donation_url(&self) -> Option<&str> {
+        self.urls.iter().find_map(|url| match url {
+            AppUrl::Donation(url) => Some(url.as_str()),
+            _ => None,
+        })
+    }
+
+    /// URL for reporting problems, from the bug tracker or the flathub packaging repository
+    pub fn report_url(&self) -> Option<String> {
+        for url in self.urls.iter() {
+            if let AppUrl::BugTracker(url) = url {
+                return Some(url.clone());
+            }
+        }
+        if self.source_id == "flathub" {
+            // Flatpak refs are in the form app/ID/ARCH/BRANCH
+            let id = self.flatpak_refs.first()?.split('/').nth(1)?;
+            return Some(format!("https://github.com/flathub/{}/issues", id));
+        }
+        None
+    }
+
+    /// Screenshots matching the theme with their indexes, falling back to untagged screenshots
+    pub fn themed_screenshots(&self, is_dark: bool) -> Vec<(usize, &AppScreenshot)> {
+        let theme = if is_dark {
+            AppScreenshotTheme::Dark
+        } else {
+            AppScreenshotTheme::Light
+        };
+        let screenshots = self.screenshots.iter().enumerate();
+        let themed: Vec<_> = screenshots
+            .clone()
+            .filter(|(_, screenshot)| screenshot.theme_opt == Some(theme))
+            .collect();
+        if !themed.is_empty() {
+            return themed;
+        }
+        let untagged: Vec<_> = screenshots
+            .clone()
+            .filter(|(_, screenshot)| screenshot.theme_opt.is_none())
+            .collect();
+        if !untagged.is_empty() {
+            return untagged;
+        }
+        screenshots.collect()
+    }
 }
@@ -1,6 +1,6 @@
 use appstream::{
-    enums::{ComponentKind, Icon, ImageKind, Launchable, ReleaseKind, ReleaseUrgency},
-    url::Url,
+    enums::{Icon, ImageKind, Launchable, ReleaseKind, ReleaseUrgency},
+    url::{self, Url},
     xmltree, Component, Image, MarkupTranslatableString, ParseError, Release, Screenshot,
 };
 use cosmic::widget;
@@ -18,11 +18,52 @@ use std::{
     time::{Instant, SystemTime},
 };
 
-use crate::{stats, AppIcon, AppId, AppInfo};
+use crate::{
+    app_info::{convert_markup, locale_fallbacks},
+    icon_cache::{remote_icon_path, remote_icon_request},
+    stats, AppAgreement, AppIcon, AppId, AppInfo, AppKind, AppLanguage, AppProvide, AppRelation,
+};
 
 const PREFIXES: &'static [&'static str] = &["/usr/share", "/var/lib", "/var/cache"];
 const CATALOGS: &'static [&'static str] = &["swcatalog", "app-info"];
 
+/// Resolve a media URL against the media base URL of its catalog, if it is relative
+fn media_url(media_base_url_opt: Option<&str>, url_str: &str) -> Result<Url, url::ParseError> {
+    let media_base_url = match media_base_url_opt {
+        Some(some) => some,
+        None => return Url::parse(url_str),
+    };
+    match Url::parse(url_str) {
+        Err(url::ParseError::RelativeUrlWithoutBase) => {
+            // Paths are appended to the base URL, even if they start with a slash
+            let base = Url::parse(&format!("{}/", media_base_url.trim_end_matches('/')))?;
+            base.join(url_str.trim_start_matches('/'))
+        }
+        result => result,
+    }
+}
+
+/// Resolve relative screenshot and remote icon URLs in a catalog component
+fn resolve_media_urls(e: &mut xmltree::Element, media_base_url: &str) {
+    let is_media = match &*e.name {
+        "image" | "video" => true,
+        "icon" => e.attributes.get("type").map_or(false, |x| x == "remote"),
+        _ => false,
+    };
+    for node in e.children.iter_mut() {
+        match node {
+            xmltree::XMLNode::Element(child) => resolve_media_urls(child, media_base_url),
+            xmltree::XMLNode::Text(text) if is_media => {
+                match media_url(Some(media_base_url), text.trim()) {
+                    Ok(url) => *text = url.to_string(),
+                    Err(err) => log::warn!("failed to resolve media URL {:?}: {}", text, err),
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 #[derive(
     Clone,
     Copy,
@@ -37,6 +78,8 @@ const CATALOGS: &'static [&'static str] = &["swcatalog", "app-info"];
 pub struct AppstreamCacheTag {
     /// When the file was last modified in seconds from the unix epoch
     pub modified: u64,
+    /// Nanoseconds part of when the file was last modified, to detect changes within a second
+    pub modified_nanos: u32,
     /// Size of the file in bytes
     pub size: u64,
 }
@@ -54,6 +97,18 @@ pub struct AppstreamCache {
 }
 
 impl AppstreamCache {
+    /// Age in seconds of the oldest appstream data source, or None if there are no sources
+    pub fn age_opt(&self) -> Option<u64> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        self.path_tags
+            .values()
+            .map(|tag| now.saturating_sub(tag.modified))
+            .max()
+    }
+
     /// Get cache for specified appstream data sources
     pub fn new(
         source_id: String,
@@ -67,6 +122,12 @@ impl AppstreamCache {
         cache.source_name = source_name;
         cache.icons_paths = icons_paths;
         cache.locale = locale.to_string();
+        log::info!(
+            "source {:?} using locale {:?} with fallbacks {:?}",
+            cache.source_id,
+            locale,
+            locale_fallbacks(locale)
+        );
 
         for path in paths.iter() {
             let canonical = match fs::canonicalize(path) {
@@ -91,9 +152,9 @@ impl AppstreamCache {
                 }
             };
 
-            let modified = match metadata.modified() {
+            let (modified, modified_nanos) = match metadata.modified() {
                 Ok(system_time) => match system_time.duration_since(SystemTime::UNIX_EPOCH) {
-                    Ok(duration) => duration.as_secs(),
+                    Ok(duration) => (duration.as_secs(), duration.subsec_nanos()),
                     Err(err) => {
                         log::error!(
                             "failed to convert modified time of {:?} to unix epoch: {}",
@@ -111,9 +172,14 @@ impl AppstreamCache {
 
             let size = metadata.len();
 
-            cache
-                .path_tags
-                .insert(canonical, AppstreamCacheTag { modified, size });
+            cache.path_tags.insert(
+                canonical,
+                AppstreamCacheTag {
+                    modified,
+                    modified_nanos,
+                    size,
+                },
+            );
         }
 
         cache
@@ -189,7 +255,7 @@ impl AppstreamCache {
 
     /// Versioned filename of cache
     fn cache_filename() -> &'static str {
-        "appstream_cache-v0-1.bitcode-v0-6"
+        "appstream_cache-v0-23.bitcode-v0-6"
     }
 
     /// Remove all files from cache not matching filename
@@ -491,6 +557,8 @@ impl AppstreamCache {
     pub fn icon(&self, info: &AppInfo) -> widget::icon::Handle {
         let mut icon_opt = None;
         let mut cached_size = 0;
+        let mut stock_opt = None;
+        let mut remote_opt = None;
         for info_icon in info.icons.iter() {
             //TODO: support other types of icons
             match info_icon {
@@ -515,10 +583,27 @@ impl AppstreamCache {
                     if let Some(icon_path) = widget::icon::from_name(stock.clone()).size(128).path()
                     {
                         icon_opt = Some(widget::icon::from_path(icon_path));
+                    } else if stock_opt.is_none() {
+                        stock_opt = Some(stock);
                     }
                 }
-                AppIcon::Remote(_url, _width, _height, _scale) => {
-                    //TODO
+                AppIcon::Remote(url, width, height, _scale) => {
+                    let size = cmp::min(width.unwrap_or(0), height.unwrap_or(0));
+                    if size < cached_size {
+                        // Skip if size is less than cached size
+                        continue;
+                    }
+                    match remote_icon_path(url) {
+                        Some(icon_path) if icon_path.is_file() => {
+                            icon_opt = Some(widget::icon::from_path(icon_path));
+                            cached_size = size;
+                        }
+                        _ => {
+                            if remote_opt.is_none() {
+                                remote_opt = Some(url);
+                            }
+                        }
+                    }
                 }
                 AppIcon::Local(path, width, height, _scale) => {
                     let size = cmp::min(width.unwrap_or(0), height.unwrap_or(0));
@@ -535,8 +620,16 @@ impl AppstreamCache {
             }
         }
         icon_opt.unwrap_or_else(|| {
+            if let Some(url) = remote_opt {
+                // Fetched in the background, results are updated when done
+                remote_icon_request(url);
+            }
+            // Stock icons not found by path may still be resolved by the icon theme
+            if let Some(stock) = stock_opt {
+                return widget::icon::from_name(stock.clone()).size(128).handle();
+            }
             log::debug!("failed to get icon from {:?}", info.icons);
-            widget::icon::from_name("package-x-generic")
+            widget::icon::from_name(info.fallback_icon_name())
                 .size(128)
                 .handle()
         })
@@ -549,8 +642,17 @@ impl AppstreamCache {
     ) -> Result<Vec<(AppId, Arc<AppInfo>)>, Box<dyn Error>> {
         let start = Instant::now();
         let path = path.as_ref();
+        let locales = locale_fallbacks(&self.locale);
         //TODO: just running this and not saving the results makes a huge memory leak!
-        let e = xmltree::Element::parse(reader)?;
+        let mut e = xmltree::Element::parse(reader)?;
+        // Relative media URLs are resolved first, as they fail to parse
+        if let Some(media_base_url) = e.attributes.get("media_baseurl").cloned() {
+            e.children.par_iter_mut().for_each(|node| {
+                if let xmltree::XMLNode::Element(component) = node {
+                    resolve_media_urls(component, &media_base_url);
+                }
+            });
+        }
         let _version = e
             .attributes
             .get("version")
@@ -565,25 +667,195 @@ impl AppstreamCache {
                     if &*e.name == "component" {
                         match Component::try_from(e) {
                             Ok(component) => {
-                                if component.kind != ComponentKind::DesktopApplication {
-                                    // Skip anything that is not a desktop application
-                                    //TODO: should we allow more components?
+                                if AppKind::from_component_kind(&component.kind).is_none() {
+                                    // Skip anything that is not an app, add-on, or font
                                     return None;
                                 }
 
+                                //TODO: move to appstream crate
+                                let screenshot_environments: Vec<_> = e
+                                    .get_child("screenshots")
+                                    .map(|screenshots| {
+                                        screenshots
+                                            .children
+                                            .iter()
+                                            .filter_map(|node| match node {
+                                                xmltree::XMLNode::Element(screenshot)
+                                                    if &*screenshot.name == "screenshot" =>
+                                                {
+                                                    Some(
+                                                        screenshot
+                                                            .attributes
+                                                            .get("environment")
+                                                            .cloned(),
+                                                    )
+                                                }
+                                                _ => None,
+                                            })
+                                            .collect()
+                                    })
+                                    .unwrap_or_default();
+
+                                //TODO: move to appstream crate
+                                let mut relations = Vec::new();
+                                for kind in ["requires", "recommends"] {
+                                    if let Some(relation) = e.get_child(kind) {
+                                        for node in relation.children.iter() {
+                                            if let xmltree::XMLNode::Element(item) = node {
+                                                let value = item.get_text().unwrap_or_default();
+                                                relations.extend(AppRelation::parse(
+                                                    kind,
+                                                    &item.name,
+                                                    &value,
+                                                    item.attributes
+                                                        .get("compare")
+                                                        .map(|x| x.as_str()),
+                                                ));
+                                            }
+                                        }
+                                    }
+                                }
+
+                                //TODO: move to appstream crate
+                                let mut provides = Vec::new();
+                                for (parent, child_opt) in
+                                    [("provides", None), ("mimetypes", Some("mimetype"))]
+                                {
+                                    if let Some(parent) = e.get_child(parent) {
+                                        for node in parent.children.iter() {
+                                            if let xmltree::XMLNode::Element(item) = node {
+                                                if child_opt.map_or(false, |x| x != item.name) {
+                                                    continue;
+                                                }
+                                                let value = item.get_text().unwrap_or_default();
+                                                provides
+                                                    .extend(AppProvide::parse(&item.name, &value));
+                                            }
+                                        }
+                                    }
+                                }
+
+                                //TODO: move to appstream crate
+                                let mut suggests = Vec::new();
+                                for node in e.children.iter() {
+                                    if let xmltree::XMLNode::Element(suggest) = node {
+                                        if suggest.name != "suggests" {
+                                            continue;
+                                        }
+                                        for node in suggest.children.iter() {
+                                            if let xmltree::XMLNode::Element(item) = node {
+                                                if item.name == "id" {
+                                                    if let Some(value) = item.get_text() {
+                                                        suggests.push(value.trim().to_string());
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+
+                                //TODO: move to appstream crate
+                                let mut agreements = Vec::new();
+                                for node in e.children.iter() {
+                                    let agreement = match node {
+                                        xmltree::XMLNode::Element(x) if x.name == "agreement" => x,
+                                        _ => continue,
+                                    };
+                                    let mut sections = Vec::new();
+                                    for node in agreement.children.iter() {
+                                        let section = match node {
+                                            xmltree::XMLNode::Element(x)
+                                                if x.name == "agreement_section" =>
+                                            {
+                                                x
+                                            }
+                                            _ => continue,
+                                        };
+                                        //TODO: translated names and descriptions
+                                        let name_opt = section
+                                            .get_child("name")
+                                            .and_then(|x| x.get_text())
+                                            .map(|x| x.to_string());
+                                        let mut paragraphs = Vec::new();
+                                        if let Some(description) = section.get_child("description")
+                                        {
+                                            for node in description.children.iter() {
+                                                if let xmltree::XMLNode::Element(item) = node {
+                                                    if let Some(text) = item.get_text() {
+                                                        paragraphs.push(text.to_string());
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        sections.push((name_opt, paragraphs));
+                                    }
+                                    agreements.extend(AppAgreement::parse(
+                                        agreement.attributes.get("type").map(|x| x.as_str()),
+                                        agreement.attributes.get("version_id").cloned(),
+                                        sections,
+                                    ));
+                                }
+
+                                //TODO: move to appstream crate
+                                let mut languages = Vec::new();
+                                if let Some(parent) = e.get_child("languages") {
+                                    for node in parent.children.iter() {
+                                        if let xmltree::XMLNode::Element(lang) = node {
+                                            if lang.name != "lang" {
+                                                continue;
+                                            }
+                                            languages.extend(AppLanguage::parse(
+                                                &lang.get_text().unwrap_or_default(),
+                                                lang.attributes
+                                                    .get("percentage")
+                                                    .and_then(|x| x.parse().ok()),
+                                            ));
+                                        }
+                                    }
+                                }
+
+                                //TODO: move to appstream crate
+                                let mut custom = BTreeMap::new();
+                                for parent in ["custom", "metadata"] {
+                                    let Some(parent) = e.get_child(parent) else {
+                                        continue;
+                                    };
+                                    for node in parent.children.iter() {
+                                        if let xmltree::XMLNode::Element(value) = node {
+                                            if value.name != "value" {
+                                                continue;
+                                            }
+                                            if let Some(key) = value.attributes.get("key") {
+                                                custom.insert(
+                                                    key.clone(),
+                                                    value
+                                                        .get_text()
+                                                        .unwrap_or_default()
+                                                        .to_string(),
+                                                );
+                                            }
+                                        }
+                                    }
+                                }
+
                                 let id = AppId::new(&component.id.0);
                                 let monthly_downloads = stats::monthly_downloads(&id).unwrap_or(0);
-                                return Some((
-                                    id,
-                                    Arc::new(AppInfo::new(
-                                        &self.source_id,
-                                        &self.source_name,
-                                        origin_opt.map(|x| x.as_str()),
-                                        component,
-                                        &self.locale,
-                                        monthly_downloads,
-                                    )),
-                                ));
+                                let mut info = AppInfo::new(
+                                    &self.source_id,
+                                    &self.source_name,
+                                    origin_opt.map(|x| x.as_str()),
+                                    component,
+                                    &locales,
+                                    monthly_downloads,
+                                    &screenshot_environments,
+                                );
+                                info.relations = relations;
+                                info.provides = provides;
+                                info.suggests = suggests;
+                                info.agreements = agreements;
+                                info.languages = languages;
+                                info.custom = custom;
+                                return Some((id, Arc::new(info)));
                             }
                             Err(err) => {
                                 log::error!(
@@ -617,6 +889,7 @@ impl AppstreamCache {
     ) -> Result<Vec<(AppId, Arc<AppInfo>)>, Box<dyn Error>> {
         let start = Instant::now();
         let path = path.as_ref();
+        let locales = locale_fallbacks(&self.locale);
         let mut origin_opt = None;
         let mut media_base_url_opt = None;
         let mut infos = Vec::new();
@@ -635,9 +908,8 @@ impl AppstreamCache {
             } else {
                 match Component::deserialize(&value) {
                     Ok(mut component) => {
-                        if component.kind != ComponentKind::DesktopApplication {
-                            // Skip anything that is not a desktop application
-                            //TODO: should we allow more components?
+                        if AppKind::from_component_kind(&component.kind).is_none() {
+                            // Skip anything that is not an app, add-on, or font
                             continue;
                         }
 
@@ -685,15 +957,55 @@ impl AppstreamCache {
                                             );
                                         }
                                     },
-                                    Some("remote") => {
-                                        // For now we just ignore remote icons
-                                        log::debug!(
-                                            "ignoring remote icons {:?} for {:?} in {:?}",
-                                            icon,
-                                            component.id,
-                                            path
-                                        );
-                                    }
+                                    Some("remote") => match icon.as_sequence() {
+                                        Some(sequence) => {
+                                            for remote in sequence {
+                                                let Some(url_str) = remote["url"].as_str() else {
+                                                    log::warn!(
+                                                        "unsupported remote icon {:?} for {:?} in {:?}",
+                                                        remote,
+                                                        component.id,
+                                                        path
+                                                    );
+                                                    continue;
+                                                };
+                                                match media_url(
+                                                    media_base_url_opt.as_deref(),
+                                                    url_str,
+                                                ) {
+                                                    Ok(url) => {
+                                                        component.icons.push(Icon::Remote {
+                                                            url,
+                                                            width: remote["width"]
+                                                                .as_u64()
+                                                                .and_then(|x| x.try_into().ok()),
+                                                            height: remote["height"]
+                                                                .as_u64()
+                                                                .and_then(|x| x.try_into().ok()),
+                                                            scale: remote["scale"]
+                                                                .as_u64()
+                                                                .and_then(|x| x.try_into().ok()),
+                                                        });
+                                                    }
+                                                    Err(err) => {
+                                                        log::warn!(
+                                                            "failed to parse {:?}: {}",
+                                                            url_str,
+                                                            err
+                                                        );
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        None => {
+                                            log::warn!(
+                                                "unsupported remote icons {:?} for {:?} in {:?}",
+                                                icon,
+                                                component.id,
+                                                path
+                                            );
+                                        }
+                                    },
                                     Some("stock") => match icon.as_str() {
                                         Some(stock) => {
                                             component.icons.push(Icon::Stock(stock.to_string()));
@@ -721,38 +1033,10 @@ impl AppstreamCache {
 
                         if let Some(launchables) = value["Launchable"].as_mapping() {
                             for (key, launchable) in launchables.iter() {
-                                match key.as_str() {
-                                    Some("desktop-id") => match launchable.as_sequence() {
-                                        Some(sequence) => {
-                                            for desktop_id in sequence {
-                                                match desktop_id.as_str() {
-                                                    Some(desktop_id) => {
-                                                        component.launchables.push(
-                                                            Launchable::DesktopId(
-                                                                desktop_id.to_string(),
-                                                            ),
-                                                        );
-                                                    }
-                                                    None => {
-                                                        log::warn!(
-                                                        "unsupported desktop-id launchable {:?} for {:?} in {:?}",
-                                                        desktop_id,
-                                                        component.id,
-                                                        path
-                                                    );
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        None => {
-                                            log::warn!(
-                                                "unsupported desktop-id launchables {:?} for {:?} in {:?}",
-                                                launchable,
-                                                component.id,
-                                                path
-                                            );
-                                        }
-                                    },
+                                let kind = match key.as_str() {
+                                    Some(
+                                        kind @ ("desktop-id" | "service" | "cockpit-manifest"),
+                                    ) => kind,
                                     _ => {
                                         log::warn!(
                                             "unsupported launchable kind {:?} for {:?} in {:?}",
@@ -760,7 +1044,41 @@ impl AppstreamCache {
                                             component.id,
                                             path
                                         );
+                                        continue;
+                                    }
+                                };
+                                let sequence = match launchable.as_sequence() {
+                                    Some(some) => some,
+                                    None => {
+                                        log::warn!(
+                                            "unsupported {} launchables {:?} for {:?} in {:?}",
+                                            kind,
+                                            launchable,
+                                            component.id,
+                                            path
+                                        );
+                                        continue;
                                     }
+                                };
+                                for item in sequence {
+                                    let item = match item.as_str() {
+                                        Some(some) => some.to_string(),
+                                        None => {
+                                            log::warn!(
+                                                "unsupported {} launchable {:?} for {:?} in {:?}",
+                                                kind,
+                                                item,
+                                                component.id,
+                                                path
+                                            );
+                                            continue;
+                                        }
+                                    };
+                                    component.launchables.push(match kind {
+                                        "service" => Launchable::Service(item),
+                                        "cockpit-manifest" => Launchable::CockpitManifest(item),
+                                        _ => Launchable::DesktopId(item),
+                                    });
                                 }
                             }
                         }
@@ -829,22 +1147,23 @@ impl AppstreamCache {
                             }
                         }
 
+                        let mut screenshot_environments = Vec::new();
                         if let Some(screenshots) = value["Screenshots"].as_sequence() {
                             for screenshot_value in screenshots {
                                 if let Some(screenshot) = screenshot_value.as_mapping() {
+                                    screenshot_environments.push(
+                                        screenshot
+                                            .get("environment")
+                                            .and_then(|x| x.as_str())
+                                            .map(|x| x.to_string()),
+                                    );
                                     let mut images = Vec::new();
                                     if let Some(source_image) =
                                         screenshot.get("source-image").and_then(|x| x.as_mapping())
                                     {
-                                        if let Some(path_str) = source_image["url"].as_str() {
-                                            let url_str = match &media_base_url_opt {
-                                                Some(media_base_url) => {
-                                                    //TODO: join using url crate?
-                                                    format!("{media_base_url}/{path_str}")
-                                                }
-                                                None => path_str.to_string(),
-                                            };
-                                            match Url::parse(&url_str) {
+                                        if let Some(url_str) = source_image["url"].as_str() {
+                                            match media_url(media_base_url_opt.as_deref(), url_str)
+                                            {
                                                 Ok(url) => {
                                                     images.push(Image {
                                                         kind: ImageKind::Source,
@@ -867,8 +1186,10 @@ impl AppstreamCache {
                                     //TODO: thumbnails
 
                                     component.screenshots.push(Screenshot {
-                                        //TODO: set is_default
-                                        is_default: false,
+                                        is_default: screenshot
+                                            .get("default")
+                                            .and_then(|x| x.as_bool())
+                                            .unwrap_or(false),
                                         //TODO: caption
                                         caption: None,
                                         images,
@@ -879,19 +1200,163 @@ impl AppstreamCache {
                             }
                         }
 
+                        let mut relations = Vec::new();
+                        for (kind, key) in [("requires", "Requires"), ("recommends", "Recommends")]
+                        {
+                            let items = match value[key].as_sequence() {
+                                Some(some) => some,
+                                None => continue,
+                            };
+                            for item in items.iter().filter_map(|x| x.as_mapping()) {
+                                for (name, item_value) in item.iter() {
+                                    let name = match name.as_str() {
+                                        Some(some) => some,
+                                        None => continue,
+                                    };
+                                    // Items are either plain values or mappings with compare and value
+                                    let (value_opt, compare_opt) = match item_value.as_mapping() {
+                                        Some(mapping) => (
+                                            mapping.get("value"),
+                                            mapping.get("compare").and_then(|x| x.as_str()),
+                                        ),
+                                        None => (Some(item_value), None),
+                                    };
+                                    let value_str = match value_opt {
+                                        Some(serde_yaml::Value::Number(number)) => {
+                                            number.to_string()
+                                        }
+                                        Some(serde_yaml::Value::String(string)) => string.clone(),
+                                        _ => continue,
+                                    };
+                                    relations.extend(AppRelation::parse(
+                                        kind,
+                                        name,
+                                        &value_str,
+                                        compare_opt,
+                                    ));
+                                }
+                            }
+                        }
+
+                        let mut provides = Vec::new();
+                        if let Some(provides_mapping) = value["Provides"].as_mapping() {
+                            for (kind, items) in provides_mapping.iter() {
+                                let kind = match kind.as_str() {
+                                    Some(some) => some,
+                                    None => continue,
+                                };
+                                let items = match items.as_sequence() {
+                                    Some(some) => some,
+                                    None => continue,
+                                };
+                                for item in items {
+                                    // Fonts are mappings with a name
+                                    let item_str = match item.as_str() {
+                                        Some(some) => some,
+                                        None => match item["name"].as_str() {
+                                            Some(some) => some,
+                                            None => continue,
+                                        },
+                                    };
+                                    provides.extend(AppProvide::parse(kind, item_str));
+                                }
+                            }
+                        }
+                        if let Some(mimetypes) = value["MimeTypes"].as_sequence() {
+                            for mimetype in mimetypes.iter().filter_map(|x| x.as_str()) {
+                                provides.extend(AppProvide::parse("mimetype", mimetype));
+                            }
+                        }
+
+                        let mut suggests = Vec::new();
+                        if let Some(items) = value["Suggests"].as_sequence() {
+                            for item in items {
+                                if let Some(ids) = item["ids"].as_sequence() {
+                                    suggests.extend(
+                                        ids.iter().filter_map(|x| x.as_str()).map(str::to_string),
+                                    );
+                                }
+                            }
+                        }
+
+                        let mut agreements = Vec::new();
+                        if let Some(items) = value["Agreements"].as_sequence() {
+                            for item in items {
+                                let mut sections = Vec::new();
+                                if let Some(section_items) = item["sections"].as_sequence() {
+                                    for section in section_items {
+                                        //TODO: translated names and descriptions
+                                        let name_opt =
+                                            section["name"]["C"].as_str().map(|x| x.to_string());
+                                        let mut paragraphs = Vec::new();
+                                        if let Some(markup) = section["description"]["C"].as_str() {
+                                            match convert_markup(markup) {
+                                                Ok(text) => paragraphs.push(text),
+                                                Err(err) => {
+                                                    log::warn!(
+                                                        "failed to parse agreement section {:?}: {}",
+                                                        markup,
+                                                        err
+                                                    );
+                                                }
+                                            }
+                                        }
+                                        sections.push((name_opt, paragraphs));
+                                    }
+                                }
+                                // Versions may be written as numbers
+                                let version_opt = match &item["version_id"] {
+                                    serde_yaml::Value::String(x) => Some(x.clone()),
+                                    serde_yaml::Value::Number(x) => Some(x.to_string()),
+                                    _ => None,
+                                };
+                                agreements.extend(AppAgreement::parse(
+                                    item["type"].as_str(),
+                                    version_opt,
+                                    sections,
+                                ));
+                            }
+                        }
+
+                        let mut languages = Vec::new();
+                        if let Some(items) = value["Languages"].as_sequence() {
+                            for item in items {
+                                if let Some(locale) = item["locale"].as_str() {
+                                    languages.extend(AppLanguage::parse(
+                                        locale,
+                                        item["percentage"].as_u64(),
+                                    ));
+                                }
+                            }
+                        }
+
+                        let mut custom = BTreeMap::new();
+                        if let Some(mapping) = value["Custom"].as_mapping() {
+                            for (key, value) in mapping.iter() {
+                                if let (Some(key), Some(value)) = (key.as_str(), value.as_str()) {
+                                    custom.insert(key.to_string(), value.to_string());
+                                }
+                            }
+                        }
+
                         let id = AppId::new(&component.id.0);
                         let monthly_downloads = stats::monthly_downloads(&id).unwrap_or(0);
-                        infos.push((
-                            id,
-                            Arc::new(AppInfo::new(
-                                &self.source_id,
-                                &self.source_name,
-                                origin_opt.as_deref(),
-                                component,
-                                &self.locale,
-                                monthly_downloads,
-                            )),
-                        ));
+                        let mut info = AppInfo::new(
+                            &self.source_id,
+                            &self.source_name,
+                            origin_opt.as_deref(),
+                            component,
+                            &locales,
+                            monthly_downloads,
+                            &screenshot_environments,
+                        );
+                        info.relations = relations;
+                        info.provides = provides;
+                        info.suggests = suggests;
+                        info.agreements = agreements;
+                        info.languages = languages;
+                        info.custom = custom;
+                        infos.push((id, Arc::new(info)));
                     }
                     Err(err) => {
                         log::error!("failed to parse {:?} in {:?}: {}", value["ID"], path, err);
@@ -909,3 +1374,239 @@ impl AppstreamCache {
         Ok(infos)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEDIA_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<components version="0.14" origin="test" media_baseurl="https://media.example.com/media/">
+  <component type="desktop-application">
+    <id>com.example.App</id>
+    <name>Example</name>
+    <summary>Example app</summary>
+    <icon type="remote" width="128" height="128">com.example.App/icon.png</icon>
+    <screenshots>
+      <screenshot type="default">
+        <image type="source">/com.example.App/screenshot-1.png</image>
+      </screenshot>
+      <screenshot>
+        <image type="source">com.example.App/screenshot-2.png</image>
+      </screenshot>
+      <screenshot>
+        <image type="source">https://other.example.com/screenshot-3.png</image>
+      </screenshot>
+    </screenshots>
+  </component>
+</components>
+"#;
+
+    #[test]
+    fn media_url_relative() {
+        let base = Some("https://media.example.com/media");
+        for url_str in ["a/b.png", "/a/b.png"] {
+            assert_eq!(
+                media_url(base, url_str).unwrap().as_str(),
+                "https://media.example.com/media/a/b.png"
+            );
+        }
+        assert_eq!(
+            media_url(base, "https://other.example.com/b.png")
+                .unwrap()
+                .as_str(),
+            "https://other.example.com/b.png"
+        );
+        assert!(media_url(None, "a/b.png").is_err());
+    }
+
+    #[test]
+    fn parse_xml_resolves_media_urls() {
+        let appstream_cache = AppstreamCache::default();
+        let infos = appstream_cache
+            .parse_xml("test.xml", MEDIA_XML.as_bytes())
+            .unwrap();
+        assert_eq!(infos.len(), 1);
+        let (id, info) = &infos[0];
+        assert_eq!(id.raw(), "com.example.App");
+        let urls: Vec<_> = info
+            .screenshots
+            .iter()
+            .map(|screenshot| screenshot.url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "https://media.example.com/media/com.example.App/screenshot-1.png",
+                "https://media.example.com/media/com.example.App/screenshot-2.png",
+                "https://other.example.com/screenshot-3.png",
+            ]
+        );
+        assert_remote_icon(info);
+    }
+
+    const MEDIA_YAML: &str = r#"---
+File: DEP-11
+Version: '0.14'
+Origin: test
+MediaBaseUrl: https://media.example.com/media
+---
+Type: desktop-application
+ID: com.example.App
+Name:
+  C: Example
+Summary:
+  C: Example app
+Icon:
+  remote:
+  - url: com.example.App/icon.png
+    width: 128
+    height: 128
+"#;
+
+    /// The remote icon is resolved relative to the media base URL
+    fn assert_remote_icon(info: &AppInfo) {
+        let urls: Vec<_> = info
+            .icons
+            .iter()
+            .filter_map(|icon| match icon {
+                AppIcon::Remote(url, width, height, _scale) => {
+                    Some((url.as_str(), *width, *height))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            urls,
+            [(
+                "https://media.example.com/media/com.example.App/icon.png",
+                Some(128),
+                Some(128)
+            )]
+        );
+    }
+
+    #[test]
+    fn parse_yaml_resolves_media_urls() {
+        let appstream_cache = AppstreamCache::default();
+        let infos = appstream_cache
+            .parse_yaml("test.yml", MEDIA_YAML.as_bytes())
+            .unwrap();
+        assert_eq!(infos.len(), 1);
+        assert_remote_icon(&infos[0].1);
+    }
+
+    const DEFAULT_SCREENSHOT_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<components version="0.14" origin="test">
+  <component type="desktop-application">
+    <id>com.example.App</id>
+    <name>Example</name>
+    <summary>Example app</summary>
+    <screenshots>
+      <screenshot>
+        <image type="source">https://media.example.com/screenshot-1.png</image>
+      </screenshot>
+      <screenshot type="default">
+        <image type="source">https://media.example.com/screenshot-2.png</image>
+      </screenshot>
+      <screenshot>
+        <image type="source">https://media.example.com/screenshot-3.png</image>
+      </screenshot>
+    </screenshots>
+  </component>
+</components>
+"#;
+
+    const DEFAULT_SCREENSHOT_YAML: &str = r#"---
+File: DEP-11
+Version: '0.14'
+Origin: test
+---
+Type: desktop-application
+ID: com.example.App
+Name:
+  C: Example
+Summary:
+  C: Example app
+Screenshots:
+- source-image:
+    url: https://media.example.com/screenshot-1.png
+- default: true
+  source-image:
+    url: https://media.example.com/screenshot-2.png
+- source-image:
+    url: https://media.example.com/screenshot-3.png
+"#;
+
+    /// The default screenshot is first, followed by the others in declared order
+    fn assert_default_screenshot_first(infos: &[(AppId, Arc<AppInfo>)]) {
+        assert_eq!(infos.len(), 1);
+        let urls: Vec<_> = infos[0]
+            .1
+            .screenshots
+            .iter()
+            .map(|screenshot| screenshot.url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "https://media.example.com/screenshot-2.png",
+                "https://media.example.com/screenshot-1.png",
+                "https://media.example.com/screenshot-3.png",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_xml_default_screenshot_first() {
+        let appstream_cache = AppstreamCache::default();
+        let infos = appstream_cache
+            .parse_xml("test.xml", DEFAULT_SCREENSHOT_XML.as_bytes())
+            .unwrap();
+        assert_default_screenshot_first(&infos);
+    }
+
+    #[test]
+    fn parse_yaml_default_screenshot_first() {
+        let appstream_cache = AppstreamCache::default();
+        let infos = appstream_cache
+            .parse_yaml("test.yml", DEFAULT_SCREENSHOT_YAML.as_bytes())
+            .unwrap();
+        assert_default_screenshot_first(&infos);
+    }
+
+    const AGREEMENT_YAML: &str = r#"---
+File: DEP-11
+Version: '0.14'
+Origin: test
+---
+Type: desktop-application
+ID: com.example.App
+Name:
+  C: Example
+Summary:
+  C: Example app
+Agreements:
+- type: eula
+  version_id: 1.0
+  sections:
+  - type: intro
+    name:
+      C: Introduction
+    description:
+      C: <p>You must accept this.</p>
+"#;
+
+    #[test]
+    fn parse_yaml_agreements() {
+        let appstream_cache = AppstreamCache::default();
+        let infos = appstream_cache
+            .parse_yaml("test.yml", AGREEMENT_YAML.as_bytes())
+            .unwrap();
+        assert_eq!(infos.len(), 1);
+        let agreements = &infos[0].1.agreements;
+        assert_eq!(agreements.len(), 1);
+        assert!(agreements[0].required());
+        assert_eq!(agreements[0].version_opt.as_deref(), Some("1.0"));
+        assert_eq!(agreements[0].text, "Introduction\n\nYou must accept this.");
+    }
+}
@@ -1,99 +1,138 @@
 use cosmic::widget;
-use libflatpak::{gio::Cancellable, prelude::*, Installation, Ref, Transaction};
+use libflatpak::{
+    gio::Cancellable, prelude::*, Installation, QueryFlags, Ref, RefKind, Transaction,
+    TransactionOperationType,
+};
 use std::{
     cell::Cell,
-    collections::HashMap,
+    cmp,
+    collections::{BTreeMap, HashMap},
     error::Error,
     fmt::Write,
+    process,
     sync::{Arc, Mutex},
 };
 
-use super::{Backend, Package};
-use crate::{AppId, AppInfo, AppstreamCache, Operation, OperationKind};
+use super::{Backend, Package, Remote};
+use crate::{
+    app_info::PRERELEASE_BRANCHES, config::InstallScope, localize::LANGUAGE_SORTER, AppId, AppInfo,
+    AppKind, AppstreamCache, Operation, OperationKind, OperationPlanItem, OperationProgress,
+    OperationRestart, OperationStatus,
+};
+
+// Appstream data older than this in seconds is refreshed on startup
+const METADATA_MAX_AGE: u64 = 24 * 60 * 60;
 
 #[derive(Debug)]
 pub struct Flatpak {
     appstream_caches: Vec<AppstreamCache>,
+    /// Installation that the remote of each appstream cache is from, by source ID
+    remote_scopes: HashMap<String, InstallScope>,
 }
 
 impl Flatpak {
     pub fn new(locale: &str) -> Result<Self, Box<dyn Error>> {
         let mut appstream_caches = Vec::new();
+        let mut remote_scopes = HashMap::new();
 
-        //TODO: should we support system installations?
-        let inst = Installation::new_user(Cancellable::NONE)?;
-        for remote in inst.list_remotes(Cancellable::NONE)? {
-            let source_id = match remote.name() {
-                Some(some) => some.to_string(),
-                None => {
-                    log::warn!("remote {:?} missing name", remote);
+        // User remotes take precedence over system remotes with the same name
+        for (scope, inst) in Self::installations()? {
+            for remote in inst.list_remotes(Cancellable::NONE)? {
+                if remote.is_disabled() {
                     continue;
                 }
-            };
-
-            let appstream_dir = match remote.appstream_dir(None).and_then(|x| x.path()) {
-                Some(some) => some,
-                None => {
-                    log::warn!("remote {:?} missing appstream dir", remote);
+                let source_id = match remote.name() {
+                    Some(some) => some.to_string(),
+                    None => {
+                        log::warn!("remote {:?} missing name", remote);
+                        continue;
+                    }
+                };
+                if remote_scopes.contains_key(&source_id) {
                     continue;
                 }
-            };
 
-            //TODO: also update if out of date?
-            if !appstream_dir.is_dir() {
-                log::info!("updating appstream data for remote {:?}", remote);
-                match inst.update_appstream_sync(&source_id, None, Cancellable::NONE) {
-                    Ok(()) => {}
-                    Err(err) => {
-                        log::warn!(
-                            "failed to update appstream data for remote {:?}: {}",
-                            remote,
-                            err
-                        );
+                let appstream_dir = match remote.appstream_dir(None).and_then(|x| x.path()) {
+                    Some(some) => some,
+                    None => {
+                        log::warn!("remote {:?} missing appstream dir", remote);
+                        continue;
                     }
-                }
-            }
+                };
 
-            let mut paths = Vec::new();
-            let xml_gz_path = appstream_dir.join("appstream.xml.gz");
-            if xml_gz_path.is_file() {
-                paths.push(xml_gz_path);
-            } else {
-                let xml_path = appstream_dir.join("appstream.xml");
-                if xml_path.is_file() {
-                    paths.push(xml_path);
+                // Missing appstream data is downloaded by refresh_metadata
+                let mut paths = Vec::new();
+                let xml_gz_path = appstream_dir.join("appstream.xml.gz");
+                if xml_gz_path.is_file() {
+                    paths.push(xml_gz_path);
+                } else {
+                    let xml_path = appstream_dir.join("appstream.xml");
+                    if xml_path.is_file() {
+                        paths.push(xml_path);
+                    }
                 }
-            }
 
-            let mut icons_paths = Vec::new();
-            let icons_path = appstream_dir.join("icons");
-            if icons_path.is_dir() {
-                match icons_path.into_os_string().into_string() {
-                    Ok(ok) => icons_paths.push(ok),
-                    Err(os_string) => {
-                        log::error!("failed to convert {:?} to string", os_string)
+                let mut icons_paths = Vec::new();
+                let icons_path = appstream_dir.join("icons");
+                if icons_path.is_dir() {
+                    match icons_path.into_os_string().into_string() {
+                        Ok(ok) => icons_paths.push(ok),
+                        Err(os_string) => {
+                            log::error!("failed to convert {:?} to string", os_string)
+                        }
                     }
                 }
-            }
 
-            let source_name = match remote.title() {
-                Some(title) => title.to_string(),
-                None => source_id.clone(),
-            };
-            appstream_caches.push(AppstreamCache::new(
-                source_id,
-                source_name,
-                paths,
-                icons_paths,
-                locale,
-            ));
+                let source_name = match remote.title() {
+                    Some(title) => title.to_string(),
+                    None => source_id.clone(),
+                };
+                remote_scopes.insert(source_id.clone(), scope);
+                appstream_caches.push(AppstreamCache::new(
+                    source_id,
+                    source_name,
+                    paths,
+                    icons_paths,
+                    locale,
+                ));
+            }
         }
 
         // We don't store the installation because it is not Send
-        Ok(Self { appstream_caches })
+        Ok(Self {
+            appstream_caches,
+            remote_scopes,
+        })
+    }
+
+    /// User installation, followed by the system installation if it can be opened
+    fn installations() -> Result<Vec<(InstallScope, Installation)>, Box<dyn Error>> {
+        let mut insts = vec![(
+            InstallScope::User,
+            Installation::new_user(Cancellable::NONE)?,
+        )];
+        match Installation::new_system(Cancellable::NONE) {
+            Ok(ok) => insts.push((InstallScope::System, ok)),
+            Err(err) => {
+                log::warn!("failed to open system flatpak installation: {}", err);
+            }
+        }
+        Ok(insts)
     }
 
-    fn ref_to_package<R: InstalledRefExt + RefExt>(&self, r: &R) -> Option<Package> {
+    /// Installation that new apps are installed to
+    fn scope_installation(scope: InstallScope) -> Result<Installation, Box<dyn Error>> {
+        Ok(match scope {
+            InstallScope::User => Installation::new_user(Cancellable::NONE)?,
+            InstallScope::System => Installation::new_system(Cancellable::NONE)?,
+        })
+    }
+
+    fn ref_to_package<R: InstalledRefExt + RefExt>(
+        &self,
+        scope: InstallScope,
+        r: &R,
+    ) -> Option<Package> {
         let id_raw = r.name()?;
         let id = AppId::new(&id_raw);
         let origin = r.origin()?;
@@ -113,9 +152,6 @@ impl Flatpak {
             if let Some(arch) = r.arch() {
                 extra.insert("arch".to_string(), arch.to_string());
             }
-            if let Some(branch) = r.branch() {
-                extra.insert("branch".to_string(), branch.to_string());
-            }
 
             return Some(Package {
                 id: id.clone(),
@@ -123,6 +159,10 @@ impl Flatpak {
                 info: info.clone(),
                 version: r.appdata_version().unwrap_or_default().to_string(),
                 extra,
+                installed_size: Some(r.installed_size()),
+                branch_opt: r.branch().map(|x| x.to_string()),
+                current: r.is_current(),
+                scope,
             });
         }
 
@@ -130,16 +170,21 @@ impl Flatpak {
         None
     }
 
-    fn refs_to_packages<R: InstalledRefExt + RefExt>(&self, rs: Vec<R>) -> Vec<Package> {
+    fn refs_to_packages<R: InstalledRefExt + RefExt>(
+        &self,
+        rs: Vec<(InstallScope, R)>,
+    ) -> Vec<Package> {
         let mut packages = Vec::new();
-        let mut system_packages = Vec::new();
-        for r in rs {
-            match self.ref_to_package(&r) {
+        // Refs without app info are grouped by installation, so changes to them stay in it
+        let mut scope_system_packages: BTreeMap<InstallScope, Vec<(String, String)>> =
+            BTreeMap::new();
+        for (scope, r) in rs {
+            match self.ref_to_package(scope, &r) {
                 Some(package) => {
                     packages.push(package);
                 }
                 None => {
-                    system_packages.push((
+                    scope_system_packages.entry(scope).or_default().push((
                         r.format_ref().unwrap_or_default().to_string(),
                         r.appdata_version()
                             .or(r.branch())
@@ -150,7 +195,7 @@ impl Flatpak {
             }
         }
 
-        if !system_packages.is_empty() {
+        for (scope, system_packages) in scope_system_packages {
             //TODO: use correct appstream cache, or do not bother to specify it
             let appstream_cache = &self.appstream_caches[0];
             let name = "System Packages".to_string();
@@ -173,6 +218,7 @@ impl Flatpak {
                     .handle(),
                 //TODO: fill in more AppInfo fields
                 info: Arc::new(AppInfo {
+                    kind: AppKind::App,
                     source_id: appstream_cache.source_id.clone(),
                     source_name: appstream_cache.source_name.clone(),
                     origin_opt: None,
@@ -182,106 +228,62 @@ impl Flatpak {
                     description,
                     pkgnames: Vec::new(),
                     categories: Vec::new(),
-                    desktop_ids: Vec::new(),
+                    keywords: Vec::new(),
+                    launchables: Vec::new(),
                     flatpak_refs,
+                    flatpak_runtime_opt: None,
                     icons: Vec::new(),
                     releases: Vec::new(),
                     screenshots: Vec::new(),
+                    urls: Vec::new(),
+                    content_rating: None,
+                    relations: Vec::new(),
+                    provides: Vec::new(),
+                    agreements: Vec::new(),
+                    languages: Vec::new(),
+                    suggests: Vec::new(),
+                    eol_opt: None,
+                    eol_rebase_opt: None,
+                    custom: BTreeMap::new(),
+                    prerelease: false,
                     monthly_downloads: 0,
                 }),
                 version: String::new(),
                 extra: HashMap::new(),
+                installed_size: None,
+                branch_opt: None,
+                current: true,
+                scope,
             });
         }
 
         packages
     }
-}
-
-impl Backend for Flatpak {
-    fn load_caches(&mut self, refresh: bool) -> Result<(), Box<dyn Error>> {
-        if refresh {
-            //TODO: should we support system installations?
-            let inst = Installation::new_user(Cancellable::NONE)?;
-            for remote in inst.list_remotes(Cancellable::NONE)? {
-                let Some(remote_name) = remote.name() else {
-                    continue;
-                };
-                inst.update_remote_sync(&remote_name, Cancellable::NONE)?;
-                inst.update_appstream_sync(&remote_name, None, Cancellable::NONE)?;
-            }
-        }
 
-        for appstream_cache in self.appstream_caches.iter_mut() {
-            appstream_cache.reload();
+    /// Nothing to install means the refs were not found in the remotes of the installation
+    fn empty_install(op: &Operation) -> Result<(), Box<dyn Error>> {
+        if op.kind != OperationKind::Install {
+            return Ok(());
         }
-        Ok(())
-    }
-
-    fn info_caches(&self) -> &[AppstreamCache] {
-        &self.appstream_caches
-    }
-
-    fn installed(&self) -> Result<Vec<Package>, Box<dyn Error>> {
-        //TODO: should we support system installations?
-        let inst = Installation::new_user(Cancellable::NONE)?;
-        let packages = self.refs_to_packages(inst.list_installed_refs(Cancellable::NONE)?);
-        Ok(packages)
-    }
-
-    fn updates(&self) -> Result<Vec<Package>, Box<dyn Error>> {
-        //TODO: should we support system installations?
-        let inst = Installation::new_user(Cancellable::NONE)?;
-        let packages =
-            self.refs_to_packages(inst.list_installed_refs_for_update(Cancellable::NONE)?);
-        Ok(packages)
-    }
-
-    fn file_packages(&self, path: &str) -> Result<Vec<Package>, Box<dyn Error>> {
-        Err("flatpak backend does not support loading details from a file".into())
+        let sources: Vec<_> = op
+            .infos
+            .iter()
+            .map(|info| info.source_id.as_str())
+            .collect();
+        Err(format!(
+            "no refs to install found in remote {} of the {:?} installation",
+            sources.join(", "),
+            op.scope
+        )
+        .into())
     }
 
-    fn operation(
-        &self,
+    /// Add the refs for an operation to a transaction
+    fn add_operation(
+        inst: &Installation,
+        tx: &Transaction,
         op: &Operation,
-        callback: Box<dyn FnMut(f32) + 'static>,
     ) -> Result<(), Box<dyn Error>> {
-        let callback = Arc::new(Mutex::new(callback));
-        //TODO: should we support system installations?
-        let inst = Installation::new_user(Cancellable::NONE)?;
-        let total_ops = Arc::new(Cell::new(0));
-        let tx = Transaction::for_installation(&inst, Cancellable::NONE)?;
-        {
-            let total_ops = total_ops.clone();
-            tx.connect_ready(move |tx| {
-                total_ops.set(tx.operations().len());
-                true
-            });
-        }
-        let started_ops = Arc::new(Cell::new(0));
-        tx.connect_new_operation(move |_, op, progress| {
-            let current_op = started_ops.get();
-            started_ops.set(current_op + 1);
-            let progress_per_op = 100.0 / (total_ops.get().max(started_ops.get()) as f32);
-            log::info!(
-                "Operation {}: {:?} {:?}",
-                current_op,
-                op.operation_type(),
-                op.get_ref()
-            );
-            let callback = callback.clone();
-            progress.connect_changed(move |progress| {
-                log::info!(
-                    "{}: {}%",
-                    progress.status().unwrap_or_default(),
-                    progress.progress()
-                );
-                let op_progress = (progress.progress() as f32) / 100.0;
-                let total_progress = ((current_op as f32) + op_progress) * progress_per_op;
-                let mut callback = callback.lock().unwrap();
-                callback(total_progress)
-            });
-        });
         match op.kind {
             OperationKind::Install => {
                 for info in op.infos.iter() {
@@ -389,7 +391,599 @@ impl Backend for Flatpak {
                 }
             }
         }
-        tx.run(Cancellable::NONE)?;
         Ok(())
     }
 }
+
+impl Backend for Flatpak {
+    fn version(&self) -> Result<String, Box<dyn Error>> {
+        // libflatpak does not provide the runtime version, so ask the flatpak command
+        let output = process::Command::new("flatpak").arg("--version").output()?;
+        if !output.status.success() {
+            return Err(format!("flatpak --version failed with {}", output.status).into());
+        }
+        let stdout = String::from_utf8(output.stdout)?;
+        Ok(stdout
+            .trim()
+            .trim_start_matches("Flatpak")
+            .trim()
+            .to_string())
+    }
+
+    fn load_caches(&mut self, refresh: bool) -> Result<(), Box<dyn Error>> {
+        if refresh {
+            self.refresh_metadata(Box::new(|_| {}))?;
+        }
+        let insts = Self::installations()?;
+
+        for appstream_cache in self.appstream_caches.iter_mut() {
+            appstream_cache.reload();
+
+            // End-of-life state is in the remote summary of the installation the remote is from
+            let Some(scope) = self.remote_scopes.get(&appstream_cache.source_id) else {
+                continue;
+            };
+            let Some((_, inst)) = insts.iter().find(|(x, _)| x == scope) else {
+                continue;
+            };
+            let remote_refs = match inst.list_remote_refs_sync_full(
+                &appstream_cache.source_id,
+                QueryFlags::ONLY_CACHED,
+                Cancellable::NONE,
+            ) {
+                Ok(ok) => ok,
+                Err(err) => {
+                    log::warn!(
+                        "failed to list cached refs of remote {:?}: {}",
+                        appstream_cache.source_id,
+                        err
+                    );
+                    continue;
+                }
+            };
+            for remote_ref in remote_refs {
+                let eol_opt = remote_ref.eol();
+                let eol_rebase_opt = remote_ref.eol_rebase();
+                if eol_opt.is_none() && eol_rebase_opt.is_none() {
+                    continue;
+                }
+                let Some(id_raw) = remote_ref.name() else {
+                    continue;
+                };
+                if let Some(info) = appstream_cache.infos.get_mut(&AppId::new(&id_raw)) {
+                    let info = Arc::make_mut(info);
+                    info.eol_opt = Some(eol_opt.unwrap_or_default().to_string());
+                    info.eol_rebase_opt =
+                        eol_rebase_opt.and_then(|rebase| match Ref::parse(&rebase) {
+                            Ok(r) => r.name().map(|x| x.to_string()),
+                            Err(err) => {
+                                log::warn!("failed to parse flatpak ref {:?}: {}", rebase, err);
+                                None
+                            }
+                        });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn metadata_stale(&self) -> bool {
+        self.appstream_caches.iter().any(|appstream_cache| {
+            appstream_cache
+                .age_opt()
+                .map_or(true, |age| age > METADATA_MAX_AGE)
+        })
+    }
+
+    fn refresh_metadata(
+        &self,
+        mut callback: Box<dyn FnMut(OperationProgress) + 'static>,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut remotes = Vec::new();
+        for (scope, inst) in Self::installations()? {
+            for remote in inst.list_remotes(Cancellable::NONE)? {
+                remotes.push((scope, inst.clone(), remote));
+            }
+        }
+        for (remote_i, (scope, inst, remote)) in remotes.iter().enumerate() {
+            let Some(remote_name) = remote.name() else {
+                continue;
+            };
+            if remote.is_disabled() {
+                continue;
+            }
+            // Progress is only known per remote
+            callback(OperationProgress {
+                percent_opt: Some(100.0 * remote_i as f32 / remotes.len() as f32),
+                status_opt: Some(OperationStatus::Downloading),
+                bytes_opt: None,
+            });
+            log::info!(
+                "updating appstream data for remote {:?} of {:?} installation",
+                remote_name,
+                scope
+            );
+            let res = inst
+                .update_remote_sync(&remote_name, Cancellable::NONE)
+                .and_then(|_| inst.update_appstream_sync(&remote_name, None, Cancellable::NONE));
+            match res {
+                Ok(_) => {}
+                // Updating the system installation may not be authorized
+                Err(err) if *scope == InstallScope::System => {
+                    log::warn!("failed to update system remote {:?}: {}", remote_name, err);
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(())
+    }
+
+    fn info_caches(&self) -> &[AppstreamCache] {
+        &self.appstream_caches
+    }
+
+    fn installed(&self) -> Result<Vec<Package>, Box<dyn Error>> {
+        let mut refs = Vec::new();
+        for (scope, inst) in Self::installations()? {
+            refs.extend(
+                inst.list_installed_refs(Cancellable::NONE)?
+                    .into_iter()
+                    .map(|r| (scope, r)),
+            );
+        }
+        let packages = self.refs_to_packages(refs);
+        Ok(packages)
+    }
+
+    fn updates(&self) -> Result<Vec<Package>, Box<dyn Error>> {
+        let mut refs = Vec::new();
+        for (scope, inst) in Self::installations()? {
+            refs.extend(
+                inst.list_installed_refs_for_update(Cancellable::NONE)?
+                    .into_iter()
+                    .map(|r| (scope, r)),
+            );
+        }
+        let packages = self.refs_to_packages(refs);
+        Ok(packages)
+    }
+
+    fn unused(&self) -> Result<Vec<Package>, Box<dyn Error>> {
+        let mut refs = Vec::new();
+        for (scope, inst) in Self::installations()? {
+            refs.extend(
+                inst.list_unused_refs(None, Cancellable::NONE)?
+                    .into_iter()
+                    .map(|r| (scope, r)),
+            );
+        }
+        let mut packages = Vec::new();
+        for (scope, r) in refs {
+            let Some(flatpak_ref) = r.format_ref() else {
+                continue;
+            };
+            let origin = r.origin().unwrap_or_default().to_string();
+            let source_name = self
+                .appstream_caches
+                .iter()
+                .find(|appstream_cache| appstream_cache.source_id == origin)
+                .map_or_else(|| origin.clone(), |x| x.source_name.clone());
+            let name = r.name().unwrap_or_default().to_string();
+            //TODO: translate
+            packages.push(Package {
+                id: AppId::new(&name),
+                icon: widget::icon::from_name("package-x-generic")
+                    .size(128)
+                    .handle(),
+                //TODO: fill in more AppInfo fields
+                info: Arc::new(AppInfo {
+                    kind: AppKind::App,
+                    source_id: origin,
+                    source_name,
+                    origin_opt: None,
+                    name,
+                    summary: flatpak_ref.to_string(),
+                    developer_name: String::new(),
+                    description: String::new(),
+                    pkgnames: Vec::new(),
+                    categories: Vec::new(),
+                    keywords: Vec::new(),
+                    launchables: Vec::new(),
+                    flatpak_refs: vec![flatpak_ref.to_string()],
+                    flatpak_runtime_opt: None,
+                    icons: Vec::new(),
+                    releases: Vec::new(),
+                    screenshots: Vec::new(),
+                    urls: Vec::new(),
+                    content_rating: None,
+                    relations: Vec::new(),
+                    provides: Vec::new(),
+                    agreements: Vec::new(),
+                    languages: Vec::new(),
+                    suggests: Vec::new(),
+                    eol_opt: None,
+                    eol_rebase_opt: None,
+                    custom: BTreeMap::new(),
+                    prerelease: r
+                        .branch()
+                        .as_deref()
+                        .map_or(false, |branch| PRERELEASE_BRANCHES.contains(&branch)),
+                    monthly_downloads: 0,
+                }),
+                version: r.branch().unwrap_or_default().to_string(),
+                extra: HashMap::new(),
+                installed_size: Some(r.installed_size()),
+                branch_opt: r.branch().map(|x| x.to_string()),
+                current: r.is_current(),
+                scope,
+            });
+        }
+        Ok(packages)
+    }
+
+    fn file_packages(&self, path: &str) -> Result<Vec<Package>, Box<dyn Error>> {
+        Err("flatpak backend does not support loading details from a file".into())
+    }
+
+    fn dependents(&self, _id: &AppId, info: &AppInfo) -> Result<Vec<String>, Box<dyn Error>> {
+        // Runtimes are used by their name/arch/branch, and add-ons by their extension point
+        let mut runtimes = Vec::new();
+        for r_str in info.flatpak_refs.iter() {
+            let r = match Ref::parse(r_str) {
+                Ok(ok) => ok,
+                Err(err) => {
+                    log::warn!("failed to parse flatpak ref {:?}: {}", r_str, err);
+                    continue;
+                }
+            };
+            if r.kind() == RefKind::Runtime {
+                runtimes.push((
+                    r_str.trim_start_matches("runtime/").to_string(),
+                    r.name().unwrap_or_default().to_string(),
+                ));
+            }
+        }
+        if runtimes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut refs = Vec::new();
+        for (_scope, inst) in Self::installations()? {
+            refs.extend(inst.list_installed_refs(Cancellable::NONE)?);
+        }
+        let mut dependents = Vec::new();
+        for r in refs {
+            let Some(r_str) = r.format_ref() else {
+                continue;
+            };
+            if info.flatpak_refs.iter().any(|x| x == r_str.as_str()) {
+                continue;
+            }
+            let metadata = match r.load_metadata(Cancellable::NONE) {
+                Ok(ok) => ok,
+                Err(err) => {
+                    log::warn!("failed to load metadata of {}: {}", r_str, err);
+                    continue;
+                }
+            };
+            //TODO: use a proper keyfile parser
+            let mut uses = false;
+            for line in String::from_utf8_lossy(&metadata).lines() {
+                let line = line.trim();
+                if let Some(runtime_ref) = line.strip_prefix("runtime=") {
+                    uses |= runtimes.iter().any(|(x, _)| x == runtime_ref);
+                } else if let Some(point) = line
+                    .strip_prefix("[Extension ")
+                    .and_then(|x| x.strip_suffix(']'))
+                {
+                    uses |= runtimes.iter().any(|(_, name)| {
+                        name == point
+                            || name
+                                .strip_prefix(point)
+                                .map_or(false, |x| x.starts_with('.'))
+                    });
+                }
+            }
+            if !uses {
+                continue;
+            }
+            // Show app names when they are found in appstream data
+            let name = r.name().unwrap_or_default().to_string();
+            let origin = r.origin().unwrap_or_default();
+            dependents.push(
+                self.appstream_caches
+                    .iter()
+                    .filter(|appstream_cache| appstream_cache.source_id == origin.as_str())
+                    .find_map(|appstream_cache| {
+                        appstream_cache
+                            .infos
+                            .get(&AppId::new(&name))
+                            .map(|info| info.name.clone())
+                    })
+                    .unwrap_or(name),
+            );
+        }
+        dependents.sort_by(|a, b| LANGUAGE_SORTER.compare(a, b));
+        dependents.dedup();
+        Ok(dependents)
+    }
+
+    fn signed(&self, _id: &AppId, info: &AppInfo) -> Result<bool, Box<dyn Error>> {
+        let scope = self
+            .remote_scopes
+            .get(&info.source_id)
+            .ok_or_else(|| format!("remote {:?} not found", info.source_id))?;
+        let inst = Self::scope_installation(*scope)?;
+        let remote = inst.remote_by_name(&info.source_id, Cancellable::NONE)?;
+        Ok(remote.gpg_verify())
+    }
+
+    fn runtime_installed(&self, runtime: &str) -> Result<bool, Box<dyn Error>> {
+        let mut parts = runtime.split('/');
+        let (Some(name), Some(arch), Some(branch)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format!("invalid runtime {:?}", runtime).into());
+        };
+        for (_scope, inst) in Self::installations()? {
+            if inst
+                .installed_ref(
+                    RefKind::Runtime,
+                    name,
+                    Some(arch),
+                    Some(branch),
+                    Cancellable::NONE,
+                )
+                .is_ok()
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn remotes(&self) -> Result<Vec<Remote>, Box<dyn Error>> {
+        let mut remotes = Vec::new();
+        for (scope, inst) in Self::installations()? {
+            for remote in inst.list_remotes(Cancellable::NONE)? {
+                let Some(name) = remote.name() else {
+                    continue;
+                };
+                remotes.push(Remote {
+                    title: remote
+                        .title()
+                        .map_or_else(|| name.to_string(), |x| x.to_string()),
+                    name: name.to_string(),
+                    url: remote.url().map(|x| x.to_string()).unwrap_or_default(),
+                    enabled: !remote.is_disabled(),
+                    scope,
+                });
+            }
+        }
+        Ok(remotes)
+    }
+
+    fn remote_enable(
+        &self,
+        name: &str,
+        scope: InstallScope,
+        enabled: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let inst = Self::scope_installation(scope)?;
+        let remote = inst.remote_by_name(name, Cancellable::NONE)?;
+        remote.set_disabled(!enabled);
+        inst.modify_remote(&remote, Cancellable::NONE)?;
+        Ok(())
+    }
+
+    fn remote_add(&self, url: &str, scope: InstallScope) -> Result<String, Box<dyn Error>> {
+        let url = url.trim();
+        // Only repository files are added, as they include the key that the remote is verified with
+        if !url.ends_with(".flatpakrepo") {
+            return Err(format!("{:?} is not a .flatpakrepo file", url).into());
+        }
+        // Name is taken from the repository file name
+        let base_name: String = url
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches(".flatpakrepo")
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            .collect();
+        if base_name.is_empty() {
+            return Err(format!("failed to find remote name in {:?}", url).into());
+        }
+        // A number is added to the name if another remote already uses it
+        let inst = Self::scope_installation(scope)?;
+        let mut name = base_name.clone();
+        let mut suffix = 2;
+        while inst.remote_by_name(&name, Cancellable::NONE).is_ok() {
+            name = format!("{}-{}", base_name, suffix);
+            suffix += 1;
+        }
+        // libflatpak does not support parsing repository files, so ask the flatpak command
+        let output = process::Command::new("flatpak")
+            .arg("remote-add")
+            .arg(match scope {
+                InstallScope::User => "--user",
+                InstallScope::System => "--system",
+            })
+            .arg("--from")
+            .arg(&name)
+            .arg(url)
+            .output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "flatpak remote-add failed with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        // Repository files without a key would add a remote that is not verified
+        let inst = Self::scope_installation(scope)?;
+        let remote = inst.remote_by_name(&name, Cancellable::NONE)?;
+        if !remote.gpg_verify() {
+            inst.remove_remote(&name, Cancellable::NONE)?;
+            return Err(format!("{:?} does not include a signing key", url).into());
+        }
+        Ok(name)
+    }
+
+    fn switch_branch(&self, id: &AppId, branch: &str) -> Result<(), Box<dyn Error>> {
+        // The branch is made current in the installation it is installed in
+        let scope = Self::installations()?
+            .into_iter()
+            .find_map(|(scope, inst)| {
+                inst.installed_ref(
+                    RefKind::App,
+                    id.raw(),
+                    None,
+                    Some(branch),
+                    Cancellable::NONE,
+                )
+                .ok()
+                .map(|_| scope)
+            })
+            .ok_or_else(|| format!("branch {:?} of {:?} is not installed", branch, id))?;
+        // libflatpak does not support making a branch current, so ask the flatpak command
+        let output = process::Command::new("flatpak")
+            .arg("make-current")
+            .arg(match scope {
+                InstallScope::User => "--user",
+                InstallScope::System => "--system",
+            })
+            .arg(id.raw())
+            .arg(branch)
+            .output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "flatpak make-current failed with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    fn recent_additions(&self, _since: i64) -> Result<Vec<(AppId, i64)>, Box<dyn Error>> {
+        Err("flatpak backend does not support listing recent additions".into())
+    }
+
+    fn operation(
+        &self,
+        op: &Operation,
+        callback: Box<dyn FnMut(OperationProgress) + 'static>,
+    ) -> Result<Option<OperationRestart>, Box<dyn Error>> {
+        let callback = Arc::new(Mutex::new(callback));
+        let inst = Self::scope_installation(op.scope)?;
+        let total_ops = Arc::new(Cell::new(0));
+        let tx = Transaction::for_installation(&inst, Cancellable::NONE)?;
+        // System installations ask for authorization through polkit, which needs interaction
+        tx.set_no_interaction(false);
+        {
+            let total_ops = total_ops.clone();
+            tx.connect_ready(move |tx| {
+                total_ops.set(tx.operations().len());
+                true
+            });
+        }
+        let started_ops = Arc::new(Cell::new(0));
+        let callback = callback.clone();
+        tx.connect_new_operation(move |_, op, progress| {
+            let current_op = started_ops.get();
+            started_ops.set(current_op + 1);
+            let progress_per_op = 100.0 / (total_ops.get().max(started_ops.get()) as f32);
+            log::info!(
+                "Operation {}: {:?} {:?}",
+                current_op,
+                op.operation_type(),
+                op.get_ref()
+            );
+            let operation_type = op.operation_type();
+            let download_size = op.download_size();
+            let callback = callback.clone();
+            progress.connect_changed(move |progress| {
+                log::info!(
+                    "{}: {}%",
+                    progress.status().unwrap_or_default(),
+                    progress.progress()
+                );
+                let op_progress = (progress.progress() as f32) / 100.0;
+                let total_progress = ((current_op as f32) + op_progress) * progress_per_op;
+                let bytes_transferred = progress.bytes_transferred();
+                let status = match operation_type {
+                    TransactionOperationType::Uninstall => OperationStatus::Removing,
+                    _ => {
+                        if bytes_transferred < download_size {
+                            OperationStatus::Downloading
+                        } else {
+                            OperationStatus::Installing
+                        }
+                    }
+                };
+                let mut callback = callback.lock().unwrap();
+                callback(OperationProgress {
+                    percent_opt: Some(total_progress),
+                    status_opt: Some(status),
+                    bytes_opt: if download_size > 0 {
+                        Some((cmp::min(bytes_transferred, download_size), download_size))
+                    } else {
+                        None
+                    },
+                })
+            });
+        });
+        Self::add_operation(&inst, &tx, op)?;
+        if tx.is_empty() {
+            Self::empty_install(op)?;
+            return Ok(None);
+        }
+        tx.run(Cancellable::NONE)?;
+        // Flatpak changes apply the next time apps are started
+        Ok(None)
+    }
+
+    fn plan(&self, op: &Operation) -> Result<Vec<OperationPlanItem>, Box<dyn Error>> {
+        let inst = Self::scope_installation(op.scope)?;
+        let tx = Transaction::for_installation(&inst, Cancellable::NONE)?;
+        let items_opt = Arc::new(Mutex::new(None));
+        {
+            let items_opt = items_opt.clone();
+            tx.connect_ready(move |tx| {
+                let items = tx
+                    .operations()
+                    .into_iter()
+                    .map(|tx_op| OperationPlanItem {
+                        kind: match tx_op.operation_type() {
+                            TransactionOperationType::Uninstall => OperationKind::Uninstall,
+                            TransactionOperationType::Update => OperationKind::Update,
+                            _ => OperationKind::Install,
+                        },
+                        name: tx_op.get_ref().map(|x| x.to_string()).unwrap_or_default(),
+                        download_size: tx_op.download_size(),
+                        installed_size: tx_op.installed_size(),
+                    })
+                    .collect();
+                *items_opt.lock().unwrap() = Some(items);
+                // Abort the transaction, only the plan is needed
+                false
+            });
+        }
+        Self::add_operation(&inst, &tx, op)?;
+        if tx.is_empty() {
+            Self::empty_install(op)?;
+            return Ok(Vec::new());
+        }
+        let res = tx.run(Cancellable::NONE);
+        let items_opt = items_opt.lock().unwrap().take();
+        match items_opt {
+            Some(items) => Ok(items),
+            None => {
+                res?;
+                Ok(Vec::new())
+            }
+        }
+    }
+}
@@ -8,7 +8,10 @@ use std::{
     time::Instant,
 };
 
-use crate::{AppId, AppInfo, AppstreamCache, Operation};
+use crate::{
+    config::InstallScope, AppId, AppInfo, AppstreamCache, Operation, OperationPlanItem,
+    OperationProgress, OperationRestart,
+};
 
 #[cfg(feature = "flatpak")]
 mod flatpak;
@@ -23,26 +26,80 @@ pub struct Package {
     pub info: Arc<AppInfo>,
     pub version: String,
     pub extra: HashMap<String, String>,
+    pub installed_size: Option<u64>,
+    /// Installed branch, for backends that can install multiple branches of an app
+    pub branch_opt: Option<String>,
+    /// If this is the branch that runs by default
+    pub current: bool,
+    /// Installation that the package is in
+    pub scope: InstallScope,
+}
+
+/// Repository that a backend installs from
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Remote {
+    pub name: String,
+    pub title: String,
+    pub url: String,
+    pub enabled: bool,
+    /// Installation that the remote is configured in
+    pub scope: InstallScope,
 }
 
 pub trait Backend: fmt::Debug + Send + Sync {
     fn load_caches(&mut self, refresh: bool) -> Result<(), Box<dyn Error>>;
+    /// If appstream data is missing or outdated, and should be refreshed before use
+    fn metadata_stale(&self) -> bool;
+    /// Download the latest appstream data, which is used after the backend is loaded again
+    fn refresh_metadata(
+        &self,
+        f: Box<dyn FnMut(OperationProgress) + 'static>,
+    ) -> Result<(), Box<dyn Error>>;
     fn info_caches(&self) -> &[AppstreamCache];
     fn installed(&self) -> Result<Vec<Package>, Box<dyn Error>>;
     fn updates(&self) -> Result<Vec<Package>, Box<dyn Error>>;
+    /// Apps added to the backend's repositories since a timestamp, with the time they were added
+    fn recent_additions(&self, since: i64) -> Result<Vec<(AppId, i64)>, Box<dyn Error>>;
+    fn unused(&self) -> Result<Vec<Package>, Box<dyn Error>>;
     fn file_packages(&self, path: &str) -> Result<Vec<Package>, Box<dyn Error>>;
+    fn remotes(&self) -> Result<Vec<Remote>, Box<dyn Error>>;
+    fn remote_enable(
+        &self,
+        name: &str,
+        scope: InstallScope,
+        enabled: bool,
+    ) -> Result<(), Box<dyn Error>>;
+    /// Add a signed remote from a repository description file to an installation, returning its
+    /// name
+    fn remote_add(&self, url: &str, scope: InstallScope) -> Result<String, Box<dyn Error>>;
+    /// Make an installed branch of an app the one that runs by default
+    fn switch_branch(&self, id: &AppId, branch: &str) -> Result<(), Box<dyn Error>>;
+    /// Names of installed apps and packages that depend on an installed item
+    fn dependents(&self, id: &AppId, info: &AppInfo) -> Result<Vec<String>, Box<dyn Error>>;
+    /// If an item comes from a source whose signatures are verified
+    fn signed(&self, id: &AppId, info: &AppInfo) -> Result<bool, Box<dyn Error>>;
+    /// If a runtime, in the form NAME/ARCH/BRANCH, is installed
+    fn runtime_installed(&self, runtime: &str) -> Result<bool, Box<dyn Error>>;
+    fn version(&self) -> Result<String, Box<dyn Error>>;
+    /// Changes an operation would make, including dependencies, without running it
+    fn plan(&self, op: &Operation) -> Result<Vec<OperationPlanItem>, Box<dyn Error>>;
+    /// Run an operation, returning the restart needed for its changes to take effect, if any
     fn operation(
         &self,
         op: &Operation,
-        f: Box<dyn FnMut(f32) + 'static>,
-    ) -> Result<(), Box<dyn Error>>;
+        f: Box<dyn FnMut(OperationProgress) + 'static>,
+    ) -> Result<Option<OperationRestart>, Box<dyn Error>>;
 }
 
 // BTreeMap for stable sort order
 pub type Backends = BTreeMap<&'static str, Arc<dyn Backend>>;
 
-pub fn backends(locale: &str, refresh: bool) -> Backends {
+/// Backends that failed to initialize, with the error
+pub type BackendErrors = Vec<(&'static str, String)>;
+
+pub fn backends(locale: &str, refresh: bool) -> (Backends, BackendErrors) {
     let mut backends = Backends::new();
+    let mut errors = BackendErrors::new();
 
     #[cfg(feature = "flatpak")]
     {
@@ -55,6 +112,7 @@ pub fn backends(locale: &str, refresh: bool) -> Backends {
             }
             Err(err) => {
                 log::error!("failed to load flatpak backend: {}", err);
+                errors.push(("flatpak", err.to_string()));
             }
         }
     }
@@ -70,6 +128,7 @@ pub fn backends(locale: &str, refresh: bool) -> Backends {
             }
             Err(err) => {
                 log::error!("failed to load packagekit backend: {}", err);
+                errors.push(("packagekit", err.to_string()));
             }
         }
     }
@@ -97,5 +156,5 @@ pub fn backends(locale: &str, refresh: bool) -> Backends {
         log::info!("trimmed allocations in {:?}", duration);
     }
 
-    backends
+    (backends, errors)
 }
@@ -4,10 +4,21 @@ use packagekit_zbus::{
     PackageKit::PackageKitProxyBlocking,
     Transaction::TransactionProxyBlocking,
 };
-use std::{collections::HashMap, error::Error, fmt::Write, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt,
+    fmt::Write,
+    fs,
+    sync::Arc,
+};
 
-use super::{Backend, Package};
-use crate::{AppId, AppInfo, AppstreamCache, Operation, OperationKind};
+use super::{Backend, Package, Remote};
+use crate::{
+    config::InstallScope, localize::LANGUAGE_SORTER, AppId, AppInfo, AppKind, AppstreamCache,
+    Operation, OperationKind, OperationPlanItem, OperationProgress, OperationRestart,
+    OperationStatus,
+};
 
 struct TransactionDetails {
     //TODO: more fields: https://www.freedesktop.org/software/PackageKit/gtk-doc/Transaction.html#Transaction::Details
@@ -23,6 +34,20 @@ struct TransactionPackage {
     summary: String,
 }
 
+#[derive(Debug)]
+struct TransactionError {
+    code: u32,
+    details: String,
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (code {})", self.details, self.code)
+    }
+}
+
+impl Error for TransactionError {}
+
 struct TransactionProgress {
     package_id: String,
     status: u32,
@@ -31,10 +56,27 @@ struct TransactionProgress {
 
 fn transaction_handle(
     tx: TransactionProxyBlocking,
-    mut on_progress: impl FnMut(u32, TransactionProgress),
+    on_progress: impl FnMut(u32, TransactionProgress),
 ) -> Result<(Vec<TransactionDetails>, Vec<TransactionPackage>), Box<dyn Error>> {
+    transaction_handle_restart(tx, on_progress)
+        .map(|(details, packages, _restart_opt)| (details, packages))
+}
+
+/// Handle a transaction, also returning the largest restart it requires
+fn transaction_handle_restart(
+    tx: TransactionProxyBlocking,
+    mut on_progress: impl FnMut(u32, TransactionProgress),
+) -> Result<
+    (
+        Vec<TransactionDetails>,
+        Vec<TransactionPackage>,
+        Option<OperationRestart>,
+    ),
+    Box<dyn Error>,
+> {
     let mut details = Vec::new();
     let mut packages = Vec::new();
+    let mut restart_opt = None;
     for signal in tx.receive_all_signals()? {
         match signal.member() {
             Some(member) => match member.as_str() {
@@ -69,7 +111,7 @@ fn transaction_handle(
                 "ErrorCode" => {
                     // https://www.freedesktop.org/software/PackageKit/gtk-doc/Transaction.html#Transaction::ErrorCode
                     let (code, details) = signal.body::<(u32, String)>()?;
-                    return Err(format!("{details} (code {code})").into());
+                    return Err(Box::new(TransactionError { code, details }));
                 }
                 "ItemProgress" => {
                     // https://www.freedesktop.org/software/PackageKit/gtk-doc/Transaction.html#Transaction::ItemProgress
@@ -93,6 +135,18 @@ fn transaction_handle(
                         summary,
                     });
                 }
+                "RequireRestart" => {
+                    // https://www.freedesktop.org/software/PackageKit/gtk-doc/Transaction.html#Transaction::RequireRestart
+                    let (kind, package_id) = signal.body::<(u32, String)>()?;
+                    // Session and system restarts, including for security updates
+                    let restart = match kind {
+                        3 | 5 => OperationRestart::Session,
+                        4 | 6 => OperationRestart::System,
+                        _ => continue,
+                    };
+                    log::info!("{} requires {:?} restart", package_id, restart);
+                    restart_opt = restart_opt.max(Some(restart));
+                }
                 "Finished" => {
                     break;
                 }
@@ -103,7 +157,22 @@ fn transaction_handle(
             None => {}
         }
     }
-    Ok((details, packages))
+    Ok((details, packages, restart_opt))
+}
+
+// https://lazka.github.io/pgi-docs/PackageKitGlib-1.0/enums.html#PackageKitGlib.StatusEnum
+fn status_kind(status: u32) -> Option<OperationStatus> {
+    match status {
+        // Download and download-* statuses
+        8 | 20..=25 => Some(OperationStatus::Downloading),
+        // Install and update statuses
+        9 | 10 => Some(OperationStatus::Installing),
+        // Remove status
+        6 => Some(OperationStatus::Removing),
+        // Cleanup, commit, and run-hook statuses
+        11 | 16 | 36 => Some(OperationStatus::Configuring),
+        _ => None,
+    }
 }
 
 // https://lazka.github.io/pgi-docs/PackageKitGlib-1.0/enums.html#PackageKitGlib.FilterEnum
@@ -121,14 +190,73 @@ enum FilterKind {
 enum TransactionFlag {
     None = 1 << 0,
     OnlyTrusted = 1 << 1,
+    Simulate = 1 << 2,
     AllowReinstall = 1 << 4,
     AllowDowngrade = 1 << 6,
 }
 
+/// Update the time each app was first seen in the system repositories.
+/// Apps found when this is first run are given a time of zero so they are not shown as new.
+fn update_first_seen(appstream_cache: &AppstreamCache) -> HashMap<AppId, i64> {
+    let path = match dirs::cache_dir() {
+        Some(some) => some.join("cosmic-store").join("packagekit-first-seen.json"),
+        None => {
+            log::warn!("failed to find cache directory");
+            return HashMap::new();
+        }
+    };
+    let (mut first_seen, initial): (HashMap<String, i64>, bool) = match fs::read(&path) {
+        Ok(data) => match serde_json::from_slice(&data) {
+            Ok(ok) => (ok, false),
+            Err(err) => {
+                log::warn!("failed to parse {:?}: {}", path, err);
+                (HashMap::new(), true)
+            }
+        },
+        Err(_) => (HashMap::new(), true),
+    };
+    let now = if initial {
+        0
+    } else {
+        chrono::Utc::now().timestamp()
+    };
+    let mut changed = initial;
+    for id in appstream_cache.infos.keys() {
+        first_seen
+            .entry(id.normalized().to_string())
+            .or_insert_with(|| {
+                changed = true;
+                now
+            });
+    }
+    if changed {
+        if let Some(parent) = path.parent() {
+            if let Err(err) = fs::create_dir_all(parent) {
+                log::warn!("failed to create {:?}: {}", parent, err);
+            }
+        }
+        match serde_json::to_vec(&first_seen) {
+            Ok(data) => {
+                if let Err(err) = fs::write(&path, data) {
+                    log::warn!("failed to write {:?}: {}", path, err);
+                }
+            }
+            Err(err) => {
+                log::warn!("failed to serialize {:?}: {}", path, err);
+            }
+        }
+    }
+    first_seen
+        .into_iter()
+        .map(|(id, timestamp)| (AppId::new(&id), timestamp))
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct Packagekit {
     connection: Connection,
     appstream_caches: Vec<AppstreamCache>,
+    first_seen: HashMap<AppId, i64>,
 }
 
 impl Packagekit {
@@ -145,6 +273,7 @@ impl Packagekit {
                 source_name.to_string(),
                 locale,
             )],
+            first_seen: HashMap::new(),
         })
     }
 
@@ -193,6 +322,7 @@ impl Packagekit {
                     .handle(),
                 //TODO: fill in more AppInfo fields
                 info: Arc::new(AppInfo {
+                    kind: AppKind::App,
                     source_id: appstream_cache.source_id.clone(),
                     source_name: appstream_cache.source_name.clone(),
                     origin_opt: None,
@@ -202,15 +332,32 @@ impl Packagekit {
                     description: tx_detail.description.clone(),
                     pkgnames: vec![package_name.to_string()],
                     categories: Vec::new(),
-                    desktop_ids: Vec::new(),
+                    keywords: Vec::new(),
+                    launchables: Vec::new(),
                     flatpak_refs: Vec::new(),
+                    flatpak_runtime_opt: None,
                     icons: Vec::new(),
                     releases: Vec::new(),
                     screenshots: Vec::new(),
+                    urls: Vec::new(),
+                    content_rating: None,
+                    relations: Vec::new(),
+                    provides: Vec::new(),
+                    agreements: Vec::new(),
+                    languages: Vec::new(),
+                    suggests: Vec::new(),
+                    eol_opt: None,
+                    eol_rebase_opt: None,
+                    custom: BTreeMap::new(),
+                    prerelease: false,
                     monthly_downloads: 0,
                 }),
                 version: version_opt.unwrap_or("").to_string(),
                 extra: HashMap::new(),
+                installed_size: None,
+                branch_opt: None,
+                current: true,
+                scope: InstallScope::System,
             });
         }
 
@@ -238,6 +385,10 @@ impl Packagekit {
                                     info: info.clone(),
                                     version: version_opt.unwrap_or("").to_string(),
                                     extra: HashMap::new(),
+                                    installed_size: None,
+                                    branch_opt: None,
+                                    current: true,
+                                    scope: InstallScope::System,
                                 });
                             }
                             None => {
@@ -277,6 +428,7 @@ impl Packagekit {
                     .handle(),
                 //TODO: fill in more AppInfo fields
                 info: Arc::new(AppInfo {
+                    kind: AppKind::App,
                     source_id: appstream_cache.source_id.clone(),
                     source_name: appstream_cache.source_name.clone(),
                     origin_opt: None,
@@ -286,15 +438,32 @@ impl Packagekit {
                     description,
                     pkgnames,
                     categories: Vec::new(),
-                    desktop_ids: Vec::new(),
+                    keywords: Vec::new(),
+                    launchables: Vec::new(),
                     flatpak_refs: Vec::new(),
+                    flatpak_runtime_opt: None,
                     icons: Vec::new(),
                     releases: Vec::new(),
                     screenshots: Vec::new(),
+                    urls: Vec::new(),
+                    content_rating: None,
+                    relations: Vec::new(),
+                    provides: Vec::new(),
+                    agreements: Vec::new(),
+                    languages: Vec::new(),
+                    suggests: Vec::new(),
+                    eol_opt: None,
+                    eol_rebase_opt: None,
+                    custom: BTreeMap::new(),
+                    prerelease: false,
                     monthly_downloads: 0,
                 }),
                 version: String::new(),
                 extra: HashMap::new(),
+                installed_size: None,
+                branch_opt: None,
+                current: true,
+                scope: InstallScope::System,
             });
         }
         Ok(packages)
@@ -302,18 +471,52 @@ impl Packagekit {
 }
 
 impl Backend for Packagekit {
+    fn version(&self) -> Result<String, Box<dyn Error>> {
+        let pk = PackageKitProxyBlocking::new(&self.connection)?;
+        Ok(format!(
+            "{}.{}.{}",
+            pk.version_major()?,
+            pk.version_minor()?,
+            pk.version_micro()?
+        ))
+    }
+
     fn load_caches(&mut self, refresh: bool) -> Result<(), Box<dyn Error>> {
         if refresh {
-            let tx = self.transaction()?;
-            tx.set_hints(&["interactive=true"])?;
-            //TODO: force refresh?
-            let force = false;
-            tx.refresh_cache(force)?;
+            self.refresh_metadata(Box::new(|_| {}))?;
         }
 
         for appstream_cache in self.appstream_caches.iter_mut() {
             appstream_cache.reload();
         }
+        self.first_seen = update_first_seen(&self.appstream_caches[0]);
+        Ok(())
+    }
+
+    fn metadata_stale(&self) -> bool {
+        // System appstream data has the age of its package, so only missing data is refreshed
+        self.appstream_caches
+            .iter()
+            .any(|appstream_cache| appstream_cache.path_tags.is_empty())
+    }
+
+    fn refresh_metadata(
+        &self,
+        mut callback: Box<dyn FnMut(OperationProgress) + 'static>,
+    ) -> Result<(), Box<dyn Error>> {
+        let tx = self.transaction()?;
+        tx.set_hints(&["interactive=true"])?;
+        //TODO: force refresh?
+        let force = false;
+        tx.refresh_cache(force)?;
+        transaction_handle(tx, |total_percentage, progress| {
+            callback(OperationProgress {
+                // Percentages above 100 mean the progress is unknown
+                percent_opt: (total_percentage <= 100).then_some(total_percentage as f32),
+                status_opt: status_kind(progress.status).or(Some(OperationStatus::Downloading)),
+                bytes_opt: None,
+            });
+        })?;
         Ok(())
     }
 
@@ -333,17 +536,161 @@ impl Backend for Packagekit {
         self.package_transaction(tx)
     }
 
+    fn recent_additions(&self, since: i64) -> Result<Vec<(AppId, i64)>, Box<dyn Error>> {
+        Ok(self
+            .first_seen
+            .iter()
+            .filter(|(_id, timestamp)| **timestamp >= since)
+            .map(|(id, timestamp)| (id.clone(), *timestamp))
+            .collect())
+    }
+
+    fn unused(&self) -> Result<Vec<Package>, Box<dyn Error>> {
+        Err("packagekit backend does not support listing unused packages".into())
+    }
+
     fn file_packages(&self, path: &str) -> Result<Vec<Package>, Box<dyn Error>> {
         let tx = self.transaction()?;
         tx.get_details_local(&[path])?;
         self.package_transaction(tx)
     }
 
+    fn dependents(&self, id: &AppId, info: &AppInfo) -> Result<Vec<String>, Box<dyn Error>> {
+        if id.is_system() {
+            // Dependents of all system packages are not useful
+            return Ok(Vec::new());
+        }
+        let package_names: Vec<_> = info.pkgnames.iter().map(|x| x.as_str()).collect();
+        if package_names.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (_tx_details, tx_packages) = {
+            let tx = self.transaction()?;
+            tx.resolve(FilterKind::Installed as u64, &package_names)?;
+            transaction_handle(tx, |_, _| {})?
+        };
+        let package_ids: Vec<_> = tx_packages
+            .iter()
+            .map(|tx_package| tx_package.package_id.as_str())
+            .collect();
+        if package_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (_tx_details, tx_packages) = {
+            let tx = self.transaction()?;
+            tx.required_by(FilterKind::Installed as u64, &package_ids, false)?;
+            transaction_handle(tx, |_, _| {})?
+        };
+
+        let appstream_cache = &self.appstream_caches[0];
+        let mut dependents = Vec::new();
+        for tx_package in tx_packages {
+            let Some(package_name) = tx_package.package_id.split(';').next() else {
+                continue;
+            };
+            // Show app names for packages that have components
+            match appstream_cache.pkgnames.get(package_name) {
+                Some(ids) => {
+                    for id in ids.iter() {
+                        if let Some(info) = appstream_cache.infos.get(id) {
+                            dependents.push(info.name.clone());
+                        }
+                    }
+                }
+                None => dependents.push(package_name.to_string()),
+            }
+        }
+        dependents.sort_by(|a, b| LANGUAGE_SORTER.compare(a, b));
+        dependents.dedup();
+        Ok(dependents)
+    }
+
+    fn signed(&self, id: &AppId, info: &AppInfo) -> Result<bool, Box<dyn Error>> {
+        if id.is_system() {
+            return Err(
+                "packagekit backend does not check signatures of all system packages".into(),
+            );
+        }
+        let package_names: Vec<_> = info.pkgnames.iter().map(|x| x.as_str()).collect();
+        if package_names.is_empty() {
+            return Err(format!("no packages for {:?}", id).into());
+        }
+        let (_tx_details, tx_packages) = {
+            let tx = self.transaction()?;
+            tx.resolve(FilterKind::Newest as u64, &package_names)?;
+            transaction_handle(tx, |_, _| {})?
+        };
+        // Installed package IDs have installed in their data, instead of only the repository
+        let package_ids: Vec<_> = tx_packages
+            .iter()
+            .map(|tx_package| tx_package.package_id.as_str())
+            .filter(|package_id| {
+                package_id
+                    .rsplit(';')
+                    .next()
+                    .map_or(false, |data| !data.starts_with("installed"))
+            })
+            .collect();
+        if package_ids.is_empty() {
+            return Err(format!("no packages for {:?} found in repositories", id).into());
+        }
+        // Simulating a trusted-only install fails for packages from unsigned repositories
+        let tx = self.transaction()?;
+        tx.install_packages(
+            TransactionFlag::OnlyTrusted as u64
+                | TransactionFlag::Simulate as u64
+                | TransactionFlag::AllowReinstall as u64,
+            &package_ids,
+        )?;
+        match transaction_handle(tx, |_, _| {}) {
+            Ok(_) => Ok(true),
+            Err(err) => match err.downcast_ref::<TransactionError>() {
+                // https://lazka.github.io/pgi-docs/PackageKitGlib-1.0/enums.html#PackageKitGlib.ErrorEnum
+                // GPG failure, bad or missing signature, and unsigned repository errors
+                Some(TransactionError {
+                    code: 5 | 30 | 31 | 50 | 51,
+                    ..
+                }) => Ok(false),
+                _ => Err(err),
+            },
+        }
+    }
+
+    fn runtime_installed(&self, _runtime: &str) -> Result<bool, Box<dyn Error>> {
+        Err("packagekit backend does not support runtimes".into())
+    }
+
+    fn remotes(&self) -> Result<Vec<Remote>, Box<dyn Error>> {
+        Err("packagekit backend does not support listing remotes".into())
+    }
+
+    fn remote_enable(
+        &self,
+        _name: &str,
+        _scope: InstallScope,
+        _enabled: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        Err("packagekit backend does not support enabling remotes".into())
+    }
+
+    fn remote_add(&self, _url: &str, _scope: InstallScope) -> Result<String, Box<dyn Error>> {
+        Err("packagekit backend does not support adding remotes".into())
+    }
+
+    fn switch_branch(&self, _id: &AppId, _branch: &str) -> Result<(), Box<dyn Error>> {
+        Err("packagekit backend does not support switching branches".into())
+    }
+
+    fn plan(&self, _op: &Operation) -> Result<Vec<OperationPlanItem>, Box<dyn Error>> {
+        //TODO: use simulate transaction flag
+        Err("packagekit backend does not support planning operations".into())
+    }
+
     fn operation(
         &self,
         op: &Operation,
-        mut f: Box<dyn FnMut(f32) + 'static>,
-    ) -> Result<(), Box<dyn Error>> {
+        mut f: Box<dyn FnMut(OperationProgress) + 'static>,
+    ) -> Result<Option<OperationRestart>, Box<dyn Error>> {
         let mut package_names = Vec::new();
         for info in op.infos.iter() {
             for pkgname in &info.pkgnames {
@@ -390,16 +737,27 @@ impl Backend for Packagekit {
                 tx.update_packages(TransactionFlag::OnlyTrusted as u64, &package_ids)?;
             }
         }
-        let _tx_packages = transaction_handle(tx, |total_percentage, progress| {
-            log::info!(
-                "{}%: {} {} {}%",
-                total_percentage,
-                progress.package_id,
-                progress.status,
-                progress.percentage
-            );
-            f(total_percentage as f32);
-        })?;
-        Ok(())
+        let (_tx_details, _tx_packages, restart_opt) =
+            transaction_handle_restart(tx, |total_percentage, progress| {
+                log::info!(
+                    "{}%: {} {} {}%",
+                    total_percentage,
+                    progress.package_id,
+                    progress.status,
+                    progress.percentage
+                );
+                //TODO: report bytes using the DownloadSizeRemaining property?
+                f(OperationProgress {
+                    // PackageKit uses 101 for unknown percentage
+                    percent_opt: if total_percentage <= 100 {
+                        Some(total_percentage as f32)
+                    } else {
+                        None
+                    },
+                    status_opt: status_kind(progress.status),
+                    bytes_opt: None,
+                });
+            })?;
+        Ok(restart_opt)
     }
 }
@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use crate::{
+    backend::{self, Backend, Backends},
+    config::InstallScope,
+    instance, AppId, AppInfo, Operation, OperationKind, OperationProgress, OperationRestart,
+};
+
+/// Find backend, info, and installation for an app ID, using installed packages when uninstalling
+fn find(
+    backends: &Backends,
+    kind: OperationKind,
+    id: &AppId,
+    install_scope: InstallScope,
+) -> Option<(&'static str, Arc<dyn Backend>, Arc<AppInfo>, InstallScope)> {
+    for (backend_name, backend) in backends.iter() {
+        match kind {
+            OperationKind::Uninstall => match backend.installed() {
+                Ok(packages) => {
+                    for package in packages {
+                        if &package.id == id {
+                            return Some((
+                                *backend_name,
+                                backend.clone(),
+                                package.info,
+                                package.scope,
+                            ));
+                        }
+                    }
+                }
+                Err(err) => {
+                    log::warn!("failed to list installed from {}: {}", backend_name, err);
+                }
+            },
+            _ => {
+                for appstream_cache in backend.info_caches() {
+                    if let Some(info) = appstream_cache.infos.get(id) {
+                        return Some((*backend_name, backend.clone(), info.clone(), install_scope));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Run operation on app IDs without the user interface, returning the exit code
+pub fn operation(kind: OperationKind, ids: &[String], install_scope: InstallScope) -> i32 {
+    //TODO: translate
+    let verb = match kind {
+        OperationKind::Install => "install",
+        OperationKind::Uninstall => "uninstall",
+        OperationKind::Update => "update",
+    };
+
+    if ids.is_empty() {
+        eprintln!("usage: cosmic-store {} <app id>...", verb);
+        return 2;
+    }
+
+    // Held until operations complete
+    let _lock_opt = match instance::lock() {
+        Ok(Some(lock)) => Some(lock),
+        Ok(None) => {
+            eprintln!(
+                "warning: another instance of cosmic-store is running, operations may conflict"
+            );
+            None
+        }
+        Err(err) => {
+            log::warn!("failed to lock instance: {}", err);
+            None
+        }
+    };
+
+    let locale = sys_locale::get_locale().unwrap_or_else(|| {
+        log::warn!("failed to get system locale, falling back to en-US");
+        String::from("en-US")
+    });
+    let (backends, errors) = backend::backends(&locale, false);
+    for (backend_name, err) in errors {
+        eprintln!("failed to load {} backend: {}", backend_name, err);
+    }
+
+    let mut exit_code = 0;
+    for id_raw in ids {
+        let id = AppId::new(id_raw);
+        let (backend_name, backend, info, scope) = match find(&backends, kind, &id, install_scope) {
+            Some(some) => some,
+            None => {
+                eprintln!("failed to {} {}: not found", verb, id);
+                exit_code = 1;
+                continue;
+            }
+        };
+
+        println!("{} {} using {}", verb, id, backend_name);
+        let op = Operation {
+            kind,
+            backend_name,
+            package_ids: vec![id.clone()],
+            infos: vec![info],
+            scope,
+        };
+        let progress_id = id.clone();
+        match backend.operation(
+            &op,
+            Box::new(move |progress: OperationProgress| match progress.text() {
+                Some(text) => println!("{}: {}", progress_id, text),
+                None => {
+                    if let Some(percent) = progress.percent_opt {
+                        println!("{}: {}%", progress_id, percent.round());
+                    }
+                }
+            }),
+        ) {
+            Ok(restart_opt) => {
+                println!("{} {} complete", verb, id);
+                match restart_opt {
+                    Some(OperationRestart::Session) => {
+                        println!("log out and back in for changes to take effect")
+                    }
+                    Some(OperationRestart::System) => {
+                        println!("restart the system for changes to take effect")
+                    }
+                    None => {}
+                }
+            }
+            Err(err) => {
+                eprintln!("failed to {} {}: {}", verb, id, err);
+                exit_code = 1;
+            }
+        }
+    }
+    exit_code
+}
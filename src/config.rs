@@ -2,9 +2,12 @@
 
 use cosmic::{
     cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry},
-    theme,
+    cosmic_theme, theme,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::ExplorePage;
 
 pub const CONFIG_VERSION: u64 = 1;
 
@@ -25,15 +28,113 @@ impl AppTheme {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Density {
+    Comfortable,
+    Compact,
+}
+
+impl Density {
+    /// Scale theme spacing for density
+    pub fn spacing(&self, mut spacing: cosmic_theme::Spacing) -> cosmic_theme::Spacing {
+        match self {
+            Self::Comfortable => {}
+            Self::Compact => {
+                spacing.space_xxs /= 2;
+                spacing.space_xs /= 2;
+                spacing.space_s /= 2;
+                spacing.space_m /= 2;
+            }
+        }
+        spacing
+    }
+}
+
+/// Backend preferred when an app is available from multiple backends
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PreferredBackend {
+    Flatpak,
+    System,
+}
+
+impl PreferredBackend {
+    pub fn backend_name(&self) -> &'static str {
+        match self {
+            Self::Flatpak => "flatpak",
+            Self::System => "packagekit",
+        }
+    }
+}
+
+/// Where new apps are installed, for backends that support per-user installs
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum InstallScope {
+    User,
+    System,
+}
+
 #[derive(Clone, CosmicConfigEntry, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Config {
     pub app_theme: AppTheme,
+    /// Days within which apps are badged as new or updated, or None to hide badges
+    pub badge_days: Option<u32>,
+    pub density: Density,
+    /// Show tools for packagers, like viewing the parsed metadata of apps
+    pub developer_mode: bool,
+    pub disabled_backends: BTreeSet<String>,
+    /// Explore section shown before the others, or None to use the default order
+    pub explore_first: Option<ExplorePage>,
+    /// Explore sections that are not shown
+    pub explore_hidden: BTreeSet<ExplorePage>,
+    /// Normalized IDs of favorite apps, in the order they were added
+    pub favorites: Vec<String>,
+    /// Normalized IDs of apps held back from update all
+    pub held: BTreeSet<String>,
+    /// Hide installed apps from search, category, and explore results
+    pub hide_installed: bool,
+    pub install_scope: InstallScope,
+    /// Maximum number of columns in result grids, or None to fit as many as possible
+    pub max_grid_columns: Option<usize>,
+    /// Hide category pages in the nav bar, except for the active one
+    pub nav_categories_collapsed: bool,
+    /// Pause background downloads, like screenshots and update checks, on metered connections
+    pub pause_metered: bool,
+    pub preferred_backend: PreferredBackend,
+    /// Fetch the first screenshot of a result when it is hovered, so details open faster
+    pub prefetch_screenshots: bool,
+    /// Show beta and development versions of apps that are not installed
+    pub prerelease: bool,
+    /// Proxy URL used for network requests, or None to use proxy environment variables
+    pub proxy: Option<String>,
+    /// Normalized IDs of recently viewed apps, most recent first
+    pub recently_viewed: Vec<String>,
+    /// Check for updates periodically and notify when the window is not focused
+    pub update_notifications: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             app_theme: AppTheme::System,
+            badge_days: Some(14),
+            density: Density::Comfortable,
+            developer_mode: false,
+            disabled_backends: BTreeSet::new(),
+            explore_first: None,
+            explore_hidden: BTreeSet::new(),
+            favorites: Vec::new(),
+            held: BTreeSet::new(),
+            hide_installed: false,
+            install_scope: InstallScope::User,
+            max_grid_columns: None,
+            nav_categories_collapsed: false,
+            pause_metered: true,
+            preferred_backend: PreferredBackend::Flatpak,
+            prefetch_screenshots: true,
+            prerelease: false,
+            proxy: None,
+            recently_viewed: Vec::new(),
+            update_notifications: true,
         }
     }
 }
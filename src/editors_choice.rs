@@ -1,3 +1,5 @@
+use std::{fs, sync::OnceLock, time::Instant};
+
 pub const EDITORS_CHOICE: &'static [&'static str] = &[
     "com.slack.Slack",
     "org.telegram",
@@ -11,3 +13,58 @@ pub const EDITORS_CHOICE: &'static [&'static str] = &[
     "org.signal.Signal",
     "org.chromium.Chromium",
 ];
+
+/// Name of curation file, searched for in XDG data directories with the cosmic-store prefix
+const CURATION_FILE: &'static str = "editors_choice.json";
+
+static CURATION: OnceLock<Vec<String>> = OnceLock::new();
+
+fn load_curation() -> Option<Vec<String>> {
+    let start = Instant::now();
+    let xdg_dirs = match xdg::BaseDirectories::with_prefix("cosmic-store") {
+        Ok(ok) => ok,
+        Err(err) => {
+            log::warn!("failed to find cosmic-store xdg directories: {}", err);
+            return None;
+        }
+    };
+    let path = xdg_dirs.find_data_file(CURATION_FILE)?;
+    let data = match fs::read(&path) {
+        Ok(ok) => ok,
+        Err(err) => {
+            log::warn!(
+                "failed to read editor's choice curation {:?}: {}",
+                path,
+                err
+            );
+            return None;
+        }
+    };
+    // The curation is a JSON array of app IDs
+    match serde_json::from_slice::<Vec<String>>(&data) {
+        Ok(ok) => {
+            let elapsed = start.elapsed();
+            log::info!(
+                "loaded editor's choice curation {:?} in {:?}",
+                path,
+                elapsed
+            );
+            Some(ok)
+        }
+        Err(err) => {
+            log::warn!(
+                "failed to parse editor's choice curation {:?}: {}",
+                path,
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Editor's choice app IDs, from a curation file if found or the built in list otherwise
+pub fn editors_choice() -> &'static [String] {
+    CURATION.get_or_init(|| {
+        load_curation().unwrap_or_else(|| EDITORS_CHOICE.iter().map(|x| x.to_string()).collect())
+    })
+}
@@ -1,11 +1,18 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use cosmic::widget::icon;
+use sha2::{Digest, Sha256};
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
+    path::PathBuf,
     sync::{Mutex, OnceLock},
 };
 
+use crate::AppId;
+
+// Maximum number of app icons to cache before clearing
+const APP_ICON_CACHE_SIZE: usize = 4096;
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct IconCacheKey {
     name: &'static str,
@@ -69,3 +76,81 @@ pub fn icon_cache_handle(name: &'static str, size: u16) -> icon::Handle {
 pub fn icon_cache_icon(name: &'static str, size: u16) -> icon::Icon {
     icon::icon(icon_cache_handle(name, size)).size(size)
 }
+
+static APP_ICON_CACHE: OnceLock<Mutex<HashMap<(String, AppId), icon::Handle>>> = OnceLock::new();
+
+pub fn app_icon_cache_clear() {
+    if let Some(app_icon_cache) = APP_ICON_CACHE.get() {
+        app_icon_cache.lock().unwrap().clear();
+    }
+}
+
+/// Remove an app icon from cache, so it is looked up again
+pub fn app_icon_cache_remove(source_id: &str, id: &AppId) {
+    if let Some(app_icon_cache) = APP_ICON_CACHE.get() {
+        app_icon_cache
+            .lock()
+            .unwrap()
+            .remove(&(source_id.to_string(), id.clone()));
+    }
+}
+
+/// Get app icon from cache, calling f to look it up if not found
+pub fn app_icon_cache_handle<F: FnOnce() -> icon::Handle>(
+    source_id: &str,
+    id: &AppId,
+    f: F,
+) -> icon::Handle {
+    let app_icon_cache = APP_ICON_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    let key = (source_id.to_string(), id.clone());
+    if let Some(handle) = app_icon_cache.lock().unwrap().get(&key) {
+        return handle.clone();
+    }
+    // Look up without holding the lock, as this is called from parallel searches
+    let handle = f();
+    let mut app_icon_cache = app_icon_cache.lock().unwrap();
+    if app_icon_cache.len() >= APP_ICON_CACHE_SIZE {
+        app_icon_cache.clear();
+    }
+    app_icon_cache.insert(key, handle.clone());
+    handle
+}
+
+/// Path where a remote icon is cached on disk, named by a hash of the URL that is stable across
+/// releases
+pub fn remote_icon_path(url: &str) -> Option<PathBuf> {
+    let hash = Sha256::digest(url.as_bytes());
+    dirs::cache_dir().map(|x| {
+        x.join("cosmic-store")
+            .join("icons")
+            .join(format!("{:x}", hash))
+    })
+}
+
+static REMOTE_ICON_REQUESTS: OnceLock<Mutex<BTreeSet<String>>> = OnceLock::new();
+
+/// Request a remote icon to be fetched, as it is not yet cached on disk
+pub fn remote_icon_request(url: &str) {
+    let remote_icon_requests = REMOTE_ICON_REQUESTS.get_or_init(|| Mutex::new(BTreeSet::new()));
+    remote_icon_requests.lock().unwrap().insert(url.to_string());
+}
+
+/// Remote icons waiting to be fetched
+pub fn remote_icon_requests() -> Vec<String> {
+    match REMOTE_ICON_REQUESTS.get() {
+        Some(remote_icon_requests) => remote_icon_requests
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Remove a remote icon request after it was fetched or failed
+pub fn remote_icon_request_done(url: &str) {
+    if let Some(remote_icon_requests) = REMOTE_ICON_REQUESTS.get() {
+        remote_icon_requests.lock().unwrap().remove(url);
+    }
+}
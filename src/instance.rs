@@ -0,0 +1,23 @@
+use std::{env, fs, io, os::unix::io::AsRawFd};
+
+/// Take the lock held by processes that run operations, returning None if another instance
+/// holds it. The lock is released when the file is closed, including when the process exits
+pub fn lock() -> io::Result<Option<fs::File>> {
+    let path = dirs::runtime_dir()
+        .unwrap_or_else(env::temp_dir)
+        .join("cosmic-store.lock");
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        return Ok(Some(file));
+    }
+    let err = io::Error::last_os_error();
+    if err.kind() == io::ErrorKind::WouldBlock {
+        Ok(None)
+    } else {
+        Err(err)
+    }
+}
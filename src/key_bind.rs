@@ -1,4 +1,4 @@
-use cosmic::iced::keyboard::{Key, Modifiers};
+use cosmic::iced::keyboard::{key::Named, Key, Modifiers};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt};
 
@@ -46,10 +46,10 @@ pub fn key_binds() -> HashMap<KeyBind, Action> {
     let mut key_binds = HashMap::new();
 
     macro_rules! bind {
-        ([$($modifier:ident),+ $(,)?], $key:expr, $action:ident) => {{
+        ([$($modifier:ident),* $(,)?], $key:expr, $action:ident) => {{
             key_binds.insert(
                 KeyBind {
-                    modifiers: vec![$(Modifier::$modifier),+],
+                    modifiers: vec![$(Modifier::$modifier),*],
                     key: $key,
                 },
                 Action::$action,
@@ -57,7 +57,12 @@ pub fn key_binds() -> HashMap<KeyBind, Action> {
         }};
     }
 
+    bind!([], Key::Named(Named::Backspace), Back);
+    bind!([], Key::Named(Named::F1), About);
+    bind!([Alt], Key::Named(Named::ArrowLeft), Back);
     bind!([Ctrl], Key::Character("f".into()), SearchActivate);
+    bind!([Ctrl], Key::Character("r".into()), Refresh);
+    bind!([Ctrl], Key::Character(",".into()), Settings);
 
     key_binds
 }
@@ -27,14 +27,23 @@ lazy_static::lazy_static! {
 }
 
 lazy_static::lazy_static! {
+    // Collation follows the system locale, which may differ from the loaded translation, so
+    // that names in scripts like Cyrillic or CJK sort as the user expects
     pub static ref LANGUAGE_SORTER: Collator = {
     let mut options = CollatorOptions::new();
     options.numeric = Some(Numeric::On);
 
-    DataLocale::from_str(&LANGUAGE_LOADER.current_language().to_string())
-            .or_else(|_| DataLocale::from_str(&LANGUAGE_LOADER.fallback_language().to_string()))
-            .ok()
+    sys_locale::get_locale()
+            .and_then(|locale| DataLocale::from_str(&locale).ok())
             .and_then(|locale| Collator::try_new(&locale, options).ok())
+            .or_else(|| {
+                DataLocale::from_str(&LANGUAGE_LOADER.current_language().to_string())
+                    .or_else(|_| {
+                        DataLocale::from_str(&LANGUAGE_LOADER.fallback_language().to_string())
+                    })
+                    .ok()
+                    .and_then(|locale| Collator::try_new(&locale, options).ok())
+            })
             .or_else(|| {
                 let locale = DataLocale::from_str("en-US").expect("en-US is a valid BCP-47 tag");
                 Collator::try_new(&locale, options).ok()
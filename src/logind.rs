@@ -1,4 +1,4 @@
-use std::os::fd::OwnedFd;
+use std::{error::Error, os::fd::OwnedFd};
 
 #[cfg(feature = "logind")]
 pub async fn inhibit() -> zbus::Result<Vec<OwnedFd>> {
@@ -27,3 +27,40 @@ pub async fn inhibit() -> zbus::Result<Vec<OwnedFd>> {
 pub async fn inhibit() -> zbus::Result<Vec<OwnedFd>> {
     Vec::new()
 }
+
+/// Reboot the system, asking for authorization if needed
+#[cfg(feature = "logind")]
+pub async fn reboot() -> Result<(), Box<dyn Error + Send + Sync>> {
+    use logind_zbus::manager::ManagerProxy;
+
+    let connection = zbus::Connection::system().await?;
+    let manager = ManagerProxy::new(&connection).await?;
+    let interactive = true;
+    let () = manager.inner().call("Reboot", &(interactive,)).await?;
+    Ok(())
+}
+
+#[cfg(not(feature = "logind"))]
+pub async fn reboot() -> Result<(), Box<dyn Error + Send + Sync>> {
+    Err("rebooting requires the logind feature".into())
+}
+
+/// End the current session, which returns to the login screen
+#[cfg(feature = "logind")]
+pub async fn log_out() -> Result<(), Box<dyn Error + Send + Sync>> {
+    use logind_zbus::manager::ManagerProxy;
+
+    let session_id = std::env::var("XDG_SESSION_ID")?;
+    let connection = zbus::Connection::system().await?;
+    let manager = ManagerProxy::new(&connection).await?;
+    let () = manager
+        .inner()
+        .call("TerminateSession", &(session_id,))
+        .await?;
+    Ok(())
+}
+
+#[cfg(not(feature = "logind"))]
+pub async fn log_out() -> Result<(), Box<dyn Error + Send + Sync>> {
+    Err("logging out requires the logind feature".into())
+}
@@ -6,51 +6,72 @@ use cosmic::{
     cosmic_config::{self, CosmicConfigEntry},
     cosmic_theme, executor,
     iced::{
+        clipboard,
         event::{self, Event},
         futures::{self, SinkExt},
-        keyboard::{Event as KeyEvent, Key, Modifiers},
+        keyboard::{key::Named, Event as KeyEvent, Key, Modifiers},
         subscription::{self, Subscription},
+        time,
         widget::scrollable,
         window::{self, Event as WindowEvent},
-        Alignment, Length, Limits, Size,
+        Alignment, ContentFit, Length, Limits, Size,
     },
     prelude::CollectionWidget,
-    theme, widget, Application, ApplicationExt, Element,
+    theme,
+    widget::{
+        self,
+        toaster::{Toast, ToastId, Toasts},
+    },
+    Application, ApplicationExt, Element,
 };
 use localize::LANGUAGE_SORTER;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::{
     any::TypeId,
     cmp,
-    collections::{BTreeMap, HashMap, VecDeque},
-    env,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    env, fmt, fs,
     future::pending,
+    io::{self, Write},
+    ops::Range,
+    path::PathBuf,
     process,
-    sync::{Arc, Mutex},
-    time::Instant,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
 };
 
 use app_id::AppId;
 mod app_id;
 
-use app_info::{AppIcon, AppInfo};
+use app_info::{
+    AppAgreementKind, AppIcon, AppInfo, AppKind, AppLanguage, AppLaunchable, AppProvide,
+    AppRelation, AppRelationItem, AppRelationKind, AppScreenshot, AppUrl,
+};
 mod app_info;
 
 use appstream_cache::AppstreamCache;
 mod appstream_cache;
 
-use backend::{Backends, Package};
+use backend::{BackendErrors, Backends, Package, Remote};
 mod backend;
 
-use config::{AppTheme, Config, CONFIG_VERSION};
+mod cli;
+
+use config::{AppTheme, Config, Density, InstallScope, PreferredBackend, CONFIG_VERSION};
 mod config;
 
-use editors_choice::EDITORS_CHOICE;
+use editors_choice::editors_choice;
 mod editors_choice;
 
-use icon_cache::{icon_cache_handle, icon_cache_icon};
+use icon_cache::{
+    app_icon_cache_clear, app_icon_cache_handle, app_icon_cache_remove, icon_cache_handle,
+    icon_cache_icon, remote_icon_path, remote_icon_request_done, remote_icon_requests,
+};
 mod icon_cache;
 
+mod instance;
+
 use key_bind::{key_binds, KeyBind};
 mod key_bind;
 
@@ -58,7 +79,12 @@ mod localize;
 
 mod logind;
 
-use operation::{Operation, OperationKind};
+mod network;
+
+use operation::{
+    Operation, OperationKind, OperationPlanItem, OperationProgress, OperationRestart,
+    OperationStatus,
+};
 mod operation;
 
 use priority::priority;
@@ -66,9 +92,59 @@ mod priority;
 
 mod stats;
 
+const REPOSITORY: &str = "https://github.com/pop-os/cosmic-store";
+const LICENSE_URL: &str = "https://www.gnu.org/licenses/gpl-3.0.html";
+// Number of editor's choice apps featured on the explore page
+const FEATURED_COUNT: usize = 5;
+// Number of remote icons fetched at once, the rest wait until one is done
+const REMOTE_ICON_FETCHES_MAX: usize = 4;
+// Number of operations that run at once, the rest are queued
+const OPERATIONS_RUNNING_MAX: usize = 1;
+// How long an uninstall can be undone from its toast
+const UNDO_DURATION: Duration = Duration::from_secs(15);
+// How often to check for updates in the background
+const UPDATES_CHECK_INTERVAL: Duration = Duration::from_secs(4 * 60 * 60);
+// Number of recently viewed apps to remember
+const RECENTLY_VIEWED_COUNT: usize = 12;
+// Number of days an app is shown as new in the distribution
+const NEW_IN_DISTRO_DAYS: i64 = 30;
+// Number of apps searched in each batch of streamed search results
+const SEARCH_BATCH_SIZE: usize = 2048;
+// Number of app names suggested while typing a search
+const SEARCH_SUGGESTIONS: usize = 5;
+// Milliseconds to wait for typing to pause before suggesting app names
+const SEARCH_SUGGEST_DELAY: u64 = 150;
+// Minimum number of search results in a category to offer browsing it
+const SEARCH_CATEGORY_MIN: usize = 3;
+// Maximum number of lines and characters of a description shown before it is expanded
+const DESCRIPTION_COLLAPSED_LINES: usize = 6;
+const DESCRIPTION_COLLAPSED_LEN: usize = 480;
+// Number of similar apps shown on the details page
+const SIMILAR_COUNT: usize = 6;
+// Minimum number of similar apps to show the similar apps section
+const SIMILAR_MIN: usize = 3;
+// Largest height of screenshots on the details page, also used while they load
+const SCREENSHOT_HEIGHT_MAX: f32 = 480.0;
+// Screenshots with a larger width or height are not shown
+const SCREENSHOT_DIMENSION_MAX: u32 = 8192;
+// Number of attempts to fetch a screenshot before showing a retry button
+const SCREENSHOT_ATTEMPTS: u32 = 3;
+// Milliseconds a result must be hovered before its first screenshot is prefetched
+const SCREENSHOT_PREFETCH_DELAY: u64 = 300;
+// Maximum number of prefetched screenshots to keep before clearing
+const SCREENSHOT_PREFETCH_CACHE_SIZE: usize = 32;
+// Largest column limit offered for result grids
+const GRID_COLUMNS_MAX: usize = 6;
+// Days offered for showing new and updated badges on results
+const BADGE_DAYS: [u32; 3] = [7, 14, 30];
+// Desktop IDs of flatpak permission editors, in order of preference, which take an app ID argument
+const PERMISSION_EDITORS: &[&str] = &["com.github.tchx84.Flatseal"];
 const ICON_SIZE_SEARCH: u16 = 48;
 const ICON_SIZE_PACKAGE: u16 = 64;
 const ICON_SIZE_DETAILS: u16 = 128;
+const ICON_SIZE_FAVORITE: u16 = 16;
+// Width of new, updated, and beta badges on result cards
+const BADGE_WIDTH: u16 = 64;
 const MAX_GRID_WIDTH: f32 = 1600.0;
 
 /// Runs application with these settings
@@ -78,9 +154,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     localize::localize();
 
-    //TODO: more advanced argument parsing
-    let subcommand_opt = env::args().nth(1);
-
     let (config_handler, config) = match cosmic_config::Config::new(App::APP_ID, CONFIG_VERSION) {
         Ok(config_handler) => {
             let config = match Config::get_entry(&config_handler) {
@@ -98,6 +171,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    //TODO: more advanced argument parsing
+    let subcommand_opt = env::args().nth(1);
+
+    // Install and uninstall subcommands run without the user interface
+    let headless_opt = match subcommand_opt.as_deref() {
+        Some("install") => Some(OperationKind::Install),
+        Some("uninstall") => Some(OperationKind::Uninstall),
+        _ => None,
+    };
+    if let Some(kind) = headless_opt {
+        let ids: Vec<String> = env::args().skip(2).collect();
+        // Apps are installed where the user interface would install them
+        process::exit(cli::operation(kind, &ids, config.install_scope));
+    }
+
     let mut settings = Settings::default();
     settings = settings.theme(config.app_theme.theme());
     settings = settings.size_limits(Limits::NONE.min_width(360.0).min_height(180.0));
@@ -115,13 +203,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Action {
+    About,
+    Back,
+    Refresh,
     SearchActivate,
+    Settings,
 }
 
 impl Action {
     pub fn message(&self) -> Message {
         match self {
+            Self::About => Message::ToggleContextPage(ContextPage::About, String::new()),
+            Self::Back => Message::SelectNone,
+            Self::Refresh => Message::Refresh,
             Self::SearchActivate => Message::SearchActivate,
+            Self::Settings => Message::ToggleContextPage(ContextPage::Settings, String::new()),
         }
     }
 }
@@ -155,51 +251,139 @@ impl CosmicFlags for Flags {
 #[derive(Clone, Debug)]
 pub enum Message {
     AppTheme(AppTheme),
-    Backends(Backends),
+    BackendEnabled(&'static str, bool),
+    BackendErrorsDismiss,
+    BackendVersions(Vec<(&'static str, String)>),
+    Backends(Backends, BackendErrors),
+    BadgeDays(Option<u32>),
+    BranchSwitched,
+    BrowseCategory(Category),
     CategoryResults(&'static [Category], Vec<SearchResult>),
     CheckUpdates,
     Config(Config),
+    Density(Density),
+    DeveloperMode(bool),
     DialogCancel,
     DialogConfirm,
     DialogPage(DialogPage),
+    ExploreFirst(Option<ExplorePage>),
     ExplorePage(Option<ExplorePage>),
     ExploreResults(ExplorePage, Vec<SearchResult>),
+    ExploreShown(ExplorePage, bool),
+    ExportInstalled,
+    FeaturedImage(String, Vec<u8>),
+    FeaturedNext,
+    FeaturedShown(usize),
+    GridColumns(Option<usize>),
+    HideInstalled(bool),
+    ImportInstalled,
+    InstallScope(InstallScope),
     Installed(Vec<(&'static str, Package)>),
     InstalledResults(Vec<SearchResult>),
+    InstalledSelect(usize, bool),
+    InstalledSortSize(bool),
     Key(Modifiers, Key),
+    KindFilter(AppKind),
     MaybeExit,
+    MetadataCopy,
+    MetadataProgress(&'static str, OperationProgress),
+    MetadataRefreshed(&'static str, Result<(), String>),
+    Metered(bool),
+    NavCategoriesCollapsed(bool),
     Notification(Arc<Mutex<notify_rust::NotificationHandle>>),
-    OpenDesktopId(String),
+    OpenLaunchable(AppLaunchable),
+    OpenPermissions(String, String),
+    OpenUrl(String),
     Operation(OperationKind, &'static str, AppId, Arc<AppInfo>),
-    PendingComplete(u64),
+    OperationQueueCancel(u64),
+    OperationQueueMove(u64, isize),
+    PauseMetered(bool),
+    PendingComplete(u64, Option<OperationRestart>),
     PendingError(u64, String),
-    PendingProgress(u64, f32),
+    PendingProgress(u64, OperationProgress),
+    PreferredBackend(PreferredBackend),
+    PrefetchScreenshot(String, bool),
+    PrefetchScreenshots(bool),
+    PrefetchedScreenshot(String, Vec<u8>),
+    Prerelease(bool),
+    ProgressPulse,
+    ProxyInput(String),
+    ProxySubmit,
+    RecentlyViewed(AppId),
+    RecentlyViewedClear,
+    Refresh,
+    RemoteAdd,
+    RemoteChanged(bool),
+    RemoteEnabled(String, InstallScope, bool),
+    RemoteIcon(String, bool),
+    RemoteInput(String),
+    Remotes(Vec<Remote>),
+    RemoveUnused,
+    RestartLater,
+    RestartNow,
+    ReviewPlan(Vec<OperationPlanItem>),
+    ReviewSuggest(usize, bool),
+    ScreenshotFullscreen(bool),
     ScrollView(scrollable::Viewport),
     SearchActivate,
     SearchClear,
     SearchInput(String),
+    SearchInstalled(bool),
     SearchResults(String, Vec<SearchResult>),
+    SearchResultsBatch(u64, String, usize, Vec<SearchResult>),
+    SearchResultsDone(u64),
     SearchSubmit,
+    SearchSuggestion(usize),
+    SearchSuggestions(String, Vec<(AppId, String)>),
     Select(&'static str, AppId, widget::icon::Handle, Arc<AppInfo>),
+    SelectId(AppId),
     SelectInstalled(usize),
     SelectUpdates(usize),
     SelectNone,
     SelectCategoryResult(usize),
     SelectExploreResult(ExplorePage, usize),
+    SelectSimilarResult(usize),
     SelectSearchResult(usize),
+    SelectedDependents(AppId, Vec<String>),
+    SelectedDescriptionExpanded(bool),
+    SelectedInstallScope(InstallScope),
+    SelectedLanguagesFilter(String),
+    SelectedLanguagesShown(bool),
+    SelectedPermissionEditor(AppId, String),
+    SelectedRuntimeInstalled(AppId, bool),
     SelectedScreenshot(usize, String, Vec<u8>),
+    SelectedScreenshotFailed(usize, String),
+    SelectedScreenshotRetry(usize),
     SelectedScreenshotShown(usize),
+    SelectedSigned(AppId, bool),
+    SelectedSimilar(AppId, Vec<SearchResult>),
     SelectedSource(usize),
+    SelectedUpdateSize(AppId, u64),
+    ShowToast(String),
+    SwitchBranch(&'static str, AppId, String),
     SystemThemeModeChange(cosmic_theme::ThemeMode),
+    ToastDismiss(ToastId),
+    ToastUndo(ToastId, Operation),
     ToggleContextPage(ContextPage, String),
+    ToggleFavorite(AppId),
+    ToggleHeld(AppId),
+    UninstallSelected,
+    Unused(Vec<(&'static str, Package)>),
     UpdateAll,
+    UpdateNotifications(bool),
     Updates(Vec<(&'static str, Package)>),
+    UpdatesNotificationActivated,
     WindowClose,
+    WindowFocused(bool),
     WindowNew,
+    WindowResized(u32, u32),
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ContextPage {
+    About,
+    Metadata,
+    Operations,
     ReleaseNotes(usize),
     Settings,
 }
@@ -207,6 +391,9 @@ pub enum ContextPage {
 impl ContextPage {
     fn title(&self, app_name: String) -> String {
         match self {
+            Self::About => fl!("about"),
+            Self::Metadata => fl!("metadata"),
+            Self::Operations => fl!("operations"),
             Self::ReleaseNotes(_) => app_name,
             Self::Settings => fl!("settings"),
         }
@@ -215,18 +402,29 @@ impl ContextPage {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DialogPage {
+    /// Agreement of an app by index, which must be accepted to run an install operation if set,
+    /// along with the index of the app in the operation
+    Agreement(Arc<AppInfo>, usize, Option<(Operation, usize)>),
     FailedOperation(u64),
+    RemoteDisable(String, InstallScope),
+    RemoveUnused,
+    Review(Vec<Operation>),
     Uninstall(&'static str, AppId, Arc<AppInfo>),
+    UpdateAll,
+    UpdateAllSummary(usize, usize),
 }
 
 // From https://specifications.freedesktop.org/menu-spec/latest/apa.html
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Category {
     AudioVideo,
+    BoardGame,
+    CardGame,
     Development,
     Education,
     Game,
     Graphics,
+    LogicGame,
     Network,
     Office,
     Science,
@@ -236,13 +434,53 @@ pub enum Category {
 }
 
 impl Category {
+    fn all() -> &'static [Self] {
+        &[
+            Self::AudioVideo,
+            Self::BoardGame,
+            Self::CardGame,
+            Self::Development,
+            Self::Education,
+            Self::Game,
+            Self::Graphics,
+            Self::LogicGame,
+            Self::Network,
+            Self::Office,
+            Self::Science,
+            Self::Settings,
+            Self::System,
+            Self::Utility,
+        ]
+    }
+
+    /// Navigation page for browsing this category, preferring pages with only this category
+    fn nav_page(&self) -> Option<NavPage> {
+        let pages = NavPage::all();
+        pages
+            .iter()
+            .find(|page| {
+                page.categories()
+                    .map_or(false, |categories| categories == [*self])
+            })
+            .or_else(|| {
+                pages.iter().find(|page| {
+                    page.categories()
+                        .map_or(false, |categories| categories.contains(self))
+                })
+            })
+            .copied()
+    }
+
     fn id(&self) -> &'static str {
         match self {
             Self::AudioVideo => "AudioVideo",
+            Self::BoardGame => "BoardGame",
+            Self::CardGame => "CardGame",
             Self::Development => "Development",
             Self::Education => "Education",
             Self::Game => "Game",
             Self::Graphics => "Graphics",
+            Self::LogicGame => "LogicGame",
             Self::Network => "Network",
             Self::Office => "Office",
             Self::Science => "Science",
@@ -253,6 +491,27 @@ impl Category {
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NavSection {
+    Discover,
+    Categories,
+    Library,
+}
+
+impl NavSection {
+    fn all() -> &'static [Self] {
+        &[Self::Discover, Self::Categories, Self::Library]
+    }
+
+    fn title(&self) -> String {
+        match self {
+            Self::Discover => fl!("discover"),
+            Self::Categories => fl!("categories"),
+            Self::Library => fl!("library"),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Default, Debug, Eq, PartialEq)]
 pub enum NavPage {
     #[default]
@@ -286,6 +545,14 @@ impl NavPage {
         ]
     }
 
+    fn section(&self) -> NavSection {
+        match self {
+            Self::Explore => NavSection::Discover,
+            Self::Installed | Self::Updates => NavSection::Library,
+            _ => NavSection::Categories,
+        }
+    }
+
     fn title(&self) -> String {
         match self {
             Self::Explore => fl!("explore"),
@@ -310,7 +577,13 @@ impl NavPage {
             Self::Develop => Some(&[Category::Development]),
             Self::Learn => Some(&[Category::Education]),
             Self::Game => Some(&[Category::Game]),
-            Self::Relax => Some(&[Category::AudioVideo]),
+            // Casual games are included with media for relaxing
+            Self::Relax => Some(&[
+                Category::AudioVideo,
+                Category::BoardGame,
+                Category::CardGame,
+                Category::LogicGame,
+            ]),
             Self::Socialize => Some(&[Category::Network]),
             Self::Utilities => Some(&[Category::Settings, Category::System, Category::Utility]),
             _ => None,
@@ -334,11 +607,14 @@ impl NavPage {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub enum ExplorePage {
+    RecentlyViewed,
+    Favorites,
     EditorsChoice,
     PopularApps,
     NewApps,
+    NewInDistro,
     RecentlyUpdated,
     DevelopmentTools,
     ScientificTools,
@@ -354,9 +630,12 @@ pub enum ExplorePage {
 impl ExplorePage {
     fn all() -> &'static [Self] {
         &[
+            Self::RecentlyViewed,
+            Self::Favorites,
             Self::EditorsChoice,
             Self::PopularApps,
             Self::NewApps,
+            Self::NewInDistro,
             Self::RecentlyUpdated,
             Self::DevelopmentTools,
             Self::ScientificTools,
@@ -372,9 +651,12 @@ impl ExplorePage {
 
     fn title(&self) -> String {
         match self {
+            Self::RecentlyViewed => fl!("recently-viewed"),
+            Self::Favorites => fl!("favorites"),
             Self::EditorsChoice => fl!("editors-choice"),
             Self::PopularApps => fl!("popular-apps"),
             Self::NewApps => fl!("new-apps"),
+            Self::NewInDistro => fl!("new-in-distro"),
             Self::RecentlyUpdated => fl!("recently-updated"),
             Self::DevelopmentTools => fl!("development-tools"),
             Self::ScientificTools => fl!("scientific-tools"),
@@ -404,6 +686,181 @@ impl ExplorePage {
     }
 }
 
+fn agreement_title(kind: AppAgreementKind) -> String {
+    match kind {
+        AppAgreementKind::Generic => fl!("agreement"),
+        AppAgreementKind::Eula => fl!("agreement-eula"),
+        AppAgreementKind::Privacy => fl!("agreement-privacy"),
+    }
+}
+
+fn app_kind_title(kind: AppKind) -> String {
+    match kind {
+        AppKind::App => fl!("kind-apps"),
+        AppKind::Addon => fl!("kind-addons"),
+        AppKind::Font => fl!("kind-fonts"),
+    }
+}
+
+fn backend_title(backend_name: &str) -> String {
+    match backend_name {
+        "flatpak" => fl!("backend-flatpak"),
+        "packagekit" => fl!("backend-packagekit"),
+        other => other.to_string(),
+    }
+}
+
+/// Path of the exported installed app list, with one backend and ID per line
+fn installed_list_path() -> Option<PathBuf> {
+    dirs::document_dir()
+        .or_else(dirs::home_dir)
+        .map(|x| x.join("cosmic-store-installed.txt"))
+}
+
+/// HTTP client for network requests, using the configured proxy if set
+///
+/// Without a configured proxy, reqwest uses HTTP_PROXY, HTTPS_PROXY, and NO_PROXY from the environment
+fn http_client(proxy_opt: Option<&str>) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy) = proxy_opt {
+        match reqwest::Proxy::all(proxy) {
+            Ok(ok) => {
+                builder = builder.proxy(ok);
+            }
+            Err(err) => {
+                log::warn!("failed to parse proxy {:?}: {}", proxy, err);
+            }
+        }
+    }
+    match builder.build() {
+        Ok(ok) => ok,
+        Err(err) => {
+            log::warn!("failed to build HTTP client: {}", err);
+            reqwest::Client::new()
+        }
+    }
+}
+
+/// Total system memory in MiB, read from /proc/meminfo
+fn system_memory_mib() -> Option<u64> {
+    static MEMORY: OnceLock<Option<u64>> = OnceLock::new();
+    *MEMORY.get_or_init(|| {
+        let meminfo = match fs::read_to_string("/proc/meminfo") {
+            Ok(ok) => ok,
+            Err(err) => {
+                log::warn!("failed to read /proc/meminfo: {}", err);
+                return None;
+            }
+        };
+        meminfo.lines().find_map(|line| {
+            let kib = line.strip_prefix("MemTotal:")?.trim().strip_suffix("kB")?;
+            kib.trim().parse::<u64>().ok().map(|kib| kib / 1024)
+        })
+    })
+}
+
+/// Returns a warning if the system does not meet a relation, or None if it does or is unknown
+///
+/// The display is at least as large as the shortest side of the largest window seen, so display
+/// length relations are only shown when that is smaller than required
+fn relation_warning(relation: &AppRelation, window_length_max: u32) -> Option<String> {
+    match relation.item {
+        AppRelationItem::DisplayLength(required) => {
+            if window_length_max >= required {
+                return None;
+            }
+            Some(match relation.kind {
+                AppRelationKind::Recommends => fl!("recommends-display", length = required),
+                AppRelationKind::Requires => fl!("requires-display", length = required),
+            })
+        }
+        AppRelationItem::Memory(required) => {
+            let available = system_memory_mib()?;
+            if available >= required {
+                return None;
+            }
+            let required = format_size(required * 1024 * 1024);
+            Some(match relation.kind {
+                AppRelationKind::Recommends => fl!("recommends-memory", memory = required),
+                AppRelationKind::Requires => fl!("requires-memory", memory = required),
+            })
+        }
+    }
+}
+
+/// Look up details of the selected app in the background, logging failures with a description
+/// of what was looked up
+fn selected_task<T, E>(
+    id: &AppId,
+    description: &'static str,
+    task: impl FnOnce(&AppId) -> Result<T, E> + Send + 'static,
+    message: impl FnOnce(AppId, T) -> Message + Send + 'static,
+) -> Command<Message>
+where
+    T: Send + 'static,
+    E: fmt::Display,
+{
+    let id = id.clone();
+    Command::perform(
+        async move {
+            tokio::task::spawn_blocking(move || match task(&id) {
+                Ok(value) => message::app(message(id, value)),
+                Err(err) => {
+                    log::info!("failed to {} of {:?}: {}", description, id, err);
+                    message::none()
+                }
+            })
+            .await
+            .unwrap_or(message::none())
+        },
+        |x| x,
+    )
+}
+
+/// Format a size in bytes using decimal units
+fn format_size(size: u64) -> String {
+    const UNITS: &[&str] = &["B", "kB", "MB", "GB", "TB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit + 1 < UNITS.len() {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", size, UNITS[unit])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Find the date and time locale for a language tag like en-US, or de when only the language
+/// is known
+fn chrono_locale(locale: &str) -> chrono::Locale {
+    let posix = locale.split('.').next().unwrap_or(locale).replace('-', "_");
+    chrono::Locale::try_from(posix.as_str())
+        .or_else(|_| {
+            let language = posix.split('_').next().unwrap_or_default();
+            chrono::Locale::try_from(format!("{}_{}", language, language.to_uppercase()).as_str())
+        })
+        .unwrap_or(chrono::Locale::POSIX)
+}
+
+/// Format a timestamp relative to now, using the date format in the locale if older than a month
+fn format_timestamp(timestamp: i64, date_format: &str, locale: &str) -> Option<String> {
+    let utc = chrono::DateTime::<chrono::Utc>::from_timestamp(timestamp, 0)?;
+    let local = chrono::DateTime::<chrono::Local>::from(utc);
+    let days = (chrono::Local::now().date_naive() - local.date_naive()).num_days();
+    Some(match days {
+        0 => fl!("today"),
+        1 => fl!("yesterday"),
+        2..=6 => fl!("days-ago", days = days),
+        7..=30 => fl!("weeks-ago", weeks = days / 7),
+        _ => local
+            .format_localized(date_format, chrono_locale(locale))
+            .to_string(),
+    })
+}
+
 pub struct GridMetrics {
     pub cols: usize,
     pub item_width: usize,
@@ -411,12 +868,20 @@ pub struct GridMetrics {
 }
 
 impl GridMetrics {
-    pub fn new(width: usize, min_width: usize, column_spacing: u16) -> Self {
+    pub fn new(
+        width: usize,
+        min_width: usize,
+        column_spacing: u16,
+        max_cols_opt: Option<usize>,
+    ) -> Self {
         let width_m1 = width.checked_sub(min_width).unwrap_or(0);
-        let cols_m1 = width_m1 / (min_width + column_spacing as usize);
-        let cols = cols_m1 + 1;
+        let mut cols = width_m1 / (min_width + column_spacing as usize) + 1;
+        if let Some(max_cols) = max_cols_opt {
+            // Items are widened to fill the width when columns are limited
+            cols = cols.min(max_cols.max(1));
+        }
         let item_width = width
-            .checked_sub(cols_m1 * column_spacing as usize)
+            .checked_sub((cols - 1) * column_spacing as usize)
             .unwrap_or(0)
             .checked_div(cols)
             .unwrap_or(0);
@@ -428,6 +893,374 @@ impl GridMetrics {
     }
 }
 
+/// Truncate text to a number of characters, adding an ellipsis if truncated
+fn ellipsize(text: &str, max_chars: usize) -> Option<String> {
+    //TODO: use text shaping to measure width, wide characters are counted as two
+    let mut width = 0;
+    for (i, c) in text.char_indices() {
+        width += if c.len_utf8() >= 3 { 2 } else { 1 };
+        if width > max_chars {
+            let mut truncated = text[..i].trim_end().to_string();
+            truncated.push('…');
+            return Some(truncated);
+        }
+    }
+    None
+}
+
+/// Card text that is truncated to fit with a tooltip showing the full text, and with an optional
+/// byte range of the text highlighted
+fn card_text<'a>(
+    text: &'a str,
+    match_opt: Option<Range<usize>>,
+    caption: bool,
+    height: f32,
+    text_width: usize,
+) -> Element<'a, Message> {
+    // Approximate characters that fit, captions have two lines of smaller text
+    let max_chars = if caption {
+        2 * text_width / 7
+    } else {
+        text_width / 8
+    };
+    let truncated_opt = ellipsize(text, max_chars);
+    let shown = truncated_opt.as_deref().unwrap_or(text);
+    let text_widget = |x: String| {
+        if caption {
+            widget::text::caption(x)
+        } else {
+            widget::text::body(x)
+        }
+    };
+    // Highlighted parts are separate widgets that cannot wrap together, so captions are only
+    // highlighted if they fit on one line
+    let highlight_opt = match_opt.filter(|range| {
+        let shown_len = match truncated_opt {
+            Some(_) => shown.len() - '…'.len_utf8(),
+            None => shown.len(),
+        };
+        range.start < range.end
+            && range.end <= shown_len
+            && (!caption || shown.chars().count() <= max_chars / 2)
+    });
+    let element: Element<'a, Message> = match highlight_opt {
+        Some(range) => widget::row::with_children(vec![
+            text_widget(shown[..range.start].to_string()).into(),
+            text_widget(shown[range.clone()].to_string())
+                .style(theme::Text::Accent)
+                .into(),
+            text_widget(shown[range.end..].to_string()).into(),
+        ])
+        .height(Length::Fixed(height))
+        .into(),
+        None => text_widget(shown.to_string())
+            .height(Length::Fixed(height))
+            .into(),
+    };
+    match truncated_opt {
+        Some(_) => widget::tooltip(element, text, widget::tooltip::Position::Bottom).into(),
+        None => element,
+    }
+}
+
+/// Find the command of a desktop entry by its desktop ID
+fn desktop_exec(desktop_id: &str) -> Option<String> {
+    let mut desktop_id = desktop_id.to_string();
+    if !desktop_id.ends_with(".desktop") {
+        desktop_id.push_str(".desktop");
+    }
+    let xdg_dirs = match xdg::BaseDirectories::with_prefix("applications") {
+        Ok(ok) => ok,
+        Err(err) => {
+            log::warn!("failed to find applications xdg directories: {}", err);
+            return None;
+        }
+    };
+    let path = match xdg_dirs.find_data_file(&desktop_id) {
+        Some(some) => some,
+        None => {
+            // Not a warning, as this is also used to check if apps are installed
+            log::debug!("failed to find desktop file for {:?}", desktop_id);
+            return None;
+        }
+    };
+    let entry = match freedesktop_entry_parser::parse_entry(&path) {
+        Ok(ok) => ok,
+        Err(err) => {
+            log::warn!("failed to read desktop file {:?}: {}", path, err);
+            return None;
+        }
+    };
+    //TODO: handlne Terminal=true
+    match entry.section("Desktop Entry").attr("Exec") {
+        Some(some) => Some(some.to_string()),
+        None => {
+            log::warn!("no exec section in {:?}", path);
+            None
+        }
+    }
+}
+
+/// Regexes matching a search phrase, and each of its words if there are multiple words
+fn search_regexes(input: &str) -> Option<(regex::Regex, Vec<regex::Regex>)> {
+    let pattern = regex::escape(input);
+    let regex = match regex::RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .build()
+    {
+        Ok(ok) => ok,
+        Err(err) => {
+            log::warn!("failed to parse regex {:?}: {}", pattern, err);
+            return None;
+        }
+    };
+    let mut word_regexes = Vec::new();
+    let words: Vec<_> = input.split_whitespace().collect();
+    if words.len() > 1 {
+        for word in words {
+            let word_pattern = regex::escape(word);
+            match regex::RegexBuilder::new(&word_pattern)
+                .case_insensitive(true)
+                .build()
+            {
+                Ok(ok) => word_regexes.push(ok),
+                Err(err) => {
+                    log::warn!("failed to parse regex {:?}: {}", word_pattern, err);
+                    return None;
+                }
+            }
+        }
+    }
+    Some((regex, word_regexes))
+}
+
+/// Weight of an app matching a search, lower is better, or None if it does not match
+fn search_weight(
+    info: &AppInfo,
+    regex: &regex::Regex,
+    word_regexes: &[regex::Regex],
+) -> Option<i64> {
+    //TODO: improve performance
+    let stats_weight = |weight: i64| {
+        //TODO: make sure no overflows
+        (weight << 56) - (info.monthly_downloads as i64)
+    };
+    //TODO: fuzzy match (nucleus-matcher?)
+    match regex.find(&info.name) {
+        Some(mat) => {
+            if mat.range().start == 0 {
+                if mat.range().end == info.name.len() {
+                    // Name equals search phrase
+                    Some(stats_weight(0))
+                } else {
+                    // Name starts with search phrase
+                    Some(stats_weight(1))
+                }
+            } else {
+                // Name contains search phrase
+                Some(stats_weight(2))
+            }
+        }
+        None => match regex.find(&info.summary) {
+            Some(mat) => {
+                if mat.range().start == 0 {
+                    if mat.range().end == info.summary.len() {
+                        // Summary equals search phrase
+                        Some(stats_weight(3))
+                    } else {
+                        // Summary starts with search phrase
+                        Some(stats_weight(4))
+                    }
+                } else {
+                    // Summary contains search phrase
+                    Some(stats_weight(5))
+                }
+            }
+            None => {
+                let mut keyword_weight = None;
+                for keyword in info.keywords.iter() {
+                    if let Some(mat) = regex.find(keyword) {
+                        if mat.range().start == 0 && mat.range().end == keyword.len() {
+                            // Keyword equals search phrase
+                            keyword_weight = Some(6);
+                            break;
+                        } else {
+                            // Keyword contains search phrase
+                            keyword_weight = Some(7);
+                        }
+                    }
+                }
+                match keyword_weight {
+                    Some(weight) => Some(stats_weight(weight)),
+                    None => match regex.find(&info.description) {
+                        Some(mat) => {
+                            if mat.range().start == 0 {
+                                if mat.range().end == info.description.len() {
+                                    // Description equals search phrase
+                                    Some(stats_weight(8))
+                                } else {
+                                    // Description starts with search phrase
+                                    Some(stats_weight(9))
+                                }
+                            } else {
+                                // Description contains search phrase
+                                Some(stats_weight(10))
+                            }
+                        }
+                        None => {
+                            if !word_regexes.is_empty()
+                                && word_regexes
+                                    .iter()
+                                    .all(|word_regex| word_regex.is_match(&info.description))
+                            {
+                                // Description contains all search words
+                                Some(stats_weight(11))
+                            } else {
+                                None
+                            }
+                        }
+                    },
+                }
+            }
+        },
+    }
+}
+
+/// Icon button with a tooltip, using the same label as its name for screen readers
+fn icon_button<'a>(
+    icon: widget::icon::Icon,
+    label: String,
+    on_press_opt: Option<Message>,
+) -> Element<'a, Message> {
+    let mut button = widget::button(icon)
+        .name(label.clone())
+        .padding(theme::active().cosmic().space_xxs())
+        .style(theme::Button::Icon);
+    if let Some(on_press) = on_press_opt {
+        button = button.on_press(on_press);
+    }
+    widget::tooltip(button, label, widget::tooltip::Position::Bottom).into()
+}
+
+/// Parsed metadata of a selected app, formatted for packagers to check
+fn metadata_text(selected: &Selected) -> String {
+    format!(
+        "backend: {}\nid: {}\n{:#?}",
+        selected.backend_name,
+        selected.id.raw(),
+        selected.info
+    )
+}
+
+/// If two items are the same package, which requires matching backends, sources, and IDs, as
+/// the same ID can be provided by both system and flatpak versions of an app
+fn same_package(
+    (backend_name, source_id, id): (&str, &str, &AppId),
+    (other_backend_name, other_source_id, other_id): (&str, &str, &AppId),
+) -> bool {
+    backend_name == other_backend_name && source_id == other_source_id && id == other_id
+}
+
+/// Check that the packages of an operation are available to install in the appstream caches of
+/// its backend, or are in its installed or updatable packages if those are loaded
+fn operation_packages_validate(
+    op: &Operation,
+    info_caches: &[AppstreamCache],
+    packages_opt: Option<&[(&'static str, Package)]>,
+) -> Result<(), String> {
+    for (package_id, info) in op.package_ids.iter().zip(op.infos.iter()) {
+        let found = match op.kind {
+            OperationKind::Install => info_caches.iter().any(|appstream_cache| {
+                appstream_cache.source_id == info.source_id
+                    && appstream_cache.infos.contains_key(package_id)
+            }),
+            // Packages that are not loaded yet cannot be checked
+            OperationKind::Uninstall | OperationKind::Update => {
+                packages_opt.map_or(true, |packages| {
+                    packages.iter().any(|(backend_name, package)| {
+                        same_package(
+                            (backend_name, &package.info.source_id, &package.id),
+                            (op.backend_name, &info.source_id, package_id),
+                        )
+                    })
+                })
+            }
+        };
+        if !found {
+            return Err(fl!("package-not-found", name = info.name.as_str()));
+        }
+    }
+    Ok(())
+}
+
+/// Image handle and dimensions of a screenshot, or None if it is not an image or is too large to
+/// show
+fn screenshot_image(
+    url: &str,
+    data: Vec<u8>,
+) -> Option<(widget::image::Handle, Option<(u32, u32)>)> {
+    // Only the header is read to find dimensions, not the whole image
+    let (width, height) = match image::io::Reader::new(io::Cursor::new(&data))
+        .with_guessed_format()
+        .map_err(|err| err.to_string())
+        .and_then(|reader| reader.into_dimensions().map_err(|err| err.to_string()))
+    {
+        Ok((width, height)) if width > 0 && height > 0 => (width, height),
+        Ok((width, height)) => {
+            log::warn!("screenshot {} is empty: {}x{}", url, width, height);
+            return None;
+        }
+        Err(err) => {
+            log::warn!("failed to read screenshot {}: {}", url, err);
+            return None;
+        }
+    };
+    if width > SCREENSHOT_DIMENSION_MAX || height > SCREENSHOT_DIMENSION_MAX {
+        log::warn!("screenshot {} is too large: {}x{}", url, width, height);
+        return None;
+    }
+    Some((
+        widget::image::Handle::from_memory(data),
+        Some((width, height)),
+    ))
+}
+
+/// Badge for apps first released or updated within a number of days
+fn release_badge(info: &AppInfo, days: u32) -> Option<String> {
+    let now = chrono::Utc::now().timestamp();
+    let since = now - i64::from(days) * 24 * 60 * 60;
+    let timestamps = info
+        .releases
+        .iter()
+        .filter_map(|release| release.timestamp)
+        .filter(|timestamp| *timestamp <= now);
+    let first = timestamps.clone().min()?;
+    let last = timestamps.max()?;
+    if first >= since {
+        Some(fl!("badge-new"))
+    } else if last >= since {
+        Some(fl!("badge-updated"))
+    } else {
+        None
+    }
+}
+
+/// Star button that adds or removes an app from favorites
+fn favorite_button<'a>(id: &AppId, favorite: bool) -> Element<'a, Message> {
+    let (icon_name, label) = if favorite {
+        ("starred-symbolic", fl!("remove-favorite"))
+    } else {
+        ("non-starred-symbolic", fl!("add-favorite"))
+    };
+    icon_button(
+        widget::icon::from_name(icon_name)
+            .size(ICON_SIZE_FAVORITE)
+            .icon(),
+        label,
+        Some(Message::ToggleFavorite(id.clone())),
+    )
+}
+
 fn package_card_view<'a>(
     info: &'a AppInfo,
     icon: &'a widget::icon::Handle,
@@ -441,15 +1274,15 @@ fn package_card_view<'a>(
         .as_deref()
         .map(|elements| 1 + elements.len())
         .unwrap_or_default();
+    //TODO: measure top controls instead of reserving space for them
+    let text_width = width
+        .saturating_sub(3 * spacing.space_s as usize + ICON_SIZE_PACKAGE as usize)
+        .saturating_sub(if top_controls.is_some() { 48 } else { 0 });
     let column = widget::column::with_children(vec![
         widget::row::with_capacity(top_row_cap)
             .push(widget::column::with_children(vec![
-                widget::text::body(&info.name)
-                    .height(Length::Fixed(20.0))
-                    .into(),
-                widget::text::caption(&info.summary)
-                    .height(Length::Fixed(28.0))
-                    .into(),
+                card_text(&info.name, None, false, 20.0, text_width),
+                card_text(&info.summary, None, true, 28.0, text_width),
             ]))
             .push_maybe(
                 top_controls
@@ -482,56 +1315,207 @@ fn package_card_view<'a>(
     .into()
 }
 
-impl Package {
-    pub fn grid_metrics(spacing: &cosmic_theme::Spacing, width: usize) -> GridMetrics {
-        GridMetrics::new(width, 320 + 2 * spacing.space_s as usize, spacing.space_xxs)
-    }
-
-    pub fn card_view<'a>(
-        &'a self,
-        controls: Vec<Element<'a, Message>>,
-        top_controls: Option<Vec<Element<'a, Message>>>,
-        spacing: &cosmic_theme::Spacing,
-        width: usize,
-    ) -> Element<'a, Message> {
-        package_card_view(
-            &self.info,
-            &self.icon,
-            controls,
-            top_controls,
-            spacing,
-            width,
-        )
-    }
+fn empty_view<'a>(text: String, spacing: &cosmic_theme::Spacing) -> Element<'a, Message> {
+    widget::container(
+        widget::column::with_children(vec![
+            widget::icon::from_name("system-search-symbolic")
+                .size(64)
+                .into(),
+            widget::text::body(text).into(),
+        ])
+        .align_items(Alignment::Center)
+        .spacing(spacing.space_xxs),
+    )
+    .padding(spacing.space_l)
+    .width(Length::Fill)
+    .center_x()
+    .into()
 }
 
-#[derive(Clone, Debug)]
-pub struct SearchResult {
-    backend_name: &'static str,
-    id: AppId,
-    icon: widget::icon::Handle,
-    // Info from selected source
-    info: Arc<AppInfo>,
-    weight: i64,
+lazy_static::lazy_static! {
+    static ref URL_REGEX: regex::Regex =
+        regex::Regex::new(r#"https?://[^\s<>"]+"#).expect("URL regex is valid");
 }
 
-impl SearchResult {
-    pub fn grid_metrics(spacing: &cosmic_theme::Spacing, width: usize) -> GridMetrics {
-        GridMetrics::new(width, 240 + 2 * spacing.space_s as usize, spacing.space_xxs)
+/// Start of a long description, cut at a line end, or None if it is short enough to show fully
+fn description_collapsed(description: &str) -> Option<&str> {
+    let description = description.trim_end();
+    let mut end = 0;
+    for (line_i, line) in description.split_inclusive('\n').enumerate() {
+        // The first line is always shown, even if it is long
+        if line_i > 0
+            && (line_i >= DESCRIPTION_COLLAPSED_LINES
+                || end + line.len() > DESCRIPTION_COLLAPSED_LEN)
+        {
+            return Some(description[..end].trim_end());
+        }
+        end += line.len();
     }
+    None
+}
 
-    pub fn grid_view<'a, F: Fn(usize) -> Message + 'a>(
+/// Apps with names starting with or containing the input, for suggestions while typing a search
+fn search_suggestions(apps: &Apps, input: &str) -> Vec<(AppId, String)> {
+    let input = input.to_lowercase();
+    let mut matches: Vec<_> = apps
+        .iter()
+        .filter_map(|(id, entries)| {
+            let info = &entries.first()?.info;
+            let position = info.name.to_lowercase().find(&input)?;
+            Some((
+                position != 0,
+                cmp::Reverse(info.monthly_downloads),
+                info,
+                id,
+            ))
+        })
+        .collect();
+    // Names starting with the input are first, then the most downloaded
+    matches.sort_by(|a, b| match (a.0, a.1).cmp(&(b.0, b.1)) {
+        cmp::Ordering::Equal => LANGUAGE_SORTER.compare(&a.2.name, &b.2.name),
+        ordering => ordering,
+    });
+    matches
+        .into_iter()
+        .take(SEARCH_SUGGESTIONS)
+        .map(|(_, _, info, id)| (id.clone(), info.name.clone()))
+        .collect()
+}
+
+/// Placeholder shown while backends or results are loading
+fn loading_view<'a>() -> Element<'a, Message> {
+    widget::text::body(fl!("loading")).into()
+}
+
+fn description_view<'a>(
+    description: &'a str,
+    spacing: &cosmic_theme::Spacing,
+) -> Element<'a, Message> {
+    let mut column = widget::column::with_capacity(1).spacing(spacing.space_xxs);
+    let mut text_start = 0;
+    let mut text_end = 0;
+    let mut line_start = 0;
+    for line in description.split_inclusive('\n') {
+        let line_end = line_start + line.len();
+        let urls: Vec<&str> = URL_REGEX
+            .find_iter(line)
+            .map(|mat| {
+                mat.as_str()
+                    .trim_end_matches(&['.', ',', ';', ':', ')'][..])
+            })
+            .collect();
+        if urls.is_empty() {
+            text_end = line_end;
+            line_start = line_end;
+            continue;
+        }
+
+        // Flush text before this line, then show the line followed by its links
+        if text_end > text_start {
+            column = column.push(widget::text::body(
+                description[text_start..text_end].trim_end(),
+            ));
+        }
+        column = column.push(widget::text::body(line.trim_end()));
+        column = column.push(
+            widget::row::with_children(
+                urls.into_iter()
+                    .map(|url| {
+                        widget::button::link(url)
+                            .on_press(Message::OpenUrl(url.to_string()))
+                            .into()
+                    })
+                    .collect(),
+            )
+            .spacing(spacing.space_xs),
+        );
+        text_start = line_end;
+        text_end = line_end;
+        line_start = line_end;
+    }
+    if text_end > text_start {
+        column = column.push(widget::text::body(
+            description[text_start..text_end].trim_end(),
+        ));
+    }
+    column.into()
+}
+
+impl Package {
+    pub fn grid_metrics(
+        spacing: &cosmic_theme::Spacing,
+        width: usize,
+        max_cols_opt: Option<usize>,
+    ) -> GridMetrics {
+        GridMetrics::new(
+            width,
+            320 + 2 * spacing.space_s as usize,
+            spacing.space_xxs,
+            max_cols_opt,
+        )
+    }
+
+    pub fn card_view<'a>(
+        &'a self,
+        controls: Vec<Element<'a, Message>>,
+        top_controls: Option<Vec<Element<'a, Message>>>,
+        spacing: &cosmic_theme::Spacing,
+        width: usize,
+    ) -> Element<'a, Message> {
+        package_card_view(
+            &self.info,
+            &self.icon,
+            controls,
+            top_controls,
+            spacing,
+            width,
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchResult {
+    backend_name: &'static str,
+    id: AppId,
+    icon: widget::icon::Handle,
+    // Info from selected source
+    info: Arc<AppInfo>,
+    weight: i64,
+    /// Byte ranges of the search phrase in the name and summary, for highlighting
+    name_match_opt: Option<Range<usize>>,
+    summary_match_opt: Option<Range<usize>>,
+}
+
+impl SearchResult {
+    pub fn grid_metrics(
+        spacing: &cosmic_theme::Spacing,
+        width: usize,
+        max_cols_opt: Option<usize>,
+    ) -> GridMetrics {
+        GridMetrics::new(
+            width,
+            240 + 2 * spacing.space_s as usize,
+            spacing.space_xxs,
+            max_cols_opt,
+        )
+    }
+
+    pub fn grid_view<'a, F: Fn(usize) -> Message + 'a>(
         results: &'a [Self],
+        favorites: &[String],
         spacing: cosmic_theme::Spacing,
         width: usize,
+        max_cols_opt: Option<usize>,
+        badge_days_opt: Option<u32>,
         callback: F,
     ) -> Element<'a, Message> {
         let GridMetrics {
             cols,
             item_width,
             column_spacing,
-        } = Self::grid_metrics(&spacing, width);
+        } = Self::grid_metrics(&spacing, width, max_cols_opt);
 
+        let is_dark = theme::active().cosmic().is_dark;
         let mut grid = widget::grid();
         let mut col = 0;
         for (result_i, result) in results.iter().enumerate() {
@@ -539,10 +1523,25 @@ impl SearchResult {
                 grid = grid.insert_row();
                 col = 0;
             }
-            grid = grid.push(
-                widget::mouse_area(result.card_view(&spacing, item_width))
-                    .on_press(callback(result_i)),
-            );
+            let mut mouse_area = widget::mouse_area(result.card_view(
+                favorites.iter().any(|x| x == result.id.normalized()),
+                // Pre-releases are always labeled, even when release badges are off
+                if result.info.prerelease {
+                    Some(fl!("badge-beta"))
+                } else {
+                    badge_days_opt.and_then(|days| release_badge(&result.info, days))
+                },
+                &spacing,
+                item_width,
+            ))
+            .on_press(callback(result_i));
+            // Hovering a result prefetches its first screenshot
+            if let Some((_, screenshot)) = result.info.themed_screenshots(is_dark).first() {
+                mouse_area = mouse_area
+                    .on_enter(Message::PrefetchScreenshot(screenshot.url.clone(), true))
+                    .on_exit(Message::PrefetchScreenshot(screenshot.url.clone(), false));
+            }
+            grid = grid.push(mouse_area);
             col += 1;
         }
         grid.column_spacing(column_spacing)
@@ -552,24 +1551,48 @@ impl SearchResult {
 
     pub fn card_view<'a>(
         &'a self,
+        favorite: bool,
+        badge_opt: Option<String>,
         spacing: &cosmic_theme::Spacing,
         width: usize,
     ) -> Element<'a, Message> {
+        let mut text_width = width.saturating_sub(
+            4 * spacing.space_s as usize + ICON_SIZE_SEARCH as usize + ICON_SIZE_FAVORITE as usize,
+        );
+        // Text is narrowed by the fixed width of the badge and its spacing
+        let badge_opt: Option<Element<Message>> = badge_opt.map(|badge| {
+            text_width = text_width.saturating_sub(BADGE_WIDTH as usize + spacing.space_s as usize);
+            widget::container(widget::text::caption(badge))
+                .width(Length::Fixed(BADGE_WIDTH.into()))
+                .center_x()
+                .into()
+        });
         widget::container(
             widget::row::with_children(vec![
                 widget::icon::icon(self.icon.clone())
                     .size(ICON_SIZE_SEARCH)
                     .into(),
                 widget::column::with_children(vec![
-                    widget::text::body(&self.info.name)
-                        .height(Length::Fixed(20.0))
-                        .into(),
-                    widget::text::caption(&self.info.summary)
-                        .height(Length::Fixed(28.0))
-                        .into(),
+                    card_text(
+                        &self.info.name,
+                        self.name_match_opt.clone(),
+                        false,
+                        20.0,
+                        text_width,
+                    ),
+                    card_text(
+                        &self.info.summary,
+                        self.summary_match_opt.clone(),
+                        true,
+                        28.0,
+                        text_width,
+                    ),
                 ])
+                .width(Length::Fill)
                 .into(),
             ])
+            .push_maybe(badge_opt)
+            .push(favorite_button(&self.id, favorite))
             .align_items(Alignment::Center)
             .spacing(spacing.space_s),
         )
@@ -607,6 +1630,7 @@ pub struct SelectedSource {
     backend_name: &'static str,
     source_id: String,
     source_name: String,
+    verified: bool,
 }
 
 impl SelectedSource {
@@ -619,6 +1643,7 @@ impl SelectedSource {
             } else {
                 info.source_name.clone()
             },
+            verified: info.verified(),
         }
     }
 }
@@ -636,9 +1661,54 @@ pub struct Selected {
     id: AppId,
     icon: widget::icon::Handle,
     info: Arc<AppInfo>,
-    screenshot_images: HashMap<usize, widget::image::Handle>,
+    /// Names of installed items that depend on this one
+    dependents: Vec<String>,
+    /// If the source of this item verifies signatures, None if unknown
+    signed_opt: Option<bool>,
+    /// Show the full description instead of its start
+    description_expanded: bool,
+    /// If the flatpak runtime of this item is installed, None if unknown
+    runtime_installed_opt: Option<bool>,
+    /// Download size of an available update
+    update_size_opt: Option<u64>,
+    /// Show the list of translations, filtered by locale
+    languages_shown: bool,
+    languages_filter: String,
+    /// Desktop ID of an installed flatpak permission editor
+    permission_editor_opt: Option<String>,
+    /// Apps sharing categories and keywords with this one
+    similar: Vec<SearchResult>,
+    screenshot_failed: HashSet<usize>,
+    /// Loaded screenshots, with their width and height if they could be read
+    screenshot_images: HashMap<usize, (widget::image::Handle, Option<(u32, u32)>)>,
+    /// Number of manual retries for each screenshot, used to restart its subscription
+    screenshot_retries: HashMap<usize, usize>,
     screenshot_shown: usize,
+    /// Show the current screenshot zoomed to fill the window
+    screenshot_fullscreen: bool,
     sources: Vec<SelectedSource>,
+    /// Where this app will be installed, defaulting to the configured scope
+    install_scope: InstallScope,
+}
+
+/// Update all run, with one operation per backend run one at a time
+#[derive(Clone, Debug)]
+pub struct UpdateAll {
+    queue: VecDeque<Operation>,
+    running_opt: Option<u64>,
+    total: usize,
+    updated: usize,
+    failed: usize,
+}
+
+/// Optional app suggested by an app being installed, offered in the review dialog
+#[derive(Clone, Debug)]
+pub struct ReviewSuggest {
+    backend_name: &'static str,
+    id: AppId,
+    info: Arc<AppInfo>,
+    scope: InstallScope,
+    checked: bool,
 }
 
 /// The [`App`] stores application-specific state.
@@ -649,8 +1719,35 @@ pub struct App {
     config: Config,
     locale: String,
     app_themes: Vec<String>,
+    densities: Vec<String>,
+    grid_columns: Vec<String>,
+    badge_days: Vec<String>,
+    explore_firsts: Vec<String>,
+    preferred_backends: Vec<String>,
+    install_scopes: Vec<String>,
+    app_kinds: Vec<String>,
+    /// Shared client for network requests, rebuilt when the proxy changes
+    http_client: reqwest::Client,
+    proxy_input: String,
     apps: Arc<Apps>,
     backends: Backends,
+    backend_errors: BackendErrors,
+    /// Backends have loaded at least once, until then pages would show as empty
+    backends_loaded: bool,
+    backend_versions: Vec<(&'static str, String)>,
+    /// Progress of backends refreshing stale appstream data before it is shown
+    metadata_refresh: BTreeMap<&'static str, OperationProgress>,
+    /// Stale appstream data is only refreshed automatically once per session
+    metadata_refreshed: bool,
+    /// If the network connection is metered
+    metered: bool,
+    /// Lock showing that this instance runs operations, None if another instance holds it
+    instance_lock_opt: Option<fs::File>,
+    /// If another instance held the lock when it was last taken
+    instance_running: bool,
+    /// Flatpak remotes, loaded when settings are shown
+    remotes: Vec<Remote>,
+    remote_input: String,
     context_page: ContextPage,
     dialog_pages: VecDeque<DialogPage>,
     explore_page_opt: Option<ExplorePage>,
@@ -658,66 +1755,89 @@ pub struct App {
     nav_model: widget::nav_bar::Model,
     notification_opt: Option<Arc<Mutex<notify_rust::NotificationHandle>>>,
     pending_operation_id: u64,
-    pending_operations: BTreeMap<u64, (Operation, f32)>,
+    pending_operations: BTreeMap<u64, (Operation, OperationProgress)>,
+    /// IDs of pending operations waiting to run, in the order they will run
+    operation_queue: VecDeque<u64>,
+    /// Animated value for progress bars of operations without a known percentage
+    progress_pulse: f32,
+    /// Strongest restart requested by completed operations, until dismissed
+    restart_opt: Option<OperationRestart>,
     failed_operations: BTreeMap<u64, (Operation, String)>,
+    refreshing: bool,
     scrollable_id: widget::Id,
     scroll_views: HashMap<ScrollContext, scrollable::Viewport>,
     search_active: bool,
     search_id: widget::Id,
     search_input: String,
+    /// Search only installed apps, available when the installed page is active
+    search_installed: bool,
+    /// Incremented for each streamed search, so results of cancelled searches are ignored
+    search_generation: u64,
+    /// Input of the search being streamed by the search subscription
+    search_stream_opt: Option<String>,
+    /// Input waiting for suggestions, which are found after typing pauses
+    search_suggest_opt: Option<String>,
+    /// App IDs and names suggested for the search input
+    search_suggestions: Vec<(AppId, String)>,
+    /// Suggestion highlighted with the arrow keys, accepted with enter
+    search_suggestion_opt: Option<usize>,
     window_id_opt: Option<window::Id>,
+    window_focused: bool,
+    /// Largest shortest side of the window seen, in logical pixels
+    window_length_max: u32,
     //TODO: use hashset?
     installed: Option<Vec<(&'static str, Package)>>,
     //TODO: use hashset?
     updates: Option<Vec<(&'static str, Package)>>,
+    /// Updates that a notification was last shown for, to avoid repeating it
+    updates_notified: BTreeSet<(&'static str, String, String)>,
+    /// Updates notification still being shown, closed before showing a new one
+    updates_notification_handle: Arc<Mutex<Option<notify_rust::NotificationHandle>>>,
+    update_all_opt: Option<UpdateAll>,
+    review_plan_opt: Option<Vec<OperationPlanItem>>,
+    review_suggests: Vec<ReviewSuggest>,
+    unused: Option<Vec<(&'static str, Package)>>,
     //TODO: use hashset?
     waiting_installed: Vec<(&'static str, String, AppId)>,
     //TODO: use hashset?
     waiting_updates: Vec<(&'static str, String, AppId)>,
     category_results: Option<(&'static [Category], Vec<SearchResult>)>,
+    /// Results of categories visited this session, reused until apps are reloaded
+    category_results_cache: HashMap<&'static [Category], Vec<SearchResult>>,
     explore_results: HashMap<ExplorePage, Vec<SearchResult>>,
+    featured_images: HashMap<String, widget::image::Handle>,
+    featured_shown: usize,
+    /// Screenshot URL of the hovered result, fetched after a delay
+    screenshot_prefetch_opt: Option<String>,
+    /// Prefetched screenshots by URL, used when a result is selected
+    screenshot_prefetched: HashMap<String, Vec<u8>>,
     installed_results: Option<Vec<SearchResult>>,
+    installed_selected: BTreeSet<usize>,
+    installed_sort_size: bool,
+    /// Component kind shown in search and category results
+    kind_filter: AppKind,
     search_results: Option<(String, Vec<SearchResult>)>,
     selected_opt: Option<Selected>,
+    toasts: Toasts<Message>,
 }
 
 impl App {
-    fn open_desktop_id(&self, mut desktop_id: String) -> Command<Message> {
+    /// Open a desktop entry, appending arguments to its command
+    fn open_desktop_id(&self, desktop_id: String, args: Vec<String>) -> Command<Message> {
         Command::perform(
             async move {
                 tokio::task::spawn_blocking(move || {
-                    if !desktop_id.ends_with(".desktop") {
-                        desktop_id.push_str(".desktop");
-                    }
-                    let xdg_dirs = match xdg::BaseDirectories::with_prefix("applications") {
-                        Ok(ok) => ok,
-                        Err(err) => {
-                            log::warn!("failed to find applications xdg directories: {}", err);
-                            return message::none();
-                        }
-                    };
-                    let path = match xdg_dirs.find_data_file(&desktop_id) {
-                        Some(some) => some,
-                        None => {
-                            log::warn!("failed to find desktop file for {:?}", desktop_id);
-                            return message::none();
-                        }
-                    };
-                    let entry = match freedesktop_entry_parser::parse_entry(&path) {
-                        Ok(ok) => ok,
-                        Err(err) => {
-                            log::warn!("failed to read desktop file {:?}: {}", path, err);
-                            return message::none();
-                        }
-                    };
-                    //TODO: handlne Terminal=true
-                    let exec = match entry.section("Desktop Entry").attr("Exec") {
+                    let mut exec = match desktop_exec(&desktop_id) {
                         Some(some) => some,
                         None => {
-                            log::warn!("no exec section in {:?}", path);
+                            log::warn!("failed to open desktop entry {:?}", desktop_id);
                             return message::none();
                         }
                     };
+                    for arg in args {
+                        exec.push(' ');
+                        exec.push_str(&arg);
+                    }
                     //TODO: use libcosmic for loading desktop data
                     cosmic::desktop::spawn_desktop_exec(exec, Vec::<(&str, &str)>::new());
                     message::none()
@@ -729,97 +1849,306 @@ impl App {
         )
     }
 
-    fn operation(&mut self, operation: Operation) {
-        let id = self.pending_operation_id;
-        self.pending_operation_id += 1;
-        self.pending_operations.insert(id, (operation, 0.0));
+    /// Start a systemd service, which asks for authorization if needed
+    fn open_service(&self, service: String) -> Command<Message> {
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    match process::Command::new("systemctl")
+                        .arg("start")
+                        .arg(&service)
+                        .output()
+                    {
+                        Ok(output) => {
+                            if !output.status.success() {
+                                log::warn!(
+                                    "failed to start service {:?} with {}: {}",
+                                    service,
+                                    output.status,
+                                    String::from_utf8_lossy(&output.stderr).trim()
+                                );
+                            }
+                        }
+                        Err(err) => {
+                            log::warn!("failed to run systemctl for {:?}: {}", service, err);
+                        }
+                    }
+                    message::none()
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
     }
 
-    fn generic_search<F: Fn(&AppId, &AppInfo, bool) -> Option<i64> + Send + Sync>(
-        apps: &Apps,
-        backends: &Backends,
-        filter_map: F,
-    ) -> Vec<SearchResult> {
-        let mut results: Vec<SearchResult> = apps
-            .par_iter()
-            .filter_map(|(id, infos)| {
-                let mut best_result: Option<SearchResult> = None;
-                for AppEntry {
-                    backend_name,
-                    info,
-                    installed,
-                } in infos.iter()
+    fn open_url(&self, url: &str) {
+        // Only web links are allowed to be opened
+        match reqwest::Url::parse(url) {
+            Ok(parsed) => match parsed.scheme() {
+                "http" | "https" => match process::Command::new("xdg-open")
+                    .arg(parsed.as_str())
+                    .spawn()
                 {
-                    if let Some(weight) = filter_map(id, info, *installed) {
-                        //TODO: optimize
-                        let Some(backend) = backends.get(backend_name) else {
-                            continue;
-                        };
-                        let appstream_caches = backend.info_caches();
-                        let Some(appstream_cache) = appstream_caches
-                            .iter()
-                            .find(|x| x.source_id == info.source_id)
-                        else {
-                            continue;
-                        };
-                        //TODO: put all infos into search result
-                        let result = SearchResult {
-                            backend_name,
-                            id: id.clone(),
-                            icon: appstream_cache.icon(info),
-                            info: info.clone(),
-                            weight,
-                        };
-                        best_result = match best_result {
-                            Some(other_result) => {
-                                if result.weight < other_result.weight {
-                                    Some(result)
-                                } else {
-                                    Some(other_result)
-                                }
-                            }
-                            None => Some(result),
-                        };
+                    Ok(_child) => {}
+                    Err(err) => {
+                        log::error!("failed to open URL {:?}: {}", url, err);
                     }
+                },
+                scheme => {
+                    log::warn!("refusing to open URL {:?} with scheme {:?}", url, scheme);
                 }
-                best_result
-            })
-            .collect();
-        results.sort_by(|a, b| match a.weight.cmp(&b.weight) {
-            cmp::Ordering::Equal => match LANGUAGE_SORTER.compare(&a.info.name, &b.info.name) {
-                cmp::Ordering::Equal => LANGUAGE_SORTER.compare(&a.backend_name, &b.backend_name),
-                ordering => ordering,
             },
-            ordering => ordering,
-        });
-        results
+            Err(err) => {
+                log::warn!("failed to parse URL {:?}: {}", url, err);
+            }
+        }
     }
 
-    fn categories(&self, categories: &'static [Category]) -> Command<Message> {
-        let apps = self.apps.clone();
+    fn export_installed(&mut self) -> Command<Message> {
+        let installed = match &self.installed {
+            Some(some) => some,
+            None => {
+                log::warn!("cannot export installed apps before they are loaded");
+                return self.toast(fl!("export-installed-loading"));
+            }
+        };
+        let path = match installed_list_path() {
+            Some(some) => some,
+            None => {
+                log::warn!("failed to find path for installed app list");
+                return self.toast(fl!("installed-list-path-failed"));
+            }
+        };
+        let mut data = String::new();
+        for (backend_name, package) in installed {
+            if package.id.is_system() {
+                continue;
+            }
+            data.push_str(&format!("{} {}\n", backend_name, package.id.raw()));
+        }
+        match fs::write(&path, data) {
+            Ok(()) => {
+                log::info!("exported installed apps to {:?}", path);
+                self.toast(fl!(
+                    "export-installed-done",
+                    path = path.display().to_string()
+                ))
+            }
+            Err(err) => {
+                log::warn!("failed to export installed apps to {:?}: {}", path, err);
+                self.toast(fl!("export-installed-failed", error = err.to_string()))
+            }
+        }
+    }
+
+    /// Read the exported installed app list and show the apps that are missing for review
+    fn import_installed(&mut self) -> Command<Message> {
+        let path = match installed_list_path() {
+            Some(some) => some,
+            None => {
+                log::warn!("failed to find path for installed app list");
+                return self.toast(fl!("installed-list-path-failed"));
+            }
+        };
+        let data = match fs::read_to_string(&path) {
+            Ok(ok) => ok,
+            Err(err) => {
+                log::warn!("failed to import installed apps from {:?}: {}", path, err);
+                return self.toast(fl!("import-installed-failed", error = err.to_string()));
+            }
+        };
+        let mut ops = HashMap::<&'static str, Operation>::new();
+        for line in data.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (backend_name, id_raw) = match line.split_once(' ') {
+                Some(some) => some,
+                None => {
+                    log::warn!("invalid line in installed app list: {:?}", line);
+                    continue;
+                }
+            };
+            let id = AppId::new(id_raw.trim());
+            let entry_opt = self.apps.get(&id).and_then(|entries| {
+                entries
+                    .iter()
+                    .find(|entry| entry.backend_name == backend_name)
+            });
+            let entry = match entry_opt {
+                Some(some) => some,
+                None => {
+                    log::warn!("{} not found in {}, skipping", id, backend_name);
+                    continue;
+                }
+            };
+            if entry.installed {
+                continue;
+            }
+            if !self.backend_enabled(entry.backend_name) {
+                log::warn!("{} is disabled, skipping {}", entry.backend_name, id);
+                continue;
+            }
+            let op = ops.entry(entry.backend_name).or_insert_with(|| Operation {
+                kind: OperationKind::Install,
+                backend_name: entry.backend_name,
+                package_ids: Vec::new(),
+                infos: Vec::new(),
+                scope: self.config.install_scope,
+            });
+            op.package_ids.push(id);
+            op.infos.push(entry.info.clone());
+        }
+        if ops.is_empty() {
+            return self.toast(fl!("import-installed-none"));
+        }
+        // The imported apps are listed for confirmation before they are installed
+        self.review(ops.into_values().collect())
+    }
+
+    /// Take the instance lock if not already held, warning if another instance holds it
+    fn instance_lock(&mut self) {
+        if self.instance_lock_opt.is_some() {
+            return;
+        }
+        match instance::lock() {
+            Ok(Some(lock)) => {
+                self.instance_lock_opt = Some(lock);
+                self.instance_running = false;
+            }
+            Ok(None) => {
+                log::warn!("another instance of cosmic-store is running, operations may conflict");
+                self.instance_running = true;
+            }
+            Err(err) => {
+                log::warn!("failed to lock instance: {}", err);
+            }
+        }
+    }
+
+    fn operation(&mut self, operation: Operation) {
+        // The other instance may have exited since the last operation
+        self.instance_lock();
+        let id = self.pending_operation_id;
+        self.pending_operation_id += 1;
+        self.pending_operations.insert(
+            id,
+            (
+                operation,
+                OperationProgress {
+                    percent_opt: Some(0.0),
+                    status_opt: Some(OperationStatus::Queued),
+                    bytes_opt: None,
+                },
+            ),
+        );
+        self.operation_queue.push_back(id);
+        self.operation_queue_start();
+    }
+
+    /// Start an operation if its packages still exist, once their required agreements are accepted
+    fn operation_start(&mut self, op: Operation) {
+        if let Err(err) = self.operation_validate(&op) {
+            log::warn!(
+                "operation on {:?} from {} is no longer valid: {}",
+                op.package_ids,
+                op.backend_name,
+                err
+            );
+            let id = self.pending_operation_id;
+            self.pending_operation_id += 1;
+            self.failed_operations.insert(id, (op, err));
+            self.dialog_pages.push_back(DialogPage::FailedOperation(id));
+            return;
+        }
+        self.operation_agreements(op, None);
+    }
+
+    /// Ask for the next required agreement after the last accepted one, starting the operation
+    /// when all are accepted
+    fn operation_agreements(&mut self, op: Operation, accepted_opt: Option<(usize, usize)>) {
+        if op.kind == OperationKind::Install {
+            let next_opt = op
+                .infos
+                .iter()
+                .enumerate()
+                .flat_map(|(info_i, info)| {
+                    info.agreements
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, agreement)| agreement.required())
+                        .map(move |(agreement_i, _)| (info_i, agreement_i))
+                })
+                .find(|next| accepted_opt.map_or(true, |accepted| *next > accepted));
+            if let Some((info_i, agreement_i)) = next_opt {
+                let info = op.infos[info_i].clone();
+                self.dialog_pages.push_back(DialogPage::Agreement(
+                    info,
+                    agreement_i,
+                    Some((op, info_i)),
+                ));
+                return;
+            }
+        }
+        self.operation(op);
+    }
+
+    /// Check that the packages of an operation still exist in its backend, which may have been
+    /// reloaded since they were shown
+    fn operation_validate(&self, op: &Operation) -> Result<(), String> {
+        let backend = match self.backends.get(op.backend_name) {
+            Some(some) => some,
+            None => return Err(fl!("backend-not-found", backend = op.backend_name)),
+        };
+        let packages_opt = match op.kind {
+            OperationKind::Install => None,
+            OperationKind::Uninstall => self.installed.as_deref(),
+            OperationKind::Update => self.updates.as_deref(),
+        };
+        operation_packages_validate(op, backend.info_caches(), packages_opt)
+    }
+
+    /// Start queued operations until the maximum number are running
+    fn operation_queue_start(&mut self) {
+        while self.pending_operations.len() - self.operation_queue.len() < OPERATIONS_RUNNING_MAX {
+            let id = match self.operation_queue.pop_front() {
+                Some(some) => some,
+                None => break,
+            };
+            if let Some((_, progress)) = self.pending_operations.get_mut(&id) {
+                *progress = OperationProgress::default();
+            }
+        }
+    }
+
+    /// Show operations for review before running them
+    fn review(&mut self, ops: Vec<Operation>) -> Command<Message> {
+        if ops.is_empty() {
+            return Command::none();
+        }
+        self.review_plan_opt = None;
+        self.review_suggests = self.review_suggests(&ops);
+        self.dialog_pages.push_back(DialogPage::Review(ops.clone()));
         let backends = self.backends.clone();
         Command::perform(
             async move {
                 tokio::task::spawn_blocking(move || {
-                    let start = Instant::now();
-                    let results =
-                        Self::generic_search(&apps, &backends, |_id, info, _installed| {
-                            for category in categories {
-                                //TODO: contains doesn't work due to type mismatch
-                                if info.categories.iter().any(|x| x == category.id()) {
-                                    return Some(-(info.monthly_downloads as i64));
-                                }
+                    let mut items = Vec::new();
+                    for op in ops.iter() {
+                        let backend = match backends.get(op.backend_name) {
+                            Some(some) => some,
+                            None => continue,
+                        };
+                        match backend.plan(op) {
+                            Ok(op_items) => items.extend(op_items),
+                            Err(err) => {
+                                log::info!("failed to plan {:?}: {}", op.package_ids, err);
                             }
-                            None
-                        });
-                    let duration = start.elapsed();
-                    log::info!(
-                        "searched for categories {:?} in {:?}, found {} results",
-                        categories,
-                        duration,
-                        results.len()
-                    );
-                    message::app(Message::CategoryResults(categories, results))
+                        }
+                    }
+                    message::app(Message::ReviewPlan(items))
                 })
                 .await
                 .unwrap_or(message::none())
@@ -828,17 +2157,381 @@ impl App {
         )
     }
 
-    fn explore_results(&self, explore_page: ExplorePage) -> Command<Message> {
-        let apps = self.apps.clone();
-        let backends = self.backends.clone();
+    /// Start an operation, reviewing installs of apps with suggestions to offer installing them too
+    fn operation_reviewed(&mut self, op: Operation) -> Command<Message> {
+        if !self.review_suggests(std::slice::from_ref(&op)).is_empty() {
+            return self.review(vec![op]);
+        }
+        self.operation_start(op);
+        Command::none()
+    }
+
+    /// Suggested apps of install operations that are available and not yet installed
+    fn review_suggests(&self, ops: &[Operation]) -> Vec<ReviewSuggest> {
+        let mut suggests: Vec<ReviewSuggest> = Vec::new();
+        for op in ops.iter().filter(|op| op.kind == OperationKind::Install) {
+            for suggest in op.infos.iter().flat_map(|info| info.suggests.iter()) {
+                let id = AppId::new(suggest);
+                if ops.iter().any(|op| op.package_ids.contains(&id))
+                    || suggests.iter().any(|x| x.id == id)
+                {
+                    continue;
+                }
+                let entries = match self.apps.get(&id) {
+                    Some(some) => some,
+                    None => continue,
+                };
+                if entries.iter().any(|entry| entry.installed) {
+                    continue;
+                }
+                // Prefer the backend of the app that suggested it
+                let entry_opt = entries
+                    .iter()
+                    .find(|entry| entry.backend_name == op.backend_name)
+                    .or_else(|| {
+                        entries
+                            .iter()
+                            .find(|entry| self.backend_enabled(entry.backend_name))
+                    });
+                if let Some(entry) = entry_opt {
+                    suggests.push(ReviewSuggest {
+                        backend_name: entry.backend_name,
+                        id,
+                        info: entry.info.clone(),
+                        scope: op.scope,
+                        checked: false,
+                    });
+                }
+            }
+        }
+        suggests
+    }
+
+    /// Start the next operation of update all, or show a summary if there are none left
+    fn update_all_next(&mut self) {
+        loop {
+            let Some(update_all) = &mut self.update_all_opt else {
+                return;
+            };
+            let Some(op) = update_all.queue.pop_front() else {
+                self.dialog_pages.push_back(DialogPage::UpdateAllSummary(
+                    update_all.updated,
+                    update_all.failed,
+                ));
+                self.update_all_opt = None;
+                return;
+            };
+            // Updates that are gone since update all started are counted as failed
+            if let Err(err) = self.operation_validate(&op) {
+                log::warn!("update of {:?} is no longer valid: {}", op.package_ids, err);
+                if let Some(update_all) = &mut self.update_all_opt {
+                    update_all.failed += op.package_ids.len();
+                }
+                continue;
+            }
+            if let Some(update_all) = &mut self.update_all_opt {
+                update_all.running_opt = Some(self.pending_operation_id);
+            }
+            self.operation(op);
+            return;
+        }
+    }
+
+    /// Overall progress of update all from 0 to 100
+    fn update_all_percent(&self, update_all: &UpdateAll) -> f32 {
+        let mut done = (update_all.updated + update_all.failed) as f32;
+        if let Some(id) = update_all.running_opt {
+            if let Some((op, progress)) = self.pending_operations.get(&id) {
+                done += op.package_ids.len() as f32 * progress.percent_opt.unwrap_or(0.0) / 100.0;
+            }
+        }
+        if update_all.total > 0 {
+            done * 100.0 / update_all.total as f32
+        } else {
+            100.0
+        }
+    }
+
+    /// Current screenshot of the selected item filling the window, if zoomed
+    fn screenshot_fullscreen_view(&self) -> Option<Element<Message>> {
+        let cosmic_theme::Spacing { space_s, .. } = theme::active().cosmic().spacing;
+        let selected = self.selected_opt.as_ref()?;
+        if !selected.screenshot_fullscreen {
+            return None;
+        }
+        let screenshots = selected
+            .info
+            .themed_screenshots(theme::active().cosmic().is_dark);
+        let &(screenshot_i, screenshot) = screenshots.get(selected.screenshot_shown)?;
+        let (image, _dimensions_opt) = selected.screenshot_images.get(&screenshot_i)?;
+        let row = widget::row::with_children(vec![
+            icon_button(
+                widget::icon::from_name("go-previous-symbolic")
+                    .size(16)
+                    .icon(),
+                fl!("previous-screenshot"),
+                selected
+                    .screenshot_shown
+                    .checked_sub(1)
+                    .map(Message::SelectedScreenshotShown),
+            ),
+            // Clicking the zoomed screenshot closes it
+            widget::mouse_area(
+                widget::image(image.clone())
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .content_fit(ContentFit::Contain),
+            )
+            .on_press(Message::ScreenshotFullscreen(false))
+            .into(),
+            icon_button(
+                widget::icon::from_name("go-next-symbolic").size(16).icon(),
+                fl!("next-screenshot"),
+                if selected.screenshot_shown + 1 < screenshots.len() {
+                    Some(Message::SelectedScreenshotShown(
+                        selected.screenshot_shown + 1,
+                    ))
+                } else {
+                    None
+                },
+            ),
+        ])
+        .align_items(Alignment::Center)
+        .height(Length::Fill);
+        Some(
+            widget::column::with_children(vec![
+                widget::row::with_children(vec![
+                    widget::horizontal_space(Length::Fill).into(),
+                    icon_button(
+                        widget::icon::from_name("window-close-symbolic")
+                            .size(16)
+                            .icon(),
+                        fl!("close-screenshot"),
+                        Some(Message::ScreenshotFullscreen(false)),
+                    ),
+                ])
+                .into(),
+                row.into(),
+                widget::text::caption(&screenshot.caption).into(),
+            ])
+            .align_items(Alignment::Center)
+            .padding(space_s)
+            .spacing(space_s)
+            .into(),
+        )
+    }
+
+    /// Progress bar for an operation, pulsing until the backend reports a percentage
+    fn progress_bar(&self, progress: &OperationProgress) -> Element<Message> {
+        //TODO: get height from theme?
+        widget::progress_bar(
+            0.0..=100.0,
+            progress.percent_opt.unwrap_or(self.progress_pulse),
+        )
+        .height(Length::Fixed(4.0))
+        .into()
+    }
+
+    /// Held apps are excluded from update all, but can still be updated manually
+    fn is_held(&self, id: &AppId) -> bool {
+        self.config.held.contains(id.normalized())
+    }
+
+    /// Installation that an installed app is in, defaulting to the configured scope
+    fn installed_scope(&self, backend_name: &str, source_id: &str, id: &AppId) -> InstallScope {
+        self.installed
+            .as_ref()
+            .and_then(|installed| {
+                installed
+                    .iter()
+                    .find(|(installed_backend_name, package)| {
+                        same_package(
+                            (installed_backend_name, &package.info.source_id, &package.id),
+                            (backend_name, source_id, id),
+                        )
+                    })
+                    .map(|(_, package)| package.scope)
+            })
+            .unwrap_or(self.config.install_scope)
+    }
+
+    /// Remove installed apps from results if they are hidden by config
+    fn hide_installed(&self, results: &mut Vec<SearchResult>) {
+        if !self.config.hide_installed {
+            return;
+        }
+        results.retain(|result| {
+            self.apps
+                .get(&result.id)
+                .map_or(true, |entries| !entries.iter().any(|entry| entry.installed))
+        });
+    }
+
+    /// Search all apps, or a batch of them when streaming results
+    fn generic_search<'a, F: Fn(&AppId, &AppInfo, bool) -> Option<i64> + Send + Sync>(
+        apps: impl IntoParallelIterator<Item = (&'a AppId, &'a Vec<AppEntry>)>,
+        backends: &Backends,
+        filter_map: F,
+    ) -> Vec<SearchResult> {
+        let mut results: Vec<SearchResult> = apps
+            .into_par_iter()
+            .filter_map(|(id, infos)| Self::generic_search_result(id, infos, backends, &filter_map))
+            .collect();
+        Self::sort_results(&mut results);
+        results
+    }
+
+    /// Best matching result of an app, using the lowest weight of its entries
+    fn generic_search_result<F: Fn(&AppId, &AppInfo, bool) -> Option<i64>>(
+        id: &AppId,
+        infos: &[AppEntry],
+        backends: &Backends,
+        filter_map: &F,
+    ) -> Option<SearchResult> {
+        let mut best_opt: Option<(&'static str, &AppstreamCache, &Arc<AppInfo>, i64)> = None;
+        for AppEntry {
+            backend_name,
+            info,
+            installed,
+        } in infos.iter()
+        {
+            if let Some(weight) = filter_map(id, info, *installed) {
+                //TODO: optimize
+                let Some(backend) = backends.get(backend_name) else {
+                    continue;
+                };
+                let appstream_caches = backend.info_caches();
+                let Some(appstream_cache) = appstream_caches
+                    .iter()
+                    .find(|x| x.source_id == info.source_id)
+                else {
+                    continue;
+                };
+                match best_opt {
+                    Some((_, _, _, best_weight)) if best_weight <= weight => {}
+                    _ => {
+                        best_opt = Some((*backend_name, appstream_cache, info, weight));
+                    }
+                }
+            }
+        }
+        // Icon is only looked up for the best result, and cached between searches
+        let (backend_name, appstream_cache, info, weight) = best_opt?;
+        //TODO: put all infos into search result
+        Some(SearchResult {
+            backend_name,
+            id: id.clone(),
+            icon: app_icon_cache_handle(&info.source_id, id, || appstream_cache.icon(info)),
+            info: info.clone(),
+            weight,
+            name_match_opt: None,
+            summary_match_opt: None,
+        })
+    }
+
+    fn sort_results(results: &mut [SearchResult]) {
+        results.sort_by(|a, b| match a.weight.cmp(&b.weight) {
+            cmp::Ordering::Equal => match LANGUAGE_SORTER.compare(&a.info.name, &b.info.name) {
+                cmp::Ordering::Equal => LANGUAGE_SORTER.compare(&a.backend_name, &b.backend_name),
+                ordering => ordering,
+            },
+            ordering => ordering,
+        });
+    }
+
+    fn categories(&self, categories: &'static [Category]) -> Command<Message> {
+        let apps = self.apps.clone();
+        let backends = self.enabled_backends();
+        let kind_filter = self.kind_filter;
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let start = Instant::now();
+                    let results =
+                        Self::generic_search(&*apps, &backends, |_id, info, _installed| {
+                            if info.kind != kind_filter {
+                                return None;
+                            }
+                            for category in categories {
+                                //TODO: contains doesn't work due to type mismatch
+                                if info.categories.iter().any(|x| x == category.id()) {
+                                    return Some(-(info.monthly_downloads as i64));
+                                }
+                            }
+                            None
+                        });
+                    let duration = start.elapsed();
+                    log::info!(
+                        "searched for categories {:?} in {:?}, found {} results",
+                        categories,
+                        duration,
+                        results.len()
+                    );
+                    message::app(Message::CategoryResults(categories, results))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
+    fn explore_results(&self, explore_page: ExplorePage) -> Command<Message> {
+        let apps = self.apps.clone();
+        let backends = self.enabled_backends();
+        let recently_viewed = self.config.recently_viewed.clone();
+        let favorites = self.config.favorites.clone();
         Command::perform(
             async move {
                 tokio::task::spawn_blocking(move || {
                     let start = Instant::now();
                     let now = chrono::Utc::now().timestamp();
-                    let results = Self::generic_search(&apps, &backends, |id, info, _installed| {
+                    // Apps recently added to repositories, and the sources they are from
+                    let mut recent_additions = HashMap::new();
+                    let mut recent_sources = HashSet::new();
+                    if explore_page == ExplorePage::NewInDistro {
+                        let since = now - NEW_IN_DISTRO_DAYS * 24 * 60 * 60;
+                        for (backend_name, backend) in backends.iter() {
+                            match backend.recent_additions(since) {
+                                Ok(additions) => {
+                                    recent_additions.extend(additions);
+                                    recent_sources.extend(
+                                        backend
+                                            .info_caches()
+                                            .iter()
+                                            .map(|x| x.source_id.clone()),
+                                    );
+                                }
+                                Err(err) => {
+                                    log::debug!(
+                                        "failed to get recent additions from {}: {}",
+                                        backend_name,
+                                        err
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    let results = Self::generic_search(&*apps, &backends, |id, info, _installed| {
+                        // Explore pages only show apps, except for ones the user chose
+                        if info.kind != AppKind::App
+                            && !matches!(
+                                explore_page,
+                                ExplorePage::RecentlyViewed | ExplorePage::Favorites
+                            )
+                        {
+                            return None;
+                        }
                         match explore_page {
-                            ExplorePage::EditorsChoice => EDITORS_CHOICE
+                            ExplorePage::RecentlyViewed => recently_viewed
+                                .iter()
+                                .position(|recent_id| recent_id == id.normalized())
+                                .map(|x| x as i64),
+                            ExplorePage::Favorites => favorites
+                                .iter()
+                                .position(|favorite_id| favorite_id == id.normalized())
+                                .map(|x| x as i64),
+                            ExplorePage::EditorsChoice => editors_choice()
                                 .iter()
                                 .position(|choice_id| choice_id == &id.normalized())
                                 .map(|x| x as i64),
@@ -847,6 +2540,14 @@ impl App {
                                 //TODO
                                 None
                             }
+                            ExplorePage::NewInDistro => {
+                                if recent_sources.contains(&info.source_id) {
+                                    // Sorts from newest to oldest
+                                    recent_additions.get(id).map(|timestamp| -timestamp)
+                                } else {
+                                    None
+                                }
+                            }
                             ExplorePage::RecentlyUpdated => {
                                 // Finds the newest release and sorts from newest to oldest
                                 //TODO: appstream release info is often incomplete
@@ -876,6 +2577,23 @@ impl App {
                             }
                         }
                     });
+                    if explore_page == ExplorePage::EditorsChoice {
+                        // Only IDs found in loaded backends are shown
+                        for choice_id in editors_choice() {
+                            if !results.iter().any(|result| result.id.normalized() == choice_id) {
+                                log::debug!("editor's choice {:?} not found", choice_id);
+                            }
+                        }
+                    }
+                    if explore_page == ExplorePage::Favorites {
+                        // Favorites are kept even if they are not found, as their backend may be
+                        // disabled or their source not yet refreshed
+                        for favorite_id in favorites.iter() {
+                            if !results.iter().any(|result| result.id.normalized() == favorite_id) {
+                                log::debug!("favorite {:?} not found", favorite_id);
+                            }
+                        }
+                    }
                     let duration = start.elapsed();
                     log::info!(
                         "searched for {:?} in {:?}, found {} results",
@@ -894,118 +2612,49 @@ impl App {
 
     fn installed_results(&self) -> Command<Message> {
         let apps = self.apps.clone();
-        let backends = self.backends.clone();
-        Command::perform(
-            async move {
-                tokio::task::spawn_blocking(move || {
-                    let start = Instant::now();
-                    let results = Self::generic_search(&apps, &backends, |id, _info, installed| {
-                        if installed {
-                            Some(if id.is_system() { -1 } else { 0 })
-                        } else {
-                            None
-                        }
-                    });
-                    let duration = start.elapsed();
-                    log::info!(
-                        "searched for installed in {:?}, found {} results",
-                        duration,
-                        results.len()
-                    );
-                    message::app(Message::InstalledResults(results))
-                })
-                .await
-                .unwrap_or(message::none())
-            },
-            |x| x,
-        )
-    }
-
-    fn search(&self) -> Command<Message> {
-        let input = self.search_input.clone();
-        let pattern = regex::escape(&input);
-        let regex = match regex::RegexBuilder::new(&pattern)
-            .case_insensitive(true)
-            .build()
-        {
-            Ok(ok) => ok,
-            Err(err) => {
-                log::warn!("failed to parse regex {:?}: {}", pattern, err);
-                return Command::none();
+        let backends = self.enabled_backends();
+        // Sizes are only needed when sorting by size
+        let mut sizes_opt = None;
+        if self.installed_sort_size {
+            let mut sizes = HashMap::new();
+            if let Some(installed) = &self.installed {
+                for (_backend_name, package) in installed {
+                    if let Some(size) = package.installed_size {
+                        sizes.insert(package.id.clone(), size);
+                    }
+                }
             }
-        };
-        let apps = self.apps.clone();
-        let backends = self.backends.clone();
+            sizes_opt = Some(sizes);
+        }
         Command::perform(
             async move {
                 tokio::task::spawn_blocking(move || {
                     let start = Instant::now();
                     let results =
-                        Self::generic_search(&apps, &backends, |_id, info, _installed| {
-                            //TODO: improve performance
-                            let stats_weight = |weight: i64| {
-                                //TODO: make sure no overflows
-                                (weight << 56) - (info.monthly_downloads as i64)
-                            };
-                            //TODO: fuzzy match (nucleus-matcher?)
-                            match regex.find(&info.name) {
-                                Some(mat) => {
-                                    if mat.range().start == 0 {
-                                        if mat.range().end == info.name.len() {
-                                            // Name equals search phrase
-                                            Some(stats_weight(0))
-                                        } else {
-                                            // Name starts with search phrase
-                                            Some(stats_weight(1))
-                                        }
-                                    } else {
-                                        // Name contains search phrase
-                                        Some(stats_weight(2))
-                                    }
-                                }
-                                None => match regex.find(&info.summary) {
-                                    Some(mat) => {
-                                        if mat.range().start == 0 {
-                                            if mat.range().end == info.summary.len() {
-                                                // Summary equals search phrase
-                                                Some(stats_weight(3))
-                                            } else {
-                                                // Summary starts with search phrase
-                                                Some(stats_weight(4))
-                                            }
-                                        } else {
-                                            // Summary contains search phrase
-                                            Some(stats_weight(5))
+                        Self::generic_search(&*apps, &backends, |id, _info, installed| {
+                            if installed {
+                                Some(if id.is_system() {
+                                    i64::MIN
+                                } else {
+                                    match &sizes_opt {
+                                        // Sort by size descending
+                                        Some(sizes) => {
+                                            -(sizes.get(id).copied().unwrap_or(0) as i64)
                                         }
+                                        None => 0,
                                     }
-                                    None => match regex.find(&info.description) {
-                                        Some(mat) => {
-                                            if mat.range().start == 0 {
-                                                if mat.range().end == info.summary.len() {
-                                                    // Description equals search phrase
-                                                    Some(stats_weight(6))
-                                                } else {
-                                                    // Description starts with search phrase
-                                                    Some(stats_weight(7))
-                                                }
-                                            } else {
-                                                // Description contains search phrase
-                                                Some(stats_weight(8))
-                                            }
-                                        }
-                                        None => None,
-                                    },
-                                },
+                                })
+                            } else {
+                                None
                             }
                         });
                     let duration = start.elapsed();
                     log::info!(
-                        "searched for {:?} in {:?}, found {} results",
-                        input,
+                        "searched for installed in {:?}, found {} results",
                         duration,
                         results.len()
                     );
-                    message::app(Message::SearchResults(input, results))
+                    message::app(Message::InstalledResults(results))
                 })
                 .await
                 .unwrap_or(message::none())
@@ -1014,6 +2663,93 @@ impl App {
         )
     }
 
+    /// Show search results, scrolling to the top if the search changed
+    fn search_results_replace(
+        &mut self,
+        input: String,
+        results: Vec<SearchResult>,
+    ) -> Command<Message> {
+        // Clear selected item so search results can be shown
+        self.selected_opt = None;
+        // Scroll to top when the search changes
+        if self
+            .search_results
+            .as_ref()
+            .map_or(true, |(old_input, _)| old_input != &input)
+        {
+            self.scroll_views.remove(&ScrollContext::SearchResults);
+        }
+        self.search_results = Some((input, results));
+        self.update_scroll()
+    }
+
+    fn installed_page_active(&self) -> bool {
+        self.selected_opt.is_none()
+            && self.nav_model.active_data::<NavPage>() == Some(&NavPage::Installed)
+    }
+
+    /// Filter installed packages by name and summary, names matching first
+    fn search_installed_results(&self, regex: &regex::Regex) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+        if let Some(installed) = &self.installed {
+            for (backend_name, package) in installed.iter() {
+                let name_match_opt = regex.find(&package.info.name).map(|x| x.range());
+                let summary_match_opt = regex.find(&package.info.summary).map(|x| x.range());
+                let weight = match (&name_match_opt, &summary_match_opt) {
+                    (Some(_), _) => 0,
+                    (None, Some(_)) => 1,
+                    (None, None) => continue,
+                };
+                results.push(SearchResult {
+                    backend_name,
+                    id: package.id.clone(),
+                    icon: package.icon.clone(),
+                    info: package.info.clone(),
+                    weight,
+                    name_match_opt,
+                    summary_match_opt,
+                });
+            }
+        }
+        results.sort_by(|a, b| match a.weight.cmp(&b.weight) {
+            cmp::Ordering::Equal => LANGUAGE_SORTER.compare(&a.info.name, &b.info.name),
+            ordering => ordering,
+        });
+        results
+    }
+
+    fn search_suggestions_clear(&mut self) {
+        self.search_suggest_opt = None;
+        self.search_suggestions.clear();
+        self.search_suggestion_opt = None;
+    }
+
+    fn search(&mut self) -> Command<Message> {
+        let input = self.search_input.clone();
+        if self.search_installed {
+            let (regex, _word_regexes) = match search_regexes(&input) {
+                Some(some) => some,
+                None => return Command::none(),
+            };
+            // Installed apps are searched at once, replacing any streamed search
+            self.search_stream_opt = None;
+            let results = self.search_installed_results(&regex);
+            log::info!(
+                "searched installed for {:?}, found {} results",
+                input,
+                results.len()
+            );
+            return Command::perform(
+                async move { message::app(Message::SearchResults(input, results)) },
+                |x| x,
+            );
+        }
+        // Results are streamed in batches by the search subscription, replacing any running search
+        self.search_generation += 1;
+        self.search_stream_opt = Some(input);
+        Command::none()
+    }
+
     fn selected_sources(
         &self,
         backend_name: &'static str,
@@ -1054,17 +2790,248 @@ impl App {
             backend_name,
             info.source_id
         );
+        // Scroll to top when a different app is selected, but not when changing its source
+        if self
+            .selected_opt
+            .as_ref()
+            .map_or(true, |selected| selected.id != id)
+        {
+            self.scroll_views.remove(&ScrollContext::Selected);
+        }
         let sources = self.selected_sources(backend_name, &id, &info);
+        let recently_viewed_command = self.update(Message::RecentlyViewed(id.clone()));
+        let dependents_command = self.selected_dependents(backend_name, &id, &info);
+        let signed_command = self.selected_signed(backend_name, &id, &info);
+        let runtime_command = self.selected_runtime(backend_name, &id, &info);
+        let update_size_command = self.selected_update_size(backend_name, &id, &info);
+        let permission_editor_command = self.selected_permission_editor(backend_name, &id);
+        let similar_command = self.selected_similar(&id, &info);
         self.selected_opt = Some(Selected {
             backend_name,
             id,
             icon,
             info,
+            dependents: Vec::new(),
+            signed_opt: None,
+            description_expanded: false,
+            runtime_installed_opt: None,
+            update_size_opt: None,
+            languages_shown: false,
+            languages_filter: String::new(),
+            permission_editor_opt: None,
+            similar: Vec::new(),
+            screenshot_failed: HashSet::new(),
             screenshot_images: HashMap::new(),
+            screenshot_retries: HashMap::new(),
             screenshot_shown: 0,
+            screenshot_fullscreen: false,
             sources,
+            install_scope: self.config.install_scope,
         });
-        self.update_scroll()
+        // Prefetched screenshots are shown without fetching them again
+        if let Some(selected) = &mut self.selected_opt {
+            for (screenshot_i, screenshot) in selected.info.screenshots.iter().enumerate() {
+                let Some(data) = self.screenshot_prefetched.get(&screenshot.url) else {
+                    continue;
+                };
+                match screenshot_image(&screenshot.url, data.clone()) {
+                    Some(image) => {
+                        selected.screenshot_images.insert(screenshot_i, image);
+                    }
+                    None => {
+                        selected.screenshot_failed.insert(screenshot_i);
+                    }
+                }
+            }
+        }
+        Command::batch([
+            recently_viewed_command,
+            dependents_command,
+            signed_command,
+            runtime_command,
+            update_size_command,
+            permission_editor_command,
+            similar_command,
+            self.update_scroll(),
+        ])
+    }
+
+    fn selected_dependents(
+        &self,
+        backend_name: &'static str,
+        id: &AppId,
+        info: &Arc<AppInfo>,
+    ) -> Command<Message> {
+        if !self.is_installed(backend_name, &info.source_id, id) {
+            return Command::none();
+        }
+        let backend = match self.backends.get(backend_name) {
+            Some(some) => some.clone(),
+            None => return Command::none(),
+        };
+        let info = info.clone();
+        selected_task(
+            id,
+            "find dependents",
+            move |id| backend.dependents(id, &info),
+            Message::SelectedDependents,
+        )
+    }
+
+    fn selected_update_size(
+        &self,
+        backend_name: &'static str,
+        id: &AppId,
+        info: &Arc<AppInfo>,
+    ) -> Command<Message> {
+        let backend = match self.backends.get(backend_name) {
+            Some(some) => some.clone(),
+            None => return Command::none(),
+        };
+        let Some(package) = self.updates.as_ref().and_then(|updates| {
+            updates.iter().find_map(|(update_backend_name, package)| {
+                same_package(
+                    (update_backend_name, &package.info.source_id, &package.id),
+                    (backend_name, &info.source_id, id),
+                )
+                .then_some(package)
+            })
+        }) else {
+            return Command::none();
+        };
+        let op = Operation {
+            kind: OperationKind::Update,
+            backend_name,
+            package_ids: vec![package.id.clone()],
+            infos: vec![package.info.clone()],
+            scope: package.scope,
+        };
+        selected_task(
+            id,
+            "find update size",
+            move |_id| backend.plan(&op),
+            |id, items| {
+                let size = items.iter().map(|item| item.download_size).sum();
+                Message::SelectedUpdateSize(id, size)
+            },
+        )
+    }
+
+    fn selected_signed(
+        &self,
+        backend_name: &'static str,
+        id: &AppId,
+        info: &Arc<AppInfo>,
+    ) -> Command<Message> {
+        let backend = match self.backends.get(backend_name) {
+            Some(some) => some.clone(),
+            None => return Command::none(),
+        };
+        let info = info.clone();
+        selected_task(
+            id,
+            "check signatures",
+            move |id| backend.signed(id, &info),
+            Message::SelectedSigned,
+        )
+    }
+
+    fn selected_runtime(
+        &self,
+        backend_name: &'static str,
+        id: &AppId,
+        info: &Arc<AppInfo>,
+    ) -> Command<Message> {
+        let backend = match self.backends.get(backend_name) {
+            Some(some) => some.clone(),
+            None => return Command::none(),
+        };
+        let runtime = match &info.flatpak_runtime_opt {
+            Some(some) => some.clone(),
+            None => return Command::none(),
+        };
+        selected_task(
+            id,
+            "check runtime",
+            move |_id| backend.runtime_installed(&runtime),
+            Message::SelectedRuntimeInstalled,
+        )
+    }
+
+    fn selected_permission_editor(
+        &self,
+        backend_name: &'static str,
+        id: &AppId,
+    ) -> Command<Message> {
+        if backend_name != "flatpak" {
+            return Command::none();
+        }
+        selected_task(
+            id,
+            "find permission editor",
+            |_id| {
+                PERMISSION_EDITORS
+                    .iter()
+                    .find(|editor| desktop_exec(editor).is_some())
+                    .map(|editor| editor.to_string())
+                    .ok_or("none installed")
+            },
+            Message::SelectedPermissionEditor,
+        )
+    }
+
+    fn selected_similar(&self, id: &AppId, info: &Arc<AppInfo>) -> Command<Message> {
+        let apps = self.apps.clone();
+        let backends = self.enabled_backends();
+        let id = id.clone();
+        let info = info.clone();
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let start = Instant::now();
+                    let mut results =
+                        Self::generic_search(&*apps, &backends, |other_id, other, _installed| {
+                            if other_id == &id || other.kind != info.kind {
+                                return None;
+                            }
+                            let categories = other
+                                .categories
+                                .iter()
+                                .filter(|x| info.categories.contains(x))
+                                .count();
+                            if categories == 0 {
+                                return None;
+                            }
+                            let keywords = other
+                                .keywords
+                                .iter()
+                                .filter(|x| info.keywords.contains(x))
+                                .count();
+                            // Shared categories count more than keywords, popularity breaks ties
+                            let score = (2 * categories + keywords) as i64;
+                            Some(
+                                -score * 1_000_000_000
+                                    - cmp::min(other.monthly_downloads, 999_999_999) as i64,
+                            )
+                        });
+                    results.truncate(SIMILAR_COUNT);
+                    let duration = start.elapsed();
+                    log::info!(
+                        "searched for apps similar to {:?} in {:?}, found {} results",
+                        id,
+                        duration,
+                        results.len()
+                    );
+                    if results.len() < SIMILAR_MIN {
+                        results.clear();
+                    }
+                    message::app(Message::SelectedSimilar(id, results))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
     }
 
     fn scroll_context(&self) -> ScrollContext {
@@ -1079,6 +3046,109 @@ impl App {
         }
     }
 
+    fn explore_landing(&self) -> bool {
+        self.scroll_context() == ScrollContext::NavPage
+            && self
+                .nav_model
+                .active_data::<NavPage>()
+                .map_or(NavPage::default(), |nav_page| *nav_page)
+                == NavPage::Explore
+    }
+
+    /// Editor's choice results with a screenshot to use as a banner
+    fn featured(&self) -> Vec<(usize, &SearchResult, &AppScreenshot)> {
+        let is_dark = theme::active().cosmic().is_dark;
+        match self.explore_results.get(&ExplorePage::EditorsChoice) {
+            Some(results) => results
+                .iter()
+                .enumerate()
+                .filter_map(|(result_i, result)| {
+                    let (_, screenshot) =
+                        result.info.themed_screenshots(is_dark).into_iter().next()?;
+                    Some((result_i, result, screenshot))
+                })
+                .take(FEATURED_COUNT)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn featured_view(&self, spacing: &cosmic_theme::Spacing) -> Option<Element<Message>> {
+        let featured = self.featured();
+        if featured.is_empty() {
+            return None;
+        }
+        let featured_shown = self.featured_shown % featured.len();
+        let (result_i, result, screenshot) = featured[featured_shown];
+        //TODO: get proper image dimensions
+        let banner_height = Length::Fixed(320.0);
+        let image_element: Element<_> = match self.featured_images.get(&screenshot.url) {
+            Some(image) => widget::image(image.clone())
+                .width(Length::Fill)
+                .height(banner_height)
+                .into(),
+            None => widget::Space::new(Length::Fill, banner_height).into(),
+        };
+        let banner = widget::mouse_area(
+            widget::container(
+                widget::column::with_children(vec![
+                    image_element,
+                    widget::row::with_children(vec![
+                        widget::icon::icon(result.icon.clone())
+                            .size(ICON_SIZE_SEARCH)
+                            .into(),
+                        widget::column::with_children(vec![
+                            widget::text::title4(&result.info.name).into(),
+                            widget::text::caption(&result.info.summary).into(),
+                        ])
+                        .into(),
+                    ])
+                    .align_items(Alignment::Center)
+                    .spacing(spacing.space_s)
+                    .into(),
+                ])
+                .spacing(spacing.space_xxs),
+            )
+            .width(Length::Fill)
+            .padding([spacing.space_xxs, spacing.space_s])
+            .style(theme::Container::Card),
+        )
+        .on_press(Message::SelectExploreResult(
+            ExplorePage::EditorsChoice,
+            result_i,
+        ));
+        let mut prev_opt = None;
+        let mut next_opt = None;
+        if featured.len() > 1 {
+            prev_opt = Some(Message::FeaturedShown(
+                (featured_shown + featured.len() - 1) % featured.len(),
+            ));
+            next_opt = Some(Message::FeaturedShown(
+                (featured_shown + 1) % featured.len(),
+            ));
+        }
+        Some(
+            widget::row::with_children(vec![
+                icon_button(
+                    widget::icon::from_name("go-previous-symbolic")
+                        .size(16)
+                        .icon(),
+                    fl!("previous-featured"),
+                    prev_opt,
+                ),
+                banner.into(),
+                icon_button(
+                    widget::icon::from_name("go-next-symbolic").size(16).icon(),
+                    fl!("next-featured"),
+                    next_opt,
+                ),
+            ])
+            .align_items(Alignment::Center)
+            .spacing(spacing.space_xxs)
+            .into(),
+        )
+    }
+
     fn update_scroll(&mut self) -> Command<Message> {
         let scroll_context = self.scroll_context();
         // Clear unused scroll contexts
@@ -1100,7 +3170,7 @@ impl App {
             async move {
                 tokio::task::spawn_blocking(move || {
                     let start = Instant::now();
-                    let backends = backend::backends(&locale, refresh);
+                    let (backends, errors) = backend::backends(&locale, refresh);
                     let duration = start.elapsed();
                     log::info!(
                         "loaded backends {} in {:?}",
@@ -1111,7 +3181,103 @@ impl App {
                         },
                         duration
                     );
-                    message::app(Message::Backends(backends))
+                    message::app(Message::Backends(backends, errors))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
+    fn update_config(&mut self) -> Command<Message> {
+        // Screenshots shown may change with the theme
+        if let Some(selected) = &mut self.selected_opt {
+            selected.screenshot_shown = 0;
+        }
+        cosmic::app::command::set_theme(self.config.app_theme.theme())
+    }
+
+    /// Explore sections in the order they are shown, without hidden ones
+    fn explore_pages(&self) -> Vec<ExplorePage> {
+        let mut explore_pages: Vec<ExplorePage> = ExplorePage::all()
+            .iter()
+            .filter(|explore_page| {
+                Some(**explore_page) == self.config.explore_first
+                    || !self.config.explore_hidden.contains(explore_page)
+            })
+            .copied()
+            .collect();
+        if let Some(explore_first) = self.config.explore_first {
+            if let Some(index) = explore_pages.iter().position(|x| *x == explore_first) {
+                let explore_page = explore_pages.remove(index);
+                explore_pages.insert(0, explore_page);
+            }
+        }
+        explore_pages
+    }
+
+    /// Background downloads are paused on metered connections if configured
+    fn downloads_paused(&self) -> bool {
+        self.metered && self.config.pause_metered
+    }
+
+    fn update_remotes(&self) -> Command<Message> {
+        let backend = match self.backends.get("flatpak") {
+            Some(some) => some.clone(),
+            None => return Command::none(),
+        };
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || match backend.remotes() {
+                    Ok(remotes) => message::app(Message::Remotes(remotes)),
+                    Err(err) => {
+                        log::warn!("failed to list flatpak remotes: {}", err);
+                        message::none()
+                    }
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
+    /// Add a flatpak remote from a URL to the configured installation, or enable or disable one
+    /// by name
+    fn remote_change(
+        &self,
+        add_opt: Option<String>,
+        enable_opt: Option<(String, InstallScope, bool)>,
+    ) -> Command<Message> {
+        let backend = match self.backends.get("flatpak") {
+            Some(some) => some.clone(),
+            None => return Command::none(),
+        };
+        let install_scope = self.config.install_scope;
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    // Appstream data is downloaded for added and enabled remotes
+                    let (res, refresh) = match (add_opt, enable_opt) {
+                        (Some(url), _) => (
+                            backend.remote_add(&url, install_scope).map(|name| {
+                                log::info!("added flatpak remote {:?} from {:?}", name, url);
+                            }),
+                            true,
+                        ),
+                        (None, Some((name, scope, enabled))) => {
+                            (backend.remote_enable(&name, scope, enabled), enabled)
+                        }
+                        (None, None) => return message::none(),
+                    };
+                    match res {
+                        Ok(()) => message::app(Message::RemoteChanged(refresh)),
+                        Err(err) => {
+                            log::warn!("failed to change flatpak remotes: {}", err);
+                            message::app(Message::ShowToast(fl!("remote-change-failed")))
+                        }
+                    }
                 })
                 .await
                 .unwrap_or(message::none())
@@ -1120,17 +3286,65 @@ impl App {
         )
     }
 
-    fn update_config(&mut self) -> Command<Message> {
-        cosmic::app::command::set_theme(self.config.app_theme.theme())
+    fn backend_enabled(&self, backend_name: &str) -> bool {
+        !self.config.disabled_backends.contains(backend_name)
+    }
+
+    fn enabled_backends(&self) -> Backends {
+        self.backends
+            .iter()
+            .filter(|(backend_name, _)| self.backend_enabled(backend_name))
+            .map(|(backend_name, backend)| (*backend_name, backend.clone()))
+            .collect()
     }
 
-    fn is_installed(&self, backend_name: &'static str, source_id: &str, id: &AppId) -> bool {
+    fn installed_size(
+        &self,
+        backend_name: &'static str,
+        source_id: &str,
+        id: &AppId,
+    ) -> Option<u64> {
+        let installed = self.installed.as_ref()?;
+        installed
+            .iter()
+            .find(|(installed_backend_name, package)| {
+                same_package(
+                    (installed_backend_name, &package.info.source_id, &package.id),
+                    (backend_name, source_id, id),
+                )
+            })
+            .and_then(|(_, package)| package.installed_size)
+    }
+
+    /// Installed branches of an app, if the backend supports installing multiple branches
+    fn installed_branches(
+        &self,
+        backend_name: &'static str,
+        source_id: &str,
+        id: &AppId,
+    ) -> Vec<&Package> {
+        let mut branches = Vec::new();
         if let Some(installed) = &self.installed {
             for (installed_backend_name, package) in installed {
-                if installed_backend_name == &backend_name
-                    && &package.info.source_id == &source_id
-                    && &package.id == id
+                if same_package(
+                    (installed_backend_name, &package.info.source_id, &package.id),
+                    (backend_name, source_id, id),
+                ) && package.branch_opt.is_some()
                 {
+                    branches.push(package);
+                }
+            }
+        }
+        branches
+    }
+
+    fn is_installed(&self, backend_name: &'static str, source_id: &str, id: &AppId) -> bool {
+        if let Some(installed) = &self.installed {
+            for (installed_backend_name, package) in installed {
+                if same_package(
+                    (installed_backend_name, &package.info.source_id, &package.id),
+                    (backend_name, source_id, id),
+                ) {
                     return true;
                 }
             }
@@ -1141,6 +3355,8 @@ impl App {
     //TODO: run in background
     fn update_apps(&mut self) {
         let start = Instant::now();
+        // Cached category results may no longer match the apps
+        self.category_results_cache.clear();
         let mut apps = Apps::new();
 
         let entry_sort = |a: &AppEntry, b: &AppEntry, id: &AppId| {
@@ -1148,8 +3364,18 @@ impl App {
             match b.installed.cmp(&a.installed) {
                 cmp::Ordering::Equal => {
                     // Sort by highest priority first to lowest priority
-                    let a_priority = priority(a.backend_name, &a.info.source_id, id);
-                    let b_priority = priority(b.backend_name, &b.info.source_id, id);
+                    let a_priority = priority(
+                        a.backend_name,
+                        &a.info.source_id,
+                        id,
+                        self.config.preferred_backend,
+                    );
+                    let b_priority = priority(
+                        b.backend_name,
+                        &b.info.source_id,
+                        id,
+                        self.config.preferred_backend,
+                    );
                     match b_priority.cmp(&a_priority) {
                         cmp::Ordering::Equal => {
                             match LANGUAGE_SORTER.compare(&a.info.source_id, &b.info.source_id) {
@@ -1168,13 +3394,21 @@ impl App {
 
         //TODO: par_iter?
         for (backend_name, backend) in self.backends.iter() {
+            if !self.backend_enabled(backend_name) {
+                continue;
+            }
             for appstream_cache in backend.info_caches() {
                 for (id, info) in appstream_cache.infos.iter() {
+                    let installed = self.is_installed(backend_name, &info.source_id, id);
+                    // Installed pre-releases are always shown so they can be managed
+                    if info.prerelease && !installed && !self.config.prerelease {
+                        continue;
+                    }
                     let entry = apps.entry(id.clone()).or_insert_with(|| Vec::new());
                     entry.push(AppEntry {
                         backend_name,
                         info: info.clone(),
-                        installed: self.is_installed(backend_name, &info.source_id, id),
+                        installed,
                     });
                     entry.sort_by(|a, b| entry_sort(a, b, id));
                 }
@@ -1221,7 +3455,7 @@ impl App {
     }
 
     fn update_installed(&self) -> Command<Message> {
-        let backends = self.backends.clone();
+        let backends = self.enabled_backends();
         Command::perform(
             async move {
                 tokio::task::spawn_blocking(move || {
@@ -1263,7 +3497,7 @@ impl App {
     }
 
     fn update_updates(&self) -> Command<Message> {
-        let backends = self.backends.clone();
+        let backends = self.enabled_backends();
         Command::perform(
             async move {
                 tokio::task::spawn_blocking(move || {
@@ -1302,6 +3536,106 @@ impl App {
         )
     }
 
+    fn update_unused(&self) -> Command<Message> {
+        let backends = self.enabled_backends();
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut unused = Vec::new();
+                    for (backend_name, backend) in backends.iter() {
+                        let start = Instant::now();
+                        match backend.unused() {
+                            Ok(packages) => {
+                                for package in packages {
+                                    unused.push((*backend_name, package));
+                                }
+                            }
+                            Err(err) => {
+                                log::info!("failed to list unused: {}", err);
+                            }
+                        }
+                        let duration = start.elapsed();
+                        log::info!("loaded unused from {} in {:?}", backend_name, duration);
+                    }
+                    message::app(Message::Unused(unused))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
+    fn unused_summary(&self) -> (usize, u64) {
+        match &self.unused {
+            Some(unused) => (
+                unused.len(),
+                unused
+                    .iter()
+                    .filter_map(|(_, package)| package.installed_size)
+                    .sum(),
+            ),
+            None => (0, 0),
+        }
+    }
+
+    /// Replace icons shown for apps using a remote icon that was just fetched
+    fn update_remote_icon(&mut self, url: &str) -> Command<Message> {
+        let icon_path = match remote_icon_path(url) {
+            Some(some) => some,
+            None => return Command::none(),
+        };
+        let handle = widget::icon::from_path(icon_path);
+        let uses_icon = |info: &AppInfo| {
+            info.icons
+                .iter()
+                .any(|icon| matches!(icon, AppIcon::Remote(x, ..) if x == url))
+        };
+        // Cached handles with the fallback icon are no longer valid
+        for (id, entries) in self.apps.iter() {
+            for entry in entries.iter().filter(|entry| uses_icon(&entry.info)) {
+                app_icon_cache_remove(&entry.info.source_id, id);
+            }
+        }
+        let mut results: Vec<&mut SearchResult> = Vec::new();
+        if let Some((_, category_results)) = &mut self.category_results {
+            results.extend(category_results.iter_mut());
+        }
+        for category_results in self.category_results_cache.values_mut() {
+            results.extend(category_results.iter_mut());
+        }
+        for explore_results in self.explore_results.values_mut() {
+            results.extend(explore_results.iter_mut());
+        }
+        if let Some(installed_results) = &mut self.installed_results {
+            results.extend(installed_results.iter_mut());
+        }
+        if let Some((_, search_results)) = &mut self.search_results {
+            results.extend(search_results.iter_mut());
+        }
+        for result in results {
+            if uses_icon(&result.info) {
+                result.icon = handle.clone();
+            }
+        }
+        for packages in [&mut self.installed, &mut self.updates, &mut self.unused]
+            .into_iter()
+            .flatten()
+        {
+            for (_, package) in packages.iter_mut() {
+                if uses_icon(&package.info) {
+                    package.icon = handle.clone();
+                }
+            }
+        }
+        if let Some(selected) = &mut self.selected_opt {
+            if uses_icon(&selected.info) {
+                selected.icon = handle.clone();
+            }
+        }
+        Command::none()
+    }
+
     fn update_notification(&mut self) -> Command<Message> {
         // Handle closing notification if there are no operations
         if self.pending_operations.is_empty() {
@@ -1326,18 +3660,87 @@ impl App {
         Command::none()
     }
 
+    fn updates_notification(&mut self) -> Command<Message> {
+        let updates = match &self.updates {
+            Some(some) => some,
+            None => return Command::none(),
+        };
+        let updates_set: BTreeSet<_> = updates
+            .iter()
+            .map(|(backend_name, package)| {
+                (
+                    *backend_name,
+                    package.id.to_string(),
+                    package.version.clone(),
+                )
+            })
+            .collect();
+        if updates_set.is_empty() || updates_set == self.updates_notified {
+            return Command::none();
+        }
+        let count = updates_set.len();
+        // Updates seen in the focused window do not need a notification
+        let notify = self.config.update_notifications
+            && (self.window_id_opt.is_none() || !self.window_focused);
+        self.updates_notified = updates_set;
+        if !notify {
+            return Command::none();
+        }
+        let handle_mutex = self.updates_notification_handle.clone();
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut handle_opt = handle_mutex.lock().unwrap();
+                    // Closing the previous notification ends the thread waiting on it
+                    if let Some(handle) = handle_opt.take() {
+                        handle.close();
+                    }
+                    match notify_rust::Notification::new()
+                        .summary(&fl!("notification-updates", count = count))
+                        .action("default", &fl!("view-updates"))
+                        .auto_icon()
+                        .show()
+                    {
+                        Ok(notification) => {
+                            let id = notification.id();
+                            *handle_opt = Some(notification);
+                            drop(handle_opt);
+                            let mut activated = false;
+                            notify_rust::handle_action(id, |action: &str| {
+                                activated = action == "default";
+                            });
+                            if activated {
+                                return message::app(Message::UpdatesNotificationActivated);
+                            }
+                        }
+                        Err(err) => {
+                            log::warn!("failed to create updates notification: {}", err);
+                        }
+                    }
+                    message::none()
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
     fn handle_appstream_url(&mut self, path: &str) -> Command<Message> {
         // Handler for appstream:component-id as described in:
         // https://freedesktop.org/software/appstream/docs/sect-AppStream-Misc-URIHandler.html
+        self.select_id(AppId::new(path.trim_start_matches('/')))
+    }
+
+    fn select_id(&self, component_id: AppId) -> Command<Message> {
         let apps = self.apps.clone();
-        let backends = self.backends.clone();
-        let component_id = AppId::new(path.trim_start_matches('/'));
+        let backends = self.enabled_backends();
         Command::perform(
             async move {
                 tokio::task::spawn_blocking(move || {
                     let start = Instant::now();
                     let results =
-                        Self::generic_search(&apps, &backends, |id, _info, _installed| {
+                        Self::generic_search(&*apps, &backends, |id, _info, _installed| {
                             //TODO: fuzzy search with lower weight?
                             if id == &component_id {
                                 Some(0)
@@ -1370,9 +3773,62 @@ impl App {
         )
     }
 
+    fn handle_provides_url(&mut self, path: &str) -> Command<Message> {
+        // Handler for provides:kind/value, like provides:mediatype/text/plain
+        let provide = match path
+            .split_once('/')
+            .and_then(|(kind, value)| AppProvide::parse(kind, value))
+        {
+            Some(some) => some,
+            None => {
+                log::warn!("unsupported provides URL path {:?}", path);
+                return Command::none();
+            }
+        };
+        // Results are shown as search results for the provided value
+        let input = match &provide {
+            AppProvide::Binary(value)
+            | AppProvide::Font(value)
+            | AppProvide::Library(value)
+            | AppProvide::MediaType(value) => value.clone(),
+        };
+        self.search_active = true;
+        self.search_input = input.clone();
+        self.search_stream_opt = None;
+        self.search_suggestions_clear();
+        let apps = self.apps.clone();
+        let backends = self.enabled_backends();
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let start = Instant::now();
+                    let results =
+                        Self::generic_search(&*apps, &backends, |_id, info, _installed| {
+                            if info.provides.contains(&provide) {
+                                Some(-(info.monthly_downloads as i64))
+                            } else {
+                                None
+                            }
+                        });
+                    let duration = start.elapsed();
+                    log::info!(
+                        "searched for provides {:?} in {:?}, found {} results",
+                        provide,
+                        duration,
+                        results.len()
+                    );
+                    message::app(Message::SearchResults(input, results))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
     fn handle_file_url(&mut self, path: &str) -> Command<Message> {
         let path = path.to_string();
-        let backends = self.backends.clone();
+        let backends = self.enabled_backends();
         Command::perform(
             async move {
                 tokio::task::spawn_blocking(move || {
@@ -1422,6 +3878,9 @@ impl App {
                     "file" => {
                         return self.handle_file_url(url.path());
                     }
+                    "provides" => {
+                        return self.handle_provides_url(url.path());
+                    }
                     scheme => {
                         log::warn!("unsupported URL scheme {scheme}");
                         Command::none()
@@ -1441,32 +3900,498 @@ impl App {
         }
     }
 
-    fn update_title(&mut self) -> Command<Message> {
-        self.set_window_title(fl!("cosmic-app-store"), self.main_window_id())
+    /// Rebuild the nav bar in sections, keeping the active page
+    fn update_nav_model(&mut self) {
+        let active_page = self
+            .nav_model
+            .active_data::<NavPage>()
+            .copied()
+            .unwrap_or_default();
+        self.update_nav_model_active(active_page);
+    }
+
+    /// Rebuild the nav bar in sections, activating the given page
+    fn update_nav_model_active(&mut self, active_page: NavPage) {
+        let collapsed = self.config.nav_categories_collapsed;
+        self.nav_model.clear();
+        for (section_i, &nav_section) in NavSection::all().iter().enumerate() {
+            let header_id = self
+                .nav_model
+                .insert()
+                .text(nav_section.title())
+                .data::<NavSection>(nav_section)
+                .divider_above(section_i > 0)
+                .id();
+            if nav_section == NavSection::Categories {
+                self.nav_model.icon_set(
+                    header_id,
+                    widget::icon::from_name(if collapsed {
+                        "go-next-symbolic"
+                    } else {
+                        "go-down-symbolic"
+                    })
+                    .size(16)
+                    .icon(),
+                );
+            }
+
+            let mut nav_pages: Vec<NavPage> = NavPage::all()
+                .iter()
+                .copied()
+                .filter(|nav_page| nav_page.section() == nav_section)
+                .collect();
+            if nav_section == NavSection::Categories {
+                nav_pages.sort_by(|a, b| LANGUAGE_SORTER.compare(&a.title(), &b.title()));
+            }
+            for nav_page in nav_pages {
+                // The active category stays visible when the section is collapsed
+                if collapsed && nav_section == NavSection::Categories && nav_page != active_page {
+                    continue;
+                }
+                let id = self
+                    .nav_model
+                    .insert()
+                    .icon(nav_page.icon())
+                    .text(nav_page.title())
+                    .data::<NavPage>(nav_page)
+                    .id();
+                if nav_page == active_page {
+                    self.nav_model.activate(id);
+                }
+            }
+        }
+    }
+
+    fn update_title(&mut self) -> Command<Message> {
+        self.set_window_title(fl!("cosmic-app-store"), self.main_window_id())
+    }
+
+    /// Open the window in this process, or focus it if already open, so that operations are
+    /// not run by competing processes
+    fn window_open(&mut self) -> Command<Message> {
+        if let Some(window_id) = self.window_id_opt {
+            return window::gain_focus(window_id);
+        }
+        let (window_id, command) = window::spawn(window::Settings {
+            min_size: Some(Size::new(360.0, 180.0)),
+            decorations: false,
+            exit_on_close_request: false,
+            ..Default::default()
+        });
+        self.window_id_opt = Some(window_id);
+        command
+    }
+
+    fn update_backend_versions(&self) -> Command<Message> {
+        let backends = self.backends.clone();
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    let mut backend_versions = Vec::with_capacity(backends.len());
+                    for (backend_name, backend) in backends.iter() {
+                        match backend.version() {
+                            Ok(version) => backend_versions.push((*backend_name, version)),
+                            Err(err) => {
+                                log::warn!("failed to get {} version: {}", backend_name, err);
+                            }
+                        }
+                    }
+                    message::app(Message::BackendVersions(backend_versions))
+                })
+                .await
+                .unwrap_or(message::none())
+            },
+            |x| x,
+        )
+    }
+
+    fn about(&self) -> Element<Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+        let mut column = widget::column::with_capacity(8)
+            .push(widget::icon::from_name(Self::APP_ID).size(128))
+            .push(widget::text::title3(fl!("cosmic-app-store")))
+            .push(widget::text::body(fl!(
+                "version",
+                version = env!("CARGO_PKG_VERSION")
+            )));
+        // Set by the build script from the locked libcosmic revision
+        if let Some(version) = option_env!("LIBCOSMIC_VERSION") {
+            column = column.push(widget::text::body(fl!(
+                "libcosmic-version",
+                version = version
+            )));
+        }
+        for (backend_name, version) in self.backend_versions.iter() {
+            column = column.push(widget::text::body(format!(
+                "{}: {}",
+                backend_title(backend_name),
+                version
+            )));
+        }
+        column
+            .push(
+                widget::button::link(fl!("homepage"))
+                    .on_press(Message::OpenUrl(REPOSITORY.to_string())),
+            )
+            .push(
+                widget::button::link(fl!("license"))
+                    .on_press(Message::OpenUrl(LICENSE_URL.to_string())),
+            )
+            .align_items(Alignment::Center)
+            .spacing(space_xxs)
+            .width(Length::Fill)
+            .into()
+    }
+
+    fn settings(&self) -> Element<Message> {
+        let app_theme_selected = match self.config.app_theme {
+            AppTheme::Dark => 1,
+            AppTheme::Light => 2,
+            AppTheme::System => 0,
+        };
+        let mut sections = Vec::with_capacity(7);
+        sections.push(
+            widget::settings::view_section(fl!("appearance"))
+                .add(
+                    widget::settings::item::builder(fl!("theme")).control(widget::dropdown(
+                        &self.app_themes,
+                        Some(app_theme_selected),
+                        move |index| {
+                            Message::AppTheme(match index {
+                                1 => AppTheme::Dark,
+                                2 => AppTheme::Light,
+                                _ => AppTheme::System,
+                            })
+                        },
+                    )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("density")).control(widget::dropdown(
+                        &self.densities,
+                        Some(match self.config.density {
+                            Density::Comfortable => 0,
+                            Density::Compact => 1,
+                        }),
+                        move |index| {
+                            Message::Density(match index {
+                                1 => Density::Compact,
+                                _ => Density::Comfortable,
+                            })
+                        },
+                    )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("grid-columns")).control(widget::dropdown(
+                        &self.grid_columns,
+                        Some(self.config.max_grid_columns.unwrap_or(0)),
+                        |index| Message::GridColumns(if index == 0 { None } else { Some(index) }),
+                    )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("badges")).control(widget::dropdown(
+                        &self.badge_days,
+                        Some(self.config.badge_days.map_or(0, |days| {
+                            BADGE_DAYS
+                                .iter()
+                                .position(|x| *x == days)
+                                .map_or(0, |x| x + 1)
+                        })),
+                        |index| {
+                            Message::BadgeDays(
+                                index
+                                    .checked_sub(1)
+                                    .and_then(|x| BADGE_DAYS.get(x))
+                                    .copied(),
+                            )
+                        },
+                    )),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("hide-installed"))
+                        .toggler(self.config.hide_installed, Message::HideInstalled),
+                )
+                .into(),
+        );
+        {
+            let mut section = widget::settings::view_section(fl!("explore")).add(
+                widget::settings::item::builder(fl!("explore-first")).control(widget::dropdown(
+                    &self.explore_firsts,
+                    Some(self.config.explore_first.map_or(0, |explore_page| {
+                        ExplorePage::all()
+                            .iter()
+                            .position(|x| *x == explore_page)
+                            .map_or(0, |x| x + 1)
+                    })),
+                    |index| {
+                        Message::ExploreFirst(
+                            index
+                                .checked_sub(1)
+                                .and_then(|x| ExplorePage::all().get(x))
+                                .copied(),
+                        )
+                    },
+                )),
+            );
+            for explore_page in ExplorePage::all() {
+                section = section.add(
+                    widget::settings::item::builder(explore_page.title()).toggler(
+                        !self.config.explore_hidden.contains(explore_page),
+                        move |shown| Message::ExploreShown(*explore_page, shown),
+                    ),
+                );
+            }
+            sections.push(section.into());
+        }
+        sections.push(
+            widget::settings::view_section(fl!("network"))
+                .add(
+                    widget::settings::item::builder(fl!("proxy"))
+                        .description(fl!("proxy-description"))
+                        .control(
+                            widget::text_input(fl!("proxy-placeholder"), &self.proxy_input)
+                                .on_input(Message::ProxyInput)
+                                .on_submit(Message::ProxySubmit),
+                        ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("pause-metered"))
+                        .description(fl!("pause-metered-description"))
+                        .toggler(self.config.pause_metered, Message::PauseMetered),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("prefetch-screenshots"))
+                        .description(fl!("prefetch-screenshots-description"))
+                        .toggler(
+                            self.config.prefetch_screenshots,
+                            Message::PrefetchScreenshots,
+                        ),
+                )
+                .into(),
+        );
+        if !self.backends.is_empty() {
+            let mut section = widget::settings::view_section(fl!("software-sources"))
+                .add(
+                    widget::settings::item::builder(fl!("preferred-source")).control(
+                        widget::dropdown(
+                            &self.preferred_backends,
+                            Some(match self.config.preferred_backend {
+                                PreferredBackend::Flatpak => 0,
+                                PreferredBackend::System => 1,
+                            }),
+                            move |index| {
+                                Message::PreferredBackend(match index {
+                                    1 => PreferredBackend::System,
+                                    _ => PreferredBackend::Flatpak,
+                                })
+                            },
+                        ),
+                    ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("update-notifications")).toggler(
+                        self.config.update_notifications,
+                        Message::UpdateNotifications,
+                    ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("prerelease"))
+                        .description(fl!("prerelease-description"))
+                        .toggler(self.config.prerelease, Message::Prerelease),
+                );
+            if self.backends.contains_key("flatpak") {
+                section =
+                    section.add(
+                        widget::settings::item::builder(fl!("install-scope"))
+                            .description(fl!("install-scope-description"))
+                            .control(self.install_scope_view(
+                                self.config.install_scope,
+                                Message::InstallScope,
+                            )),
+                    );
+            }
+            for backend_name in self.backends.keys() {
+                let backend_name = *backend_name;
+                section = section.add(
+                    widget::settings::item::builder(backend_title(backend_name))
+                        .toggler(self.backend_enabled(backend_name), move |enabled| {
+                            Message::BackendEnabled(backend_name, enabled)
+                        }),
+                );
+            }
+            sections.push(section.into());
+        }
+        if self.backends.contains_key("flatpak") {
+            let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+            let mut section = widget::settings::view_section(fl!("flatpak-remotes"));
+            for remote in self.remotes.iter() {
+                let name = remote.name.clone();
+                let scope = remote.scope;
+                let scope_name = match scope {
+                    InstallScope::User => fl!("install-scope-user"),
+                    InstallScope::System => fl!("install-scope-system"),
+                };
+                section = section.add(
+                    widget::settings::item::builder(remote.title.clone())
+                        .description(format!("{} ({})", remote.url, scope_name))
+                        .toggler(remote.enabled, move |enabled| {
+                            Message::RemoteEnabled(name.clone(), scope, enabled)
+                        }),
+                );
+            }
+            section = section.add(
+                widget::settings::item::builder(fl!("add-remote"))
+                    .description(fl!("add-remote-description"))
+                    .control(
+                        widget::row::with_children(vec![
+                            widget::text_input(fl!("add-remote-placeholder"), &self.remote_input)
+                                .on_input(Message::RemoteInput)
+                                .on_submit(Message::RemoteAdd)
+                                .into(),
+                            widget::button::standard(fl!("add"))
+                                .on_press(Message::RemoteAdd)
+                                .into(),
+                        ])
+                        .align_items(Alignment::Center)
+                        .spacing(space_xxs),
+                    ),
+            );
+            sections.push(section.into());
+        }
+        let mut section = widget::settings::view_section(fl!("maintenance"))
+            .add(
+                widget::settings::item::builder(fl!("recently-viewed")).control(
+                    widget::button::standard(fl!("clear")).on_press(Message::RecentlyViewedClear),
+                ),
+            )
+            .add(
+                widget::settings::item::builder(fl!("export-installed")).control(
+                    widget::button::standard(fl!("export")).on_press(Message::ExportInstalled),
+                ),
+            )
+            .add(
+                widget::settings::item::builder(fl!("import-installed")).control(
+                    widget::button::standard(fl!("import")).on_press(Message::ImportInstalled),
+                ),
+            )
+            .add(
+                widget::settings::item::builder(fl!("developer-mode"))
+                    .description(fl!("developer-mode-description"))
+                    .toggler(self.config.developer_mode, Message::DeveloperMode),
+            );
+        let (unused_count, unused_size) = self.unused_summary();
+        if unused_count > 0 {
+            section = section.add(
+                widget::settings::item::builder(fl!(
+                    "unused-runtimes",
+                    count = unused_count,
+                    size = format_size(unused_size)
+                ))
+                .control(
+                    widget::button::standard(fl!("remove"))
+                        .on_press(Message::DialogPage(DialogPage::RemoveUnused)),
+                ),
+            );
+        }
+        sections.push(section.into());
+        sections.push(
+            widget::settings::view_section(fl!("about"))
+                .add(
+                    widget::settings::item::builder(fl!("cosmic-app-store")).control(
+                        widget::button::standard(fl!("about")).on_press(
+                            Message::ToggleContextPage(ContextPage::About, String::new()),
+                        ),
+                    ),
+                )
+                .into(),
+        );
+        widget::settings::view_column(sections).into()
+    }
+
+    fn operations(&self) -> Element<Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+        let mut column = widget::column::with_capacity(self.pending_operations.len().max(1))
+            .width(Length::Fill)
+            .spacing(space_s);
+        if self.pending_operations.is_empty() {
+            column = column.push(widget::text::body(fl!("no-operations")));
+        }
+        // Running operations are shown first, then queued ones in the order they will run
+        let running = self
+            .pending_operations
+            .keys()
+            .filter(|id| !self.operation_queue.contains(id));
+        for id in running.chain(self.operation_queue.iter()) {
+            let (op, progress) = match self.pending_operations.get(id) {
+                Some(some) => some,
+                None => continue,
+            };
+            let names: Vec<_> = op.infos.iter().map(|info| info.name.as_str()).collect();
+            let heading =
+                widget::text::heading(format!("{}: {}", op.kind.title(), names.join(", ")));
+            let mut op_column = widget::column::with_capacity(3).spacing(space_xxs);
+            match self.operation_queue.iter().position(|x| x == id) {
+                Some(queue_i) => {
+                    op_column = op_column.push(
+                        widget::row::with_children(vec![
+                            heading.width(Length::Fill).into(),
+                            icon_button(
+                                widget::icon::from_name("go-up-symbolic").size(16).icon(),
+                                fl!("move-up"),
+                                if queue_i > 0 {
+                                    Some(Message::OperationQueueMove(*id, -1))
+                                } else {
+                                    None
+                                },
+                            ),
+                            icon_button(
+                                widget::icon::from_name("go-down-symbolic").size(16).icon(),
+                                fl!("move-down"),
+                                if queue_i + 1 < self.operation_queue.len() {
+                                    Some(Message::OperationQueueMove(*id, 1))
+                                } else {
+                                    None
+                                },
+                            ),
+                            icon_button(
+                                widget::icon::from_name("window-close-symbolic")
+                                    .size(16)
+                                    .icon(),
+                                fl!("cancel"),
+                                Some(Message::OperationQueueCancel(*id)),
+                            ),
+                        ])
+                        .align_items(Alignment::Center),
+                    );
+                }
+                None => {
+                    op_column = op_column.push(heading).push(self.progress_bar(progress));
+                }
+            }
+            if let Some(text) = progress.text() {
+                op_column = op_column.push(widget::text::caption(text));
+            }
+            column = column.push(op_column);
+        }
+        column.into()
     }
 
-    fn settings(&self) -> Element<Message> {
-        let app_theme_selected = match self.config.app_theme {
-            AppTheme::Dark => 1,
-            AppTheme::Light => 2,
-            AppTheme::System => 0,
+    fn metadata(&self) -> Element<Message> {
+        let cosmic_theme::Spacing { space_s, .. } = theme::active().cosmic().spacing;
+        let Some(selected) = &self.selected_opt else {
+            return widget::text::body(fl!("no-metadata")).into();
         };
-        widget::settings::view_column(vec![widget::settings::view_section(fl!("appearance"))
-            .add(
-                widget::settings::item::builder(fl!("theme")).control(widget::dropdown(
-                    &self.app_themes,
-                    Some(app_theme_selected),
-                    move |index| {
-                        Message::AppTheme(match index {
-                            1 => AppTheme::Dark,
-                            2 => AppTheme::Light,
-                            _ => AppTheme::System,
-                        })
-                    },
-                )),
+        widget::column::with_capacity(2)
+            .push(
+                widget::button::standard(fl!("copy-metadata"))
+                    .leading_icon(icon_cache_handle("edit-copy-symbolic", 16))
+                    .on_press(Message::MetadataCopy),
             )
-            .into()])
-        .into()
+            .push(widget::text::monotext(metadata_text(selected)))
+            .width(Length::Fill)
+            .spacing(space_s)
+            .into()
     }
 
     fn release_notes(&self, index: usize) -> Element<Message> {
@@ -1496,14 +4421,8 @@ impl App {
                         version
                     )))
                     .push_maybe(
-                        date.and_then(|secs| {
-                            chrono::DateTime::from_timestamp(secs, 0).map(|dt| {
-                                dt.with_timezone(&chrono::Local)
-                                    .format("%Y-%m-%d")
-                                    .to_string()
-                            })
-                        })
-                        .map(widget::text),
+                        date.and_then(|secs| format_timestamp(secs, "%Y-%m-%d", &self.locale))
+                            .map(widget::text),
                     ),
             )
             .push(widget::scrollable(widget::text(
@@ -1516,7 +4435,10 @@ impl App {
     }
 
     fn view_responsive(&self, size: Size) -> Element<Message> {
-        let spacing = theme::active().cosmic().spacing;
+        let spacing = self
+            .config
+            .density
+            .spacing(theme::active().cosmic().spacing);
         let cosmic_theme::Spacing {
             space_m,
             space_s,
@@ -1526,6 +4448,26 @@ impl App {
             ..
         } = spacing;
         let grid_width = (size.width - 2.0 * space_s as f32).floor().max(0.0) as usize;
+        if !self.backends.is_empty()
+            && self
+                .backends
+                .keys()
+                .all(|backend_name| !self.backend_enabled(backend_name))
+        {
+            return widget::column::with_children(vec![
+                widget::text::title4(fl!("no-backends-enabled")).into(),
+                widget::button::standard(fl!("settings"))
+                    .on_press(Message::ToggleContextPage(
+                        ContextPage::Settings,
+                        String::new(),
+                    ))
+                    .into(),
+            ])
+            .padding([0, space_s])
+            .spacing(space_xxs)
+            .width(Length::Fill)
+            .into();
+        }
         match &self.selected_opt {
             Some(selected) => {
                 //TODO: more efficient checks
@@ -1535,10 +4477,14 @@ impl App {
                     .iter()
                     .chain(self.waiting_updates.iter())
                 {
-                    if backend_name == &selected.backend_name
-                        && source_id == &selected.info.source_id
-                        && package_id == &selected.id
-                    {
+                    if same_package(
+                        (backend_name, source_id, package_id),
+                        (
+                            selected.backend_name,
+                            &selected.info.source_id,
+                            &selected.id,
+                        ),
+                    ) {
                         waiting_refresh = true;
                         break;
                     }
@@ -1551,10 +4497,14 @@ impl App {
                 let mut update_opt = None;
                 if let Some(updates) = &self.updates {
                     for (backend_name, package) in updates {
-                        if backend_name == &selected.backend_name
-                            && &package.info.source_id == &selected.info.source_id
-                            && &package.id == &selected.id
-                        {
+                        if same_package(
+                            (backend_name, &package.info.source_id, &package.id),
+                            (
+                                selected.backend_name,
+                                &selected.info.source_id,
+                                &selected.id,
+                            ),
+                        ) {
                             update_opt = Some(Message::Operation(
                                 OperationKind::Update,
                                 backend_name,
@@ -1567,17 +4517,12 @@ impl App {
                 }
                 let mut progress_opt = None;
                 for (_id, (op, progress)) in self.pending_operations.iter() {
-                    if op.backend_name == selected.backend_name
-                        && op
-                            .infos
-                            .iter()
-                            .any(|info| info.source_id == selected.info.source_id)
-                        && op
-                            .package_ids
-                            .iter()
-                            .any(|package_id| package_id == &selected.id)
-                    {
-                        progress_opt = Some(*progress);
+                    if op.contains(
+                        selected.backend_name,
+                        &selected.info.source_id,
+                        &selected.id,
+                    ) {
+                        progress_opt = Some(progress);
                         break;
                     }
                 }
@@ -1604,27 +4549,53 @@ impl App {
                 );
                 let mut buttons = Vec::with_capacity(2);
                 if let Some(progress) = progress_opt {
-                    //TODO: get height from theme?
-                    buttons.push(
-                        widget::progress_bar(0.0..=100.0, progress)
-                            .height(Length::Fixed(4.0))
-                            .into(),
-                    )
+                    buttons.push(self.progress_bar(progress));
+                    if let Some(text) = progress.text() {
+                        buttons.push(widget::text::caption(text).into());
+                    }
                 } else if waiting_refresh {
                     // Do not show buttons while waiting for refresh
                 } else if is_installed {
-                    //TODO: what if there are multiple desktop IDs?
-                    if let Some(desktop_id) = selected.info.desktop_ids.first() {
+                    //TODO: what if there are multiple launchables?
+                    if let Some(launchable) = selected.info.launchable() {
                         buttons.push(
                             widget::button::suggested(fl!("open"))
-                                .on_press(Message::OpenDesktopId(desktop_id.clone()))
+                                .on_press(Message::OpenLaunchable(launchable.clone()))
                                 .into(),
                         );
                     }
                     if let Some(update) = update_opt {
+                        // Download size is shown once the update has been planned
+                        let label = match selected.update_size_opt {
+                            Some(size) => fl!("update-size", size = format_size(size)),
+                            None => fl!("update"),
+                        };
+                        buttons.push(widget::button::standard(label).on_press(update).into());
+                    }
+                    buttons.push(
+                        widget::button::standard(if self.is_held(&selected.id) {
+                            fl!("release-hold")
+                        } else {
+                            fl!("hold-updates")
+                        })
+                        .on_press(Message::ToggleHeld(selected.id.clone()))
+                        .into(),
+                    );
+                    // Flatpak refs are in the form app/ID/ARCH/BRANCH
+                    let flatpak_id_opt = selected
+                        .info
+                        .flatpak_refs
+                        .first()
+                        .and_then(|flatpak_ref| flatpak_ref.split('/').nth(1));
+                    if let (Some(editor), Some(flatpak_id)) =
+                        (&selected.permission_editor_opt, flatpak_id_opt)
+                    {
                         buttons.push(
-                            widget::button::standard(fl!("update"))
-                                .on_press(update)
+                            widget::button::standard(fl!("permissions"))
+                                .on_press(Message::OpenPermissions(
+                                    editor.clone(),
+                                    flatpak_id.to_string(),
+                                ))
                                 .into(),
                         );
                     }
@@ -1640,33 +4611,91 @@ impl App {
                         );
                     }
                 } else {
-                    buttons.push(
+                    // End-of-life apps can still be installed, but the replacement is suggested
+                    let install_message = Message::Operation(
+                        OperationKind::Install,
+                        selected.backend_name,
+                        selected.id.clone(),
+                        selected.info.clone(),
+                    );
+                    buttons.push(if selected.info.eol_opt.is_some() {
+                        widget::button::standard(fl!("install"))
+                            .on_press(install_message)
+                            .into()
+                    } else {
                         widget::button::suggested(fl!("install"))
-                            .on_press(Message::Operation(
-                                OperationKind::Install,
-                                selected.backend_name,
-                                selected.id.clone(),
-                                selected.info.clone(),
-                            ))
-                            .into(),
-                    )
+                            .on_press(install_message)
+                            .into()
+                    });
+                    if selected.backend_name == "flatpak" {
+                        buttons.push(self.install_scope_view(
+                            selected.install_scope,
+                            Message::SelectedInstallScope,
+                        ));
+                    }
+                }
+                if progress_opt.is_none() && !waiting_refresh {
+                    if let Some(eol_rebase) = &selected.info.eol_rebase_opt {
+                        buttons.push(
+                            widget::button::suggested(fl!("get-replacement"))
+                                .on_press(Message::SelectId(AppId::new(eol_rebase)))
+                                .into(),
+                        );
+                    }
                 }
+                buttons.push(favorite_button(
+                    &selected.id,
+                    self.config
+                        .favorites
+                        .iter()
+                        .any(|x| x == selected.id.normalized()),
+                ));
                 column = column.push(
                     widget::row::with_children(vec![
                         widget::icon::icon(selected.icon.clone())
                             .size(ICON_SIZE_DETAILS)
                             .into(),
+                        // Fill width so long names and summaries wrap
                         widget::column::with_children(vec![
-                            widget::text::title2(&selected.info.name).into(),
-                            widget::text(&selected.info.summary).into(),
+                            widget::text::title2(&selected.info.name)
+                                .width(Length::Fill)
+                                .into(),
+                            widget::text(&selected.info.summary)
+                                .width(Length::Fill)
+                                .into(),
                             widget::vertical_space(Length::Fixed(space_s.into())).into(),
                             widget::row::with_children(buttons).spacing(space_xs).into(),
                         ])
+                        .width(Length::Fill)
                         .into(),
                     ])
                     .align_items(Alignment::Center)
                     .spacing(space_m),
                 );
+                let branches = self.installed_branches(
+                    selected.backend_name,
+                    &selected.info.source_id,
+                    &selected.id,
+                );
+                if branches.len() > 1 {
+                    let current_opt = branches.iter().position(|package| package.current);
+                    let mut row = widget::row::with_capacity(branches.len() + 1)
+                        .align_items(Alignment::Center)
+                        .spacing(space_s)
+                        .push(widget::text::heading(fl!("branch-default")));
+                    for (branch_i, package) in branches.iter().enumerate() {
+                        let branch = package.branch_opt.clone().unwrap_or_default();
+                        let backend_name = selected.backend_name;
+                        let id = selected.id.clone();
+                        row = row.push(widget::radio(
+                            package.branch_opt.as_deref().unwrap_or_default(),
+                            branch_i,
+                            current_opt,
+                            move |_| Message::SwitchBranch(backend_name, id, branch),
+                        ));
+                    }
+                    column = column.push(row);
+                }
 
                 let sources_widget = widget::column::with_children(vec![if selected.sources.len()
                     == 1
@@ -1699,6 +4728,19 @@ impl App {
                 ])
                 .align_items(Alignment::Center)
                 .width(Length::Fill);
+                let content_rating_widget = widget::column::with_children(vec![
+                    widget::text::heading(match &selected.info.content_rating {
+                        Some(content_rating) => match content_rating.age() {
+                            0 => fl!("all-ages"),
+                            age => fl!("ages", age = age),
+                        },
+                        None => fl!("not-rated"),
+                    })
+                    .into(),
+                    widget::text::body(fl!("content-rating")).into(),
+                ])
+                .align_items(Alignment::Center)
+                .width(Length::Fill);
                 if grid_width < 416 {
                     column = column.push(
                         widget::column::with_children(vec![
@@ -1709,6 +4751,8 @@ impl App {
                             widget::divider::horizontal::default().into(),
                             downloads_widget.into(),
                             widget::divider::horizontal::default().into(),
+                            content_rating_widget.into(),
+                            widget::divider::horizontal::default().into(),
                         ])
                         .spacing(space_xxs),
                     );
@@ -1726,6 +4770,10 @@ impl App {
                                     .height(Length::Fixed(32.0))
                                     .into(),
                                 downloads_widget.into(),
+                                widget::divider::vertical::default()
+                                    .height(Length::Fixed(32.0))
+                                    .into(),
+                                content_rating_widget.into(),
                             ])
                             .align_items(Alignment::Center)
                             .into(),
@@ -1734,29 +4782,254 @@ impl App {
                         .spacing(space_xxs),
                     );
                 }
-                //TODO: proper image scroller
-                if let Some(screenshot) = selected.info.screenshots.get(selected.screenshot_shown) {
-                    //TODO: get proper image dimensions
-                    let image_height = Length::Fixed(480.0);
-                    let mut row = widget::row::with_capacity(3).align_items(Alignment::Center);
-                    {
-                        let mut button = widget::button::icon(
-                            widget::icon::from_name("go-previous-symbolic").size(16),
+                if let Some(donation_url) = selected.info.donation_url() {
+                    column = column.push(
+                        widget::row::with_children(vec![
+                            widget::button::standard(fl!("support-project"))
+                                .leading_icon(
+                                    widget::icon::from_name("emblem-favorite-symbolic")
+                                        .size(16)
+                                        .handle(),
+                                )
+                                .on_press(Message::OpenUrl(donation_url.to_string()))
+                                .into(),
+                            widget::text::caption(fl!(
+                                "support-project-description",
+                                app = selected.info.name.as_str()
+                            ))
+                            .into(),
+                        ])
+                        .align_items(Alignment::Center)
+                        .spacing(space_xs),
+                    );
+                }
+                let report_url_opt = selected.info.report_url();
+                if !selected.info.urls.is_empty() || report_url_opt.is_some() {
+                    let mut urls = selected.info.urls.clone();
+                    urls.sort_by_key(|url| match url {
+                        AppUrl::Homepage(_) => 0,
+                        AppUrl::BugTracker(_) => 1,
+                        AppUrl::Help(_) => 2,
+                        AppUrl::Donation(_) => 3,
+                    });
+                    let mut row = widget::row::with_capacity(urls.len() + 1).spacing(space_xs);
+                    for url in urls {
+                        let (label, url) = match url {
+                            // Bug tracker is shown as the report link, donation as its own button
+                            AppUrl::BugTracker(_) | AppUrl::Donation(_) => continue,
+                            AppUrl::Help(url) => (fl!("help"), url),
+                            AppUrl::Homepage(url) => (fl!("homepage"), url),
+                        };
+                        row = row.push(widget::button::link(label).on_press(Message::OpenUrl(url)));
+                    }
+                    if let Some(report_url) = report_url_opt {
+                        row = row.push(
+                            widget::button::link(fl!("bug-tracker"))
+                                .on_press(Message::OpenUrl(report_url)),
                         );
-                        if selected.screenshot_shown > 0 {
-                            button = button.on_press(Message::SelectedScreenshotShown(
-                                selected.screenshot_shown - 1,
-                            ));
+                    }
+                    column = column.push(row);
+                }
+                if let Some(content_rating) = &selected.info.content_rating {
+                    let categories: Vec<_> = content_rating
+                        .categories()
+                        .into_iter()
+                        .map(|category| match category {
+                            "drugs" => fl!("content-drugs"),
+                            "language" => fl!("content-language"),
+                            "money" => fl!("content-money"),
+                            "sex" => fl!("content-sex"),
+                            "social" => fl!("content-social"),
+                            "violence" => fl!("content-violence"),
+                            other => other.to_string(),
+                        })
+                        .collect();
+                    if !categories.is_empty() {
+                        column = column.push(widget::text::body(fl!(
+                            "content-rating-contains",
+                            categories = categories.join(", ")
+                        )));
+                    }
+                }
+                if !selected.dependents.is_empty() {
+                    column = column.push(widget::text::body(fl!(
+                        "required-by",
+                        apps = selected.dependents.join(", ")
+                    )));
+                }
+                if let Some(runtime) = selected
+                    .info
+                    .flatpak_runtime_opt
+                    .as_ref()
+                    .filter(|_| selected.backend_name == "flatpak")
+                {
+                    // Shown as name and branch, as the architecture is always the native one
+                    let mut parts = runtime.split('/');
+                    let name = parts.next().unwrap_or_default();
+                    let runtime = match parts.nth(1) {
+                        Some(branch) => format!("{} {}", name, branch),
+                        None => name.to_string(),
+                    };
+                    column = column.push(widget::text::body(
+                        if selected.runtime_installed_opt == Some(true) {
+                            fl!("runtime-installed", runtime = runtime)
+                        } else {
+                            fl!("runtime", runtime = runtime)
+                        },
+                    ));
+                }
+                if selected.info.prerelease {
+                    column = column.push(widget::warning(fl!("prerelease-warning")));
+                }
+                if let Some(eol) = &selected.info.eol_opt {
+                    column = column.push(widget::warning(if eol.is_empty() {
+                        fl!("eol-warning")
+                    } else {
+                        fl!("eol-warning-reason", reason = eol.as_str())
+                    }));
+                }
+                for warning in selected
+                    .info
+                    .relations
+                    .iter()
+                    .filter_map(|relation| relation_warning(relation, self.window_length_max))
+                {
+                    column = column.push(widget::warning(warning));
+                }
+                if !selected.info.agreements.is_empty() {
+                    let mut row = widget::row::with_capacity(selected.info.agreements.len())
+                        .spacing(space_xxs);
+                    for (agreement_i, agreement) in selected.info.agreements.iter().enumerate() {
+                        row = row.push(
+                            widget::button::link(agreement_title(agreement.kind)).on_press(
+                                Message::DialogPage(DialogPage::Agreement(
+                                    selected.info.clone(),
+                                    agreement_i,
+                                    None,
+                                )),
+                            ),
+                        );
+                    }
+                    column = column.push(row);
+                }
+                if !selected.info.languages.is_empty() {
+                    let mut section = widget::column::with_capacity(3).spacing(space_xxs);
+                    match selected.info.language_percentage(&self.locale) {
+                        Some(percentage) => {
+                            section = section.push(widget::text::heading(fl!(
+                                "language-supported",
+                                percentage = percentage
+                            )));
                         }
-                        row = row.push(button);
+                        // Apps are usually written in English, so it is not listed as a translation
+                        None if !self.locale.starts_with("en") => {
+                            section = section.push(widget::warning(fl!("language-unsupported")));
+                        }
+                        None => {}
+                    }
+                    if selected.languages_shown {
+                        let filter = selected.languages_filter.to_lowercase();
+                        let mut languages: Vec<&AppLanguage> = selected
+                            .info
+                            .languages
+                            .iter()
+                            .filter(|language| language.locale.to_lowercase().contains(&filter))
+                            .collect();
+                        languages.sort_by(|a, b| LANGUAGE_SORTER.compare(&a.locale, &b.locale));
+                        let languages: Vec<_> = languages
+                            .into_iter()
+                            .map(|language| {
+                                format!("{} ({}%)", language.locale, language.percentage)
+                            })
+                            .collect();
+                        section = section
+                            .push(
+                                widget::text_input::search_input(
+                                    fl!("filter-languages"),
+                                    &selected.languages_filter,
+                                )
+                                .width(Length::Fixed(240.0))
+                                .on_clear(Message::SelectedLanguagesFilter(String::new()))
+                                .on_input(Message::SelectedLanguagesFilter),
+                            )
+                            .push(widget::text::body(languages.join(", ")))
+                            .push(
+                                widget::button::link(fl!("hide-languages"))
+                                    .on_press(Message::SelectedLanguagesShown(false)),
+                            );
+                    } else {
+                        section = section.push(
+                            widget::button::link(fl!(
+                                "show-languages",
+                                count = selected.info.languages.len()
+                            ))
+                            .on_press(Message::SelectedLanguagesShown(true)),
+                        );
                     }
-                    let image_element = if let Some(image) =
-                        selected.screenshot_images.get(&selected.screenshot_shown)
+                    column = column.push(section);
+                }
+                //TODO: proper image scroller
+                let screenshots = selected
+                    .info
+                    .themed_screenshots(theme::active().cosmic().is_dark);
+                if let Some(&(screenshot_i, screenshot)) =
+                    screenshots.get(selected.screenshot_shown)
+                {
+                    let image_height = Length::Fixed(SCREENSHOT_HEIGHT_MAX);
+                    let mut row = widget::row::with_capacity(3).align_items(Alignment::Center);
+                    row = row.push(icon_button(
+                        widget::icon::from_name("go-previous-symbolic")
+                            .size(16)
+                            .icon(),
+                        fl!("previous-screenshot"),
+                        selected
+                            .screenshot_shown
+                            .checked_sub(1)
+                            .map(Message::SelectedScreenshotShown),
+                    ));
+                    let image_element = if let Some((image, dimensions_opt)) =
+                        selected.screenshot_images.get(&screenshot_i)
                     {
-                        widget::image(image.clone())
-                            .width(Length::Fill)
-                            .height(image_height)
-                            .into()
+                        match dimensions_opt {
+                            Some((width, height)) => {
+                                // Fit between the previous and next buttons, without upscaling
+                                let button_width = 16.0 + 2.0 * space_xxs as f32;
+                                let max_width = (grid_width as f32 - 2.0 * button_width).max(1.0);
+                                let scale = (max_width / *width as f32)
+                                    .min(SCREENSHOT_HEIGHT_MAX / *height as f32)
+                                    .min(1.0);
+                                widget::mouse_area(
+                                    widget::image(image.clone())
+                                        .width(Length::Fixed(*width as f32 * scale))
+                                        .height(Length::Fixed(*height as f32 * scale)),
+                                )
+                                .on_press(Message::ScreenshotFullscreen(true))
+                                .into()
+                            }
+                            None => widget::mouse_area(
+                                widget::image(image.clone())
+                                    .width(Length::Fill)
+                                    .height(image_height),
+                            )
+                            .on_press(Message::ScreenshotFullscreen(true))
+                            .into(),
+                        }
+                    } else if selected.screenshot_failed.contains(&screenshot_i) {
+                        widget::container(
+                            widget::column::with_children(vec![
+                                widget::text::body(fl!("screenshot-failed")).into(),
+                                widget::button::standard(fl!("retry"))
+                                    .on_press(Message::SelectedScreenshotRetry(screenshot_i))
+                                    .into(),
+                            ])
+                            .align_items(Alignment::Center)
+                            .spacing(space_xxs),
+                        )
+                        .width(Length::Fill)
+                        .height(image_height)
+                        .center_x()
+                        .center_y()
+                        .into()
                     } else {
                         widget::Space::new(Length::Fill, image_height).into()
                     };
@@ -1767,20 +5040,53 @@ impl App {
                         ])
                         .align_items(Alignment::Center),
                     );
-                    {
-                        let mut button = widget::button::icon(
-                            widget::icon::from_name("go-next-symbolic").size(16),
-                        );
-                        if selected.screenshot_shown + 1 < selected.info.screenshots.len() {
-                            button = button.on_press(Message::SelectedScreenshotShown(
+                    row = row.push(icon_button(
+                        widget::icon::from_name("go-next-symbolic").size(16).icon(),
+                        fl!("next-screenshot"),
+                        if selected.screenshot_shown + 1 < screenshots.len() {
+                            Some(Message::SelectedScreenshotShown(
                                 selected.screenshot_shown + 1,
-                            ));
-                        }
-                        row = row.push(button);
-                    }
+                            ))
+                        } else {
+                            None
+                        },
+                    ));
                     column = column.push(row);
                 }
-                column = column.push(widget::text::body(&selected.info.description));
+                match description_collapsed(&selected.info.description) {
+                    Some(collapsed) if !selected.description_expanded => {
+                        column = column.push(description_view(collapsed, &spacing));
+                        column = column.push(
+                            widget::button::link(fl!("read-more"))
+                                .on_press(Message::SelectedDescriptionExpanded(true)),
+                        );
+                    }
+                    Some(_) => {
+                        column =
+                            column.push(description_view(&selected.info.description, &spacing));
+                        column = column.push(
+                            widget::button::link(fl!("read-less"))
+                                .on_press(Message::SelectedDescriptionExpanded(false)),
+                        );
+                    }
+                    None => {
+                        column =
+                            column.push(description_view(&selected.info.description, &spacing));
+                    }
+                }
+
+                if !selected.similar.is_empty() {
+                    column = column.push(widget::text::title4(fl!("similar-apps")));
+                    column = column.push(SearchResult::grid_view(
+                        &selected.similar,
+                        &self.config.favorites,
+                        spacing,
+                        grid_width,
+                        self.config.max_grid_columns,
+                        self.config.badge_days,
+                        Message::SelectSimilarResult,
+                    ));
+                }
 
                 for release in selected.info.releases.iter() {
                     let mut release_col = widget::column::with_capacity(2).spacing(space_xxxs);
@@ -1788,14 +5094,8 @@ impl App {
                     release_col = release_col
                         .push(widget::text::title4(format!("Version {}", release.version)));
                     if let Some(timestamp) = release.timestamp {
-                        if let Some(utc) =
-                            chrono::DateTime::<chrono::Utc>::from_timestamp(timestamp, 0)
-                        {
-                            let local = chrono::DateTime::<chrono::Local>::from(utc);
-                            release_col = release_col.push(widget::text::body(format!(
-                                "{}",
-                                local.format("%b %-d, %-Y")
-                            )));
+                        if let Some(date) = format_timestamp(timestamp, "%x", &self.locale) {
+                            release_col = release_col.push(widget::text::body(date));
                         }
                     }
                     if let Some(description) = &release.description {
@@ -1806,6 +5106,67 @@ impl App {
                     break;
                 }
 
+                let packages = if !selected.info.flatpak_refs.is_empty() {
+                    selected.info.flatpak_refs.join(", ")
+                } else if !selected.info.pkgnames.is_empty() {
+                    selected.info.pkgnames.join(", ")
+                } else {
+                    selected.id.raw().to_string()
+                };
+                let mut section =
+                    widget::column::with_capacity(1 + selected.sources.len()).spacing(space_xs);
+                section = section.push(widget::text::title4(fl!("source-details")));
+                for (source_i, source) in selected.sources.iter().enumerate() {
+                    let current = selected_source == Some(source_i);
+                    let mut block = widget::column::with_capacity(6).spacing(space_xxxs);
+                    block = block.push(widget::text::heading(source.source_name.as_str()));
+                    block = block.push(widget::text::body(fl!(
+                        "package-backend",
+                        backend = backend_title(source.backend_name)
+                    )));
+                    block = block.push(widget::text::body(fl!(
+                        "package-source",
+                        source = source.source_id.as_str()
+                    )));
+                    // Only flatpak repositories record if the publisher is verified
+                    if source.verified {
+                        block = block.push(widget::text::body(fl!("source-verified")));
+                    } else if source.backend_name == "flatpak" {
+                        block = block.push(widget::text::body(fl!("source-unverified")));
+                    }
+                    if current {
+                        match selected.signed_opt {
+                            Some(true) => {
+                                block = block.push(widget::text::body(fl!(
+                                    "source-signed",
+                                    source = selected.info.source_name.as_str()
+                                )));
+                            }
+                            Some(false) => {
+                                block = block.push(widget::warning(fl!(
+                                    "source-unsigned-warning",
+                                    source = selected.info.source_name.as_str()
+                                )));
+                            }
+                            None => {}
+                        }
+                        block =
+                            block.push(widget::text::body(fl!("package-name", package = packages)));
+                    } else {
+                        block = block.push(
+                            widget::button::link(fl!("source-view"))
+                                .on_press(Message::SelectedSource(source_i)),
+                        );
+                    }
+                    section = section.push(block);
+                }
+                if self.config.developer_mode {
+                    section = section.push(widget::button::link(fl!("view-metadata")).on_press(
+                        Message::ToggleContextPage(ContextPage::Metadata, String::new()),
+                    ));
+                }
+                column = column.push(section);
+
                 column.into()
             }
             None => match &self.search_results {
@@ -1818,14 +5179,64 @@ impl App {
                         .spacing(space_xxs)
                         .width(Length::Fill);
                     //TODO: back button?
+                    column = column.push(self.kind_filter_view());
                     if results.is_empty() {
                         column =
                             column.push(widget::text(fl!("no-results", search = input.as_str())));
                     }
+                    // Offer browsing the pages of the most common categories in results
+                    let mut page_counts: Vec<(NavPage, Category, usize)> = Vec::new();
+                    for category in Category::all() {
+                        let nav_page = match category.nav_page() {
+                            Some(some) => some,
+                            None => continue,
+                        };
+                        let count = results
+                            .iter()
+                            .filter(|result| {
+                                result.info.categories.iter().any(|x| x == category.id())
+                            })
+                            .count();
+                        if count < SEARCH_CATEGORY_MIN {
+                            continue;
+                        }
+                        match page_counts
+                            .iter_mut()
+                            .find(|(page, _, _)| *page == nav_page)
+                        {
+                            Some(page_count) => {
+                                if count > page_count.2 {
+                                    *page_count = (nav_page, *category, count);
+                                }
+                            }
+                            None => page_counts.push((nav_page, *category, count)),
+                        }
+                    }
+                    page_counts.sort_by(|a, b| b.2.cmp(&a.2));
+                    if !page_counts.is_empty() {
+                        let mut row = widget::row::with_capacity(page_counts.len() + 1)
+                            .align_items(Alignment::Center)
+                            .spacing(space_xxs);
+                        row = row.push(widget::text::body(fl!("browse")));
+                        for (nav_page, category, count) in page_counts.into_iter().take(3) {
+                            row = row.push(
+                                widget::button::standard(format!(
+                                    "{} ({})",
+                                    nav_page.title(),
+                                    count
+                                ))
+                                .on_press(Message::BrowseCategory(category)),
+                            );
+                        }
+                        column = column.push(row);
+                    }
                     column = column.push(SearchResult::grid_view(
                         &results[..results_len],
+                        &self.config.favorites,
                         spacing,
                         grid_width,
+                        self.config.max_grid_columns,
+                        self.config.badge_days,
                         |result_i| Message::SelectSearchResult(result_i),
                     ));
                     column.into()
@@ -1855,36 +5266,47 @@ impl App {
                                         let results_len = cmp::min(results.len(), 256);
 
                                         if results.is_empty() {
-                                            //TODO: no results message?
+                                            column = column
+                                                .push(empty_view(fl!("no-apps-found"), &spacing));
                                         }
                                         column = column.push(SearchResult::grid_view(
                                             &results[..results_len],
+                                            &self.config.favorites,
                                             spacing,
                                             grid_width,
+                                            self.config.max_grid_columns,
+                                            self.config.badge_days,
                                             move |result_i| {
                                                 Message::SelectExploreResult(explore_page, result_i)
                                             },
                                         ));
                                     }
                                     None => {
-                                        //TODO: loading message?
+                                        column = column.push(loading_view());
                                     }
                                 }
                                 column.into()
                             }
                             None => {
-                                let explore_pages = ExplorePage::all();
+                                let explore_pages = self.explore_pages();
                                 let mut column =
-                                    widget::column::with_capacity(explore_pages.len() * 2)
+                                    widget::column::with_capacity(explore_pages.len() * 2 + 1)
                                         .padding([0, space_s])
                                         .spacing(space_xxs)
                                         .width(Length::Fill);
+                                if let Some(featured) = self.featured_view(&spacing) {
+                                    column = column.push(featured);
+                                }
                                 for explore_page in explore_pages.iter() {
                                     //TODO: ensure explore_page matches
                                     match self.explore_results.get(&explore_page) {
                                         Some(results) if !results.is_empty() => {
                                             let GridMetrics { cols, .. } =
-                                                SearchResult::grid_metrics(&spacing, grid_width);
+                                                SearchResult::grid_metrics(
+                                                    &spacing,
+                                                    grid_width,
+                                                    self.config.max_grid_columns,
+                                                );
 
                                             let max_results = match cols {
                                                 1 => 4,
@@ -1912,8 +5334,11 @@ impl App {
 
                                             column = column.push(SearchResult::grid_view(
                                                 &results[..results_len],
+                                                &self.config.favorites,
                                                 spacing,
                                                 grid_width,
+                                                self.config.max_grid_columns,
+                                                self.config.badge_days,
                                                 |result_i| {
                                                     Message::SelectExploreResult(
                                                         *explore_page,
@@ -1940,13 +5365,50 @@ impl App {
                                 if installed.is_empty() {
                                     column =
                                         column.push(widget::text(fl!("no-installed-applications")));
+                                } else {
+                                    let total_size: u64 = installed
+                                        .iter()
+                                        .filter_map(|result| {
+                                            self.installed_size(
+                                                result.backend_name,
+                                                &result.info.source_id,
+                                                &result.id,
+                                            )
+                                        })
+                                        .sum();
+                                    let mut row = widget::row::with_capacity(4)
+                                        .align_items(Alignment::Center)
+                                        .spacing(space_xs)
+                                        .push(widget::text::body(fl!(
+                                            "installed-total-size",
+                                            size = format_size(total_size)
+                                        )))
+                                        .push(widget::horizontal_space(Length::Fill));
+                                    if !self.installed_selected.is_empty() {
+                                        row = row.push(
+                                            widget::button::destructive(fl!(
+                                                "uninstall-selected-count",
+                                                count = self.installed_selected.len()
+                                            ))
+                                            .on_press(Message::UninstallSelected),
+                                        );
+                                    }
+                                    column = column.push(row.push(widget::checkbox(
+                                        fl!("sort-by-size"),
+                                        self.installed_sort_size,
+                                        Message::InstalledSortSize,
+                                    )));
                                 }
 
                                 let GridMetrics {
                                     cols,
                                     item_width,
                                     column_spacing,
-                                } = Package::grid_metrics(&spacing, grid_width);
+                                } = Package::grid_metrics(
+                                    &spacing,
+                                    grid_width,
+                                    self.config.max_grid_columns,
+                                );
                                 let mut grid = widget::grid();
                                 let mut col = 0;
                                 for (installed_i, result) in installed.iter().enumerate() {
@@ -1954,24 +5416,73 @@ impl App {
                                         grid = grid.insert_row();
                                         col = 0;
                                     }
-                                    let mut buttons = Vec::with_capacity(1);
-                                    if let Some(desktop_id) = result.info.desktop_ids.first() {
+                                    let mut buttons = Vec::with_capacity(3);
+                                    if let Some(launchable) = result.info.launchable() {
                                         buttons.push(
                                             widget::button::standard(fl!("open"))
-                                                .on_press(Message::OpenDesktopId(
-                                                    desktop_id.clone(),
+                                                .on_press(Message::OpenLaunchable(
+                                                    launchable.clone(),
                                                 ))
                                                 .into(),
                                         );
                                     } else {
                                         buttons.push(widget::vertical_space(Length::Shrink).into());
                                     }
+                                    buttons.push(widget::horizontal_space(Length::Fill).into());
+                                    // Branch is shown when multiple are installed
+                                    let branches = self.installed_branches(
+                                        result.backend_name,
+                                        &result.info.source_id,
+                                        &result.id,
+                                    );
+                                    if branches.len() > 1 {
+                                        if let Some(branch) = branches
+                                            .iter()
+                                            .find(|package| package.current)
+                                            .and_then(|package| package.branch_opt.as_deref())
+                                        {
+                                            buttons.push(
+                                                widget::text::caption(fl!(
+                                                    "branch",
+                                                    branch = branch,
+                                                    count = branches.len()
+                                                ))
+                                                .into(),
+                                            );
+                                        }
+                                    }
+                                    buttons.push(
+                                        widget::text::caption(
+                                            match self.installed_size(
+                                                result.backend_name,
+                                                &result.info.source_id,
+                                                &result.id,
+                                            ) {
+                                                Some(size) => format_size(size),
+                                                None => "—".to_string(),
+                                            },
+                                        )
+                                        .into(),
+                                    );
                                     grid = grid.push(
                                         widget::mouse_area(package_card_view(
                                             &result.info,
                                             &result.icon,
                                             buttons,
-                                            None,
+                                            // System packages cannot be uninstalled
+                                            (!result.id.is_system()).then(|| {
+                                                vec![widget::checkbox(
+                                                    "",
+                                                    self.installed_selected.contains(&installed_i),
+                                                    move |selected| {
+                                                        Message::InstalledSelect(
+                                                            installed_i,
+                                                            selected,
+                                                        )
+                                                    },
+                                                )
+                                                .into()]
+                                            }),
                                             &spacing,
                                             item_width,
                                         ))
@@ -1985,7 +5496,7 @@ impl App {
                                 );
                             }
                             None => {
-                                //TODO: loading message?
+                                column = column.push(loading_view());
                             }
                         }
                         column.into()
@@ -2005,11 +5516,37 @@ impl App {
                                         widget::button::standard(fl!("check-for-updates"))
                                             .on_press(Message::CheckUpdates),
                                     );
+                                } else if let Some(update_all) = &self.update_all_opt {
+                                    column = column.push(
+                                        widget::column::with_children(vec![
+                                            widget::progress_bar(
+                                                0.0..=100.0,
+                                                self.update_all_percent(update_all),
+                                            )
+                                            .height(Length::Fixed(4.0))
+                                            .into(),
+                                            widget::text::caption(fl!(
+                                                "update-all-progress",
+                                                done = update_all.updated + update_all.failed,
+                                                total = update_all.total
+                                            ))
+                                            .into(),
+                                        ])
+                                        .spacing(space_xxs),
+                                    );
                                 } else {
+                                    let mut update_all =
+                                        widget::button::standard(fl!("update-all"));
+                                    // Update all is disabled when every update is held
+                                    if updates
+                                        .iter()
+                                        .any(|(_, package)| !self.is_held(&package.id))
+                                    {
+                                        update_all = update_all
+                                            .on_press(Message::DialogPage(DialogPage::UpdateAll));
+                                    }
                                     column = column.push(widget::row::with_children(vec![
-                                        widget::button::standard(fl!("update-all"))
-                                            .on_press(Message::UpdateAll)
-                                            .into(),
+                                        update_all.into(),
                                         widget::horizontal_space(Length::Fill).into(),
                                     ]));
                                 }
@@ -2018,7 +5555,11 @@ impl App {
                                     cols,
                                     item_width,
                                     column_spacing,
-                                } = Package::grid_metrics(&spacing, grid_width);
+                                } = Package::grid_metrics(
+                                    &spacing,
+                                    grid_width,
+                                    self.config.max_grid_columns,
+                                );
                                 let mut grid = widget::grid();
                                 let mut col = 0;
                                 for (updates_i, (backend_name, package)) in
@@ -2030,53 +5571,57 @@ impl App {
                                         .iter()
                                         .chain(self.waiting_updates.iter())
                                     {
-                                        if other_backend_name == backend_name
-                                            && source_id == &package.info.source_id
-                                            && package_id == &package.id
-                                        {
+                                        if same_package(
+                                            (other_backend_name, source_id, package_id),
+                                            (backend_name, &package.info.source_id, &package.id),
+                                        ) {
                                             waiting_refresh = true;
                                             break;
                                         }
                                     }
                                     let mut progress_opt = None;
                                     for (_id, (op, progress)) in self.pending_operations.iter() {
-                                        if &op.backend_name == backend_name
-                                            && op.infos.iter().any(|info| {
-                                                info.source_id == package.info.source_id
-                                            })
-                                            && op
-                                                .package_ids
-                                                .iter()
-                                                .any(|package_id| package_id == &package.id)
-                                        {
-                                            progress_opt = Some(*progress);
+                                        if op.contains(
+                                            backend_name,
+                                            &package.info.source_id,
+                                            &package.id,
+                                        ) {
+                                            progress_opt = Some(progress);
                                             break;
                                         }
                                     }
                                     let controls = if let Some(progress) = progress_opt {
-                                        vec![widget::progress_bar(0.0..=100.0, progress)
-                                            .height(Length::Fixed(4.0))
-                                            .into()]
+                                        let mut controls = vec![self.progress_bar(progress)];
+                                        if let Some(text) = progress.text() {
+                                            controls.push(widget::text::caption(text).into());
+                                        }
+                                        controls
                                     } else if waiting_refresh {
                                         vec![]
                                     } else {
-                                        vec![widget::button::standard(fl!("update"))
-                                            .on_press(Message::Operation(
-                                                OperationKind::Update,
-                                                backend_name,
-                                                package.id.clone(),
-                                                package.info.clone(),
-                                            ))
-                                            .into()]
+                                        let mut controls =
+                                            vec![widget::button::standard(fl!("update"))
+                                                .on_press(Message::Operation(
+                                                    OperationKind::Update,
+                                                    backend_name,
+                                                    package.id.clone(),
+                                                    package.info.clone(),
+                                                ))
+                                                .into()];
+                                        if self.is_held(&package.id) {
+                                            controls
+                                                .push(widget::text::caption(fl!("held")).into());
+                                        }
+                                        controls
                                     };
-                                    let top_controls = Some(vec![widget::button::icon(
-                                        widget::icon::from_name("help-info-symbolic"),
-                                    )
-                                    .on_press(Message::ToggleContextPage(
-                                        ContextPage::ReleaseNotes(updates_i),
-                                        package.info.name.clone(),
-                                    ))
-                                    .into()]);
+                                    let top_controls = Some(vec![icon_button(
+                                        widget::icon::from_name("help-info-symbolic").icon(),
+                                        fl!("release-notes"),
+                                        Some(Message::ToggleContextPage(
+                                            ContextPage::ReleaseNotes(updates_i),
+                                            package.info.name.clone(),
+                                        )),
+                                    )]);
                                     if col >= cols {
                                         grid = grid.insert_row();
                                         col = 0;
@@ -2109,7 +5654,14 @@ impl App {
                             .padding([0, space_s])
                             .spacing(space_xxs)
                             .width(Length::Fill);
-                        column = column.push(widget::text::title2(nav_page.title()));
+                        column = column.push(
+                            widget::row::with_children(vec![
+                                widget::text::title2(nav_page.title()).into(),
+                                widget::horizontal_space(Length::Fill).into(),
+                                self.kind_filter_view(),
+                            ])
+                            .align_items(Alignment::Center),
+                        );
                         //TODO: ensure category matches?
                         match &self.category_results {
                             Some((_, results)) => {
@@ -2117,18 +5669,22 @@ impl App {
                                 let results_len = cmp::min(results.len(), 256);
 
                                 if results.is_empty() {
-                                    //TODO: no results message?
+                                    column = column
+                                        .push(empty_view(fl!("no-apps-in-category"), &spacing));
                                 }
 
                                 column = column.push(SearchResult::grid_view(
                                     &results[..results_len],
+                                    &self.config.favorites,
                                     spacing,
                                     grid_width,
+                                    self.config.max_grid_columns,
+                                    self.config.badge_days,
                                     |result_i| Message::SelectCategoryResult(result_i),
                                 ));
                             }
                             None => {
-                                //TODO: loading message?
+                                column = column.push(loading_view());
                             }
                         }
                         column.into()
@@ -2137,6 +5693,54 @@ impl App {
             },
         }
     }
+
+    fn kind_filter_view(&self) -> Element<Message> {
+        widget::dropdown(
+            &self.app_kinds,
+            AppKind::all().iter().position(|x| *x == self.kind_filter),
+            |index| Message::KindFilter(AppKind::all().get(index).copied().unwrap_or_default()),
+        )
+        .into()
+    }
+
+    /// Show a transient message that is dismissed after a while
+    fn toast(&mut self, message: String) -> Command<Message> {
+        self.toasts
+            .push(Toast::new(message))
+            .map(cosmic::app::Message::App)
+    }
+
+    /// Show a transient message that can undo a change by running an operation
+    fn toast_undo(&mut self, message: String, undo: Operation) -> Command<Message> {
+        self.toasts
+            .push(
+                Toast::new(message)
+                    .action(fl!("undo"), move |id| Message::ToastUndo(id, undo.clone()))
+                    .duration(UNDO_DURATION),
+            )
+            .map(cosmic::app::Message::App)
+    }
+
+    fn install_scope_view<'a>(
+        &'a self,
+        install_scope: InstallScope,
+        on_select: impl Fn(InstallScope) -> Message + 'static,
+    ) -> Element<'a, Message> {
+        widget::dropdown(
+            &self.install_scopes,
+            Some(match install_scope {
+                InstallScope::User => 0,
+                InstallScope::System => 1,
+            }),
+            move |index| {
+                on_select(match index {
+                    1 => InstallScope::System,
+                    _ => InstallScope::User,
+                })
+            },
+        )
+        .into()
+    }
 }
 
 /// Implement [`Application`] to integrate with COSMIC.
@@ -2172,20 +5776,19 @@ impl Application for App {
         });
 
         let app_themes = vec![fl!("match-desktop"), fl!("dark"), fl!("light")];
-
-        let mut nav_model = widget::nav_bar::Model::default();
-        for &nav_page in NavPage::all() {
-            let id = nav_model
-                .insert()
-                .icon(nav_page.icon())
-                .text(nav_page.title())
-                .data::<NavPage>(nav_page)
-                .id();
-            if nav_page == NavPage::default() {
-                //TODO: save last page?
-                nav_model.activate(id);
-            }
-        }
+        let densities = vec![fl!("comfortable"), fl!("compact")];
+        let mut grid_columns = vec![fl!("automatic")];
+        grid_columns.extend((1..=GRID_COLUMNS_MAX).map(|cols| cols.to_string()));
+        let mut badge_days = vec![fl!("badges-off")];
+        badge_days.extend(BADGE_DAYS.iter().map(|days| fl!("badge-days", days = days)));
+        let mut explore_firsts = vec![fl!("explore-first-default")];
+        explore_firsts.extend(ExplorePage::all().iter().map(|x| x.title()));
+        let preferred_backends = vec![fl!("backend-flatpak"), fl!("backend-packagekit")];
+        let install_scopes = vec![fl!("install-scope-user"), fl!("install-scope-system")];
+        let app_kinds = AppKind::all().iter().map(|x| app_kind_title(*x)).collect();
+
+        let http_client = http_client(flags.config.proxy.as_deref());
+        let proxy_input = flags.config.proxy.clone().unwrap_or_default();
 
         let mut app = App {
             core,
@@ -2194,34 +5797,85 @@ impl Application for App {
             config: flags.config,
             locale,
             app_themes,
+            densities,
+            grid_columns,
+            badge_days,
+            explore_firsts,
+            preferred_backends,
+            install_scopes,
+            app_kinds,
+            http_client,
+            proxy_input,
             apps: Arc::new(Apps::new()),
             backends: Backends::new(),
+            backend_errors: BackendErrors::new(),
+            backends_loaded: false,
+            backend_versions: Vec::new(),
+            metadata_refresh: BTreeMap::new(),
+            metadata_refreshed: false,
+            metered: false,
+            instance_lock_opt: None,
+            instance_running: false,
+            remotes: Vec::new(),
+            remote_input: String::new(),
             context_page: ContextPage::Settings,
             dialog_pages: VecDeque::new(),
             explore_page_opt: None,
             key_binds: key_binds(),
-            nav_model,
+            nav_model: widget::nav_bar::Model::default(),
             notification_opt: None,
             pending_operation_id: 0,
             pending_operations: BTreeMap::new(),
+            operation_queue: VecDeque::new(),
+            progress_pulse: 0.0,
+            restart_opt: None,
             failed_operations: BTreeMap::new(),
+            refreshing: false,
             scrollable_id: widget::Id::unique(),
             scroll_views: HashMap::new(),
             search_active: false,
             search_id: widget::Id::unique(),
             search_input: String::new(),
+            search_installed: false,
+            search_generation: 0,
+            search_stream_opt: None,
+            search_suggest_opt: None,
+            search_suggestions: Vec::new(),
+            search_suggestion_opt: None,
             window_id_opt: Some(window::Id::MAIN),
+            window_focused: true,
+            window_length_max: 0,
             installed: None,
             updates: None,
+            updates_notified: BTreeSet::new(),
+            updates_notification_handle: Arc::new(Mutex::new(None)),
+            update_all_opt: None,
+            review_plan_opt: None,
+            review_suggests: Vec::new(),
+            unused: None,
             waiting_installed: Vec::new(),
             waiting_updates: Vec::new(),
             category_results: None,
+            category_results_cache: HashMap::new(),
             explore_results: HashMap::new(),
+            featured_images: HashMap::new(),
+            featured_shown: 0,
+            screenshot_prefetch_opt: None,
+            screenshot_prefetched: HashMap::new(),
             installed_results: None,
+            installed_selected: BTreeSet::new(),
+            installed_sort_size: false,
+            kind_filter: AppKind::App,
             search_results: None,
             selected_opt: None,
+            toasts: Toasts::new(Message::ToastDismiss),
         };
 
+        //TODO: save last page?
+        app.update_nav_model();
+
+        app.instance_lock();
+
         let command = Command::batch([app.update_title(), app.update_backends(false)]);
         (app, command)
     }
@@ -2237,17 +5891,7 @@ impl Application for App {
     fn dbus_activation(&mut self, msg: DbusActivationMessage) -> Command<Message> {
         //TODO: parse msg
         log::info!("{:?}", msg);
-        if self.window_id_opt.is_none() {
-            let (window_id, command) = window::spawn(window::Settings {
-                min_size: Some(Size::new(360.0, 180.0)),
-                decorations: false,
-                exit_on_close_request: false,
-                ..Default::default()
-            });
-            self.window_id_opt = Some(window_id);
-            return command;
-        }
-        Command::none()
+        self.window_open()
     }
 
     fn on_app_exit(&mut self) -> Option<Message> {
@@ -2255,26 +5899,56 @@ impl Application for App {
     }
 
     fn on_escape(&mut self) -> Command<Message> {
-        if self.core.window.show_context {
+        if let Some(selected) = self
+            .selected_opt
+            .as_mut()
+            .filter(|selected| selected.screenshot_fullscreen)
+        {
+            // Close zoomed screenshot if open
+            selected.screenshot_fullscreen = false;
+        } else if self.core.window.show_context {
             // Close context drawer if open
             self.core.window.show_context = false;
+        } else if self.search_active && !self.search_suggestions.is_empty() {
+            // Close search suggestions if open
+            self.search_suggestions_clear();
         } else if self.search_active {
             // Close search if open
             self.search_active = false;
+            self.search_stream_opt = None;
             if self.search_results.take().is_some() {
                 return self.update_scroll();
             }
+        } else if self.selected_opt.take().is_some() {
+            // Go back from details if open
+            return self.update_scroll();
         }
         Command::none()
     }
 
     fn on_nav_select(&mut self, id: widget::nav_bar::Id) -> Command<Message> {
+        if let Some(nav_section) = self.nav_model.data::<NavSection>(id) {
+            // Section headers do not change the page, the categories header toggles its section
+            if *nav_section == NavSection::Categories {
+                return self.update(Message::NavCategoriesCollapsed(
+                    !self.config.nav_categories_collapsed,
+                ));
+            }
+            return Command::none();
+        }
         self.category_results = None;
         self.explore_page_opt = None;
         self.search_active = false;
+        self.search_installed = false;
         self.search_results = None;
+        self.search_stream_opt = None;
+        self.search_suggestions_clear();
         self.selected_opt = None;
         self.nav_model.activate(id);
+        if self.config.nav_categories_collapsed {
+            // Hide the previously active category
+            self.update_nav_model();
+        }
         let mut commands = Vec::with_capacity(2);
         self.scroll_views.clear();
         commands.push(self.update_scroll());
@@ -2283,7 +5957,14 @@ impl Application for App {
             .active_data::<NavPage>()
             .and_then(|nav_page| nav_page.categories())
         {
-            commands.push(self.categories(categories));
+            match self.category_results_cache.get(categories) {
+                Some(results) => {
+                    self.category_results = Some((categories, results.clone()));
+                }
+                None => {
+                    commands.push(self.categories(categories));
+                }
+            }
         }
         match self.nav_model.active_data::<NavPage>() {
             Some(NavPage::Updates) => {
@@ -2310,6 +5991,7 @@ impl Application for App {
                                     stringify!($name),
                                     err
                                 );
+                                return self.toast(fl!("config-save-failed"));
                             }
                         }
                     }
@@ -2329,11 +6011,81 @@ impl Application for App {
                 config_set!(app_theme, app_theme);
                 return self.update_config();
             }
-            Message::Backends(backends) => {
+            Message::BackendEnabled(backend_name, enabled) => {
+                let mut disabled_backends = self.config.disabled_backends.clone();
+                if enabled {
+                    disabled_backends.remove(backend_name);
+                } else {
+                    disabled_backends.insert(backend_name.to_string());
+                }
+                config_set!(disabled_backends, disabled_backends);
+                // Results are updated when installed is reloaded
+                return Command::batch([
+                    self.update_installed(),
+                    self.update_updates(),
+                    self.update_unused(),
+                ]);
+            }
+            Message::BackendErrorsDismiss => {
+                self.backend_errors.clear();
+            }
+            Message::BackendVersions(backend_versions) => {
+                self.backend_versions = backend_versions;
+            }
+            Message::Backends(backends, errors) => {
+                // Icons may have changed when backends were reloaded
+                app_icon_cache_clear();
                 self.backends = backends;
-                return Command::batch([self.update_installed(), self.update_updates()]);
+                self.backend_errors = errors;
+                self.backends_loaded = true;
+                // Refresh is skipped on metered connections, and tried again on the next load
+                if !self.metadata_refreshed && !self.downloads_paused() {
+                    self.metadata_refreshed = true;
+                    for (backend_name, backend) in self.enabled_backends() {
+                        if backend.metadata_stale() {
+                            log::info!("{} backend has stale appstream data", backend_name);
+                            self.metadata_refresh
+                                .insert(backend_name, OperationProgress::default());
+                        }
+                    }
+                }
+                return Command::batch([
+                    self.update_installed(),
+                    self.update_updates(),
+                    self.update_unused(),
+                ]);
             }
-            Message::CategoryResults(categories, results) => {
+            Message::BadgeDays(badge_days) => {
+                config_set!(badge_days, badge_days);
+            }
+            Message::BranchSwitched => {
+                // Reload installed to show the new default branch
+                return self.update_installed();
+            }
+            Message::BrowseCategory(category) => {
+                let nav_page = match category.nav_page() {
+                    Some(some) => some,
+                    None => {
+                        log::warn!("no page found for category {:?}", category);
+                        return Command::none();
+                    }
+                };
+                if self.config.nav_categories_collapsed {
+                    // Make sure the page is shown in the collapsed categories section
+                    self.update_nav_model_active(nav_page);
+                }
+                let id_opt = self
+                    .nav_model
+                    .iter()
+                    .find(|id| self.nav_model.data::<NavPage>(*id) == Some(&nav_page));
+                if let Some(id) = id_opt {
+                    return self.on_nav_select(id);
+                }
+            }
+            Message::CategoryResults(categories, mut results) => {
+                self.hide_installed(&mut results);
+                self.category_results_cache
+                    .insert(categories, results.clone());
                 self.category_results = Some((categories, results));
                 return self.update_scroll();
             }
@@ -2353,14 +6105,45 @@ impl Application for App {
                 if config != self.config {
                     log::info!("update config");
                     //TODO: update syntax theme by clearing tabs, only if needed
+                    if config.proxy != self.config.proxy {
+                        self.http_client = http_client(config.proxy.as_deref());
+                        self.proxy_input = config.proxy.clone().unwrap_or_default();
+                    }
+                    let nav_changed =
+                        config.nav_categories_collapsed != self.config.nav_categories_collapsed;
                     self.config = config;
+                    if nav_changed {
+                        self.update_nav_model();
+                    }
                     return self.update_config();
                 }
             }
+            Message::Density(density) => {
+                config_set!(density, density);
+            }
+            Message::DeveloperMode(developer_mode) => {
+                config_set!(developer_mode, developer_mode);
+                if !developer_mode && self.context_page == ContextPage::Metadata {
+                    self.core.window.show_context = false;
+                }
+            }
             Message::DialogCancel => {
-                self.dialog_pages.pop_front();
+                if let Some(DialogPage::Review(_)) = self.dialog_pages.pop_front() {
+                    // Queued operations are discarded
+                    self.review_plan_opt = None;
+                    self.review_suggests.clear();
+                }
             }
             Message::DialogConfirm => match self.dialog_pages.pop_front() {
+                Some(DialogPage::RemoteDisable(name, scope)) => {
+                    return self.remote_change(None, Some((name, scope, false)));
+                }
+                Some(DialogPage::Agreement(_info, agreement_i, Some((op, info_i)))) => {
+                    self.operation_agreements(op, Some((info_i, agreement_i)));
+                }
+                Some(DialogPage::RemoveUnused) => {
+                    return self.update(Message::RemoveUnused);
+                }
                 Some(DialogPage::Uninstall(backend_name, id, info)) => {
                     return self.update(Message::Operation(
                         OperationKind::Uninstall,
@@ -2369,18 +6152,106 @@ impl Application for App {
                         info,
                     ));
                 }
+                Some(DialogPage::Review(ops)) => {
+                    self.review_plan_opt = None;
+                    for op in ops {
+                        self.operation_start(op);
+                    }
+                    // Checked suggestions are installed after the reviewed operations
+                    let mut suggest_ops: Vec<Operation> = Vec::new();
+                    for suggest in std::mem::take(&mut self.review_suggests) {
+                        if !suggest.checked {
+                            continue;
+                        }
+                        match suggest_ops.iter_mut().find(|op| {
+                            op.backend_name == suggest.backend_name && op.scope == suggest.scope
+                        }) {
+                            Some(op) => {
+                                op.package_ids.push(suggest.id);
+                                op.infos.push(suggest.info);
+                            }
+                            None => suggest_ops.push(Operation {
+                                kind: OperationKind::Install,
+                                backend_name: suggest.backend_name,
+                                package_ids: vec![suggest.id],
+                                infos: vec![suggest.info],
+                                scope: suggest.scope,
+                            }),
+                        }
+                    }
+                    for op in suggest_ops {
+                        self.operation_start(op);
+                    }
+                }
+                Some(DialogPage::UpdateAll) => {
+                    return self.update(Message::UpdateAll);
+                }
                 _ => {}
             },
             Message::DialogPage(dialog_page) => {
                 self.dialog_pages.push_back(dialog_page);
             }
+            Message::ExploreFirst(explore_first) => {
+                config_set!(explore_first, explore_first);
+            }
             Message::ExplorePage(explore_page_opt) => {
                 self.explore_page_opt = explore_page_opt;
                 return self.update_scroll();
             }
-            Message::ExploreResults(explore_page, results) => {
+            Message::ExploreResults(explore_page, mut results) => {
+                self.hide_installed(&mut results);
                 self.explore_results.insert(explore_page, results);
             }
+            Message::ExploreShown(explore_page, shown) => {
+                let mut explore_hidden = self.config.explore_hidden.clone();
+                if shown {
+                    explore_hidden.remove(&explore_page);
+                } else {
+                    explore_hidden.insert(explore_page);
+                }
+                config_set!(explore_hidden, explore_hidden);
+            }
+            Message::ExportInstalled => {
+                return self.export_installed();
+            }
+            Message::FeaturedImage(url, data) => {
+                self.featured_images
+                    .insert(url, widget::image::Handle::from_memory(data));
+            }
+            Message::FeaturedNext => {
+                let featured_len = self.featured().len();
+                if featured_len > 0 {
+                    self.featured_shown = (self.featured_shown + 1) % featured_len;
+                }
+            }
+            Message::FeaturedShown(featured_shown) => {
+                self.featured_shown = featured_shown;
+            }
+            Message::GridColumns(max_grid_columns) => {
+                config_set!(max_grid_columns, max_grid_columns);
+            }
+            Message::HideInstalled(hide_installed) => {
+                config_set!(hide_installed, hide_installed);
+                self.category_results_cache.clear();
+                // Results are filtered when received, so they are searched again
+                let mut commands = Vec::new();
+                for explore_page in ExplorePage::all() {
+                    commands.push(self.explore_results(*explore_page));
+                }
+                if let Some((categories, _)) = &self.category_results {
+                    commands.push(self.categories(*categories));
+                }
+                if self.search_results.is_some() {
+                    commands.push(self.search());
+                }
+                return Command::batch(commands);
+            }
+            Message::ImportInstalled => {
+                return self.import_installed();
+            }
+            Message::InstallScope(install_scope) => {
+                config_set!(install_scope, install_scope);
+            }
             Message::Installed(installed) => {
                 self.installed = Some(installed);
                 self.waiting_installed.clear();
@@ -2392,83 +6263,467 @@ impl Application for App {
                 for explore_page in ExplorePage::all() {
                     commands.push(self.explore_results(*explore_page));
                 }
-                return Command::batch(commands);
+                if let Some((categories, _)) = &self.category_results {
+                    commands.push(self.categories(*categories));
+                }
+                return Command::batch(commands);
+            }
+            Message::InstalledResults(installed_results) => {
+                // Selection is by index, so it is cleared when results change
+                self.installed_selected.clear();
+                self.installed_results = Some(installed_results);
+            }
+            Message::InstalledSelect(installed_i, selected) => {
+                if selected {
+                    self.installed_selected.insert(installed_i);
+                } else {
+                    self.installed_selected.remove(&installed_i);
+                }
+            }
+            Message::InstalledSortSize(installed_sort_size) => {
+                self.installed_sort_size = installed_sort_size;
+                return self.installed_results();
+            }
+            Message::Key(modifiers, key) => {
+                // Arrow keys move through search suggestions
+                if self.search_active && !self.search_suggestions.is_empty() && modifiers.is_empty()
+                {
+                    let last = self.search_suggestions.len() - 1;
+                    match key {
+                        Key::Named(Named::ArrowDown) => {
+                            self.search_suggestion_opt = Some(match self.search_suggestion_opt {
+                                Some(suggestion_i) => cmp::min(suggestion_i + 1, last),
+                                None => 0,
+                            });
+                            return Command::none();
+                        }
+                        Key::Named(Named::ArrowUp) => {
+                            self.search_suggestion_opt = self
+                                .search_suggestion_opt
+                                .and_then(|suggestion_i| suggestion_i.checked_sub(1));
+                            return Command::none();
+                        }
+                        _ => {}
+                    }
+                }
+                for (key_bind, action) in self.key_binds.iter() {
+                    if key_bind.matches(modifiers, &key) {
+                        return self.update(action.message());
+                    }
+                }
+            }
+            Message::KindFilter(kind_filter) => {
+                self.kind_filter = kind_filter;
+                self.category_results_cache.clear();
+                let mut commands = Vec::new();
+                if let Some((categories, _)) = &self.category_results {
+                    commands.push(self.categories(*categories));
+                }
+                if self.search_results.is_some() {
+                    commands.push(self.search());
+                }
+                return Command::batch(commands);
+            }
+            Message::MaybeExit => {
+                if self.window_id_opt.is_none()
+                    && self.pending_operations.is_empty()
+                    && !self.config.update_notifications
+                {
+                    // Exit if window is closed and there are no pending operations, unless
+                    // updates are checked for in the background
+                    process::exit(0);
+                }
+            }
+            Message::MetadataCopy => {
+                if let Some(selected) = &self.selected_opt {
+                    return clipboard::write(metadata_text(selected));
+                }
+            }
+            Message::MetadataProgress(backend_name, progress) => {
+                if let Some(metadata_progress) = self.metadata_refresh.get_mut(backend_name) {
+                    *metadata_progress = progress;
+                }
+            }
+            Message::MetadataRefreshed(backend_name, res) => {
+                self.metadata_refresh.remove(backend_name);
+                let mut commands = Vec::with_capacity(2);
+                if let Err(err) = res {
+                    log::warn!("failed to refresh {} appstream data: {}", backend_name, err);
+                    commands.push(self.toast(fl!(
+                        "metadata-refresh-failed",
+                        source = backend_title(backend_name)
+                    )));
+                }
+                if self.metadata_refresh.is_empty() {
+                    // Load backends again to use the new appstream data
+                    commands.push(self.update_backends(false));
+                }
+                return Command::batch(commands);
+            }
+            Message::Metered(metered) => {
+                if metered != self.metered {
+                    log::info!("network metered: {}", metered);
+                    self.metered = metered;
+                }
+            }
+            Message::NavCategoriesCollapsed(nav_categories_collapsed) => {
+                config_set!(nav_categories_collapsed, nav_categories_collapsed);
+                self.update_nav_model();
+            }
+            Message::Notification(notification) => {
+                self.notification_opt = Some(notification);
+            }
+            Message::OpenLaunchable(launchable) => match launchable {
+                AppLaunchable::DesktopId(desktop_id) => {
+                    return self.open_desktop_id(desktop_id, Vec::new());
+                }
+                AppLaunchable::Service(service) => {
+                    return self.open_service(service);
+                }
+                AppLaunchable::CockpitManifest(manifest) => {
+                    // Cockpit serves its pages from the local web console
+                    self.open_url(&format!("https://localhost:9090/{}", manifest));
+                }
+                AppLaunchable::Url(url) => {
+                    self.open_url(&url);
+                }
+            },
+            Message::OpenPermissions(editor, flatpak_id) => {
+                return self.open_desktop_id(editor, vec![flatpak_id]);
+            }
+            Message::OpenUrl(url) => {
+                self.open_url(&url);
+            }
+            Message::Operation(kind, backend_name, package_id, info) => {
+                let toast_command = if self.metered && kind != OperationKind::Uninstall {
+                    self.toast(fl!("metered-operation-warning"))
+                } else {
+                    Command::none()
+                };
+                // The details page can override where an app is installed, other operations
+                // apply to the installation the app is in
+                let scope = match &self.selected_opt {
+                    _ if kind != OperationKind::Install => {
+                        self.installed_scope(backend_name, &info.source_id, &package_id)
+                    }
+                    Some(selected) if selected.id == package_id => selected.install_scope,
+                    _ => self.config.install_scope,
+                };
+                let op = Operation {
+                    kind,
+                    backend_name,
+                    package_ids: vec![package_id],
+                    infos: vec![info],
+                    scope,
+                };
+                return Command::batch([toast_command, self.operation_reviewed(op)]);
+            }
+            Message::OperationQueueCancel(id) => {
+                // Only queued operations can be cancelled, as backends cannot stop running ones
+                if let Some(queue_i) = self.operation_queue.iter().position(|x| *x == id) {
+                    self.operation_queue.remove(queue_i);
+                    self.pending_operations.remove(&id);
+                    if let Some(update_all) = &self.update_all_opt {
+                        if update_all.running_opt == Some(id) {
+                            self.update_all_next();
+                        }
+                    }
+                    return self.update_notification();
+                }
+            }
+            Message::OperationQueueMove(id, offset) => {
+                if let Some(queue_i) = self.operation_queue.iter().position(|x| *x == id) {
+                    let new_i = queue_i.saturating_add_signed(offset);
+                    if new_i < self.operation_queue.len() {
+                        self.operation_queue.swap(queue_i, new_i);
+                    }
+                }
+            }
+            Message::PauseMetered(pause_metered) => {
+                config_set!(pause_metered, pause_metered);
+            }
+            Message::PendingComplete(id, restart_opt) => {
+                self.restart_opt = self.restart_opt.max(restart_opt);
+                let mut commands = Vec::with_capacity(5);
+                if let Some((op, _)) = self.pending_operations.remove(&id) {
+                    if let Some(update_all) = &mut self.update_all_opt {
+                        if update_all.running_opt == Some(id) {
+                            update_all.updated += op.package_ids.len();
+                            self.update_all_next();
+                        }
+                    }
+                    for (package_id, info) in op.package_ids.iter().zip(op.infos.iter()) {
+                        self.waiting_installed.push((
+                            op.backend_name,
+                            info.source_id.clone(),
+                            package_id.clone(),
+                        ));
+                        self.waiting_updates.push((
+                            op.backend_name,
+                            info.source_id.clone(),
+                            package_id.clone(),
+                        ));
+                    }
+                    // Uninstalls can be undone for a while by installing the same items again,
+                    // in the installation they were removed from
+                    if op.kind == OperationKind::Uninstall {
+                        let names: Vec<_> =
+                            op.infos.iter().map(|info| info.name.as_str()).collect();
+                        let message = fl!("uninstalled", names = names.join(", "));
+                        let undo = Operation {
+                            kind: OperationKind::Install,
+                            ..op
+                        };
+                        commands.push(self.toast_undo(message, undo));
+                    }
+                    //TODO: self.complete_operations.insert(id, op);
+                }
+                self.operation_queue_start();
+                commands.extend([
+                    self.update_notification(),
+                    self.update_installed(),
+                    self.update_updates(),
+                    self.update_unused(),
+                ]);
+                return Command::batch(commands);
+            }
+            Message::PendingError(id, err) => {
+                log::warn!("operation {id} failed: {err}");
+                if let Some((op, _)) = self.pending_operations.remove(&id) {
+                    if let Some(update_all) = &mut self.update_all_opt {
+                        if update_all.running_opt == Some(id) {
+                            update_all.failed += op.package_ids.len();
+                            self.update_all_next();
+                        }
+                    }
+                    self.failed_operations.insert(id, (op, err));
+                    self.dialog_pages.push_back(DialogPage::FailedOperation(id));
+                }
+                self.operation_queue_start();
+            }
+            Message::PendingProgress(id, new_progress) => {
+                if let Some((_, progress)) = self.pending_operations.get_mut(&id) {
+                    *progress = new_progress;
+                }
+                return self.update_notification();
+            }
+            Message::PreferredBackend(preferred_backend) => {
+                config_set!(preferred_backend, preferred_backend);
+                // Sources are re-sorted so results show the preferred backend first
+                self.update_apps();
+                let mut commands = Vec::new();
+                commands.push(self.installed_results());
+                for explore_page in ExplorePage::all() {
+                    commands.push(self.explore_results(*explore_page));
+                }
+                if let Some((categories, _)) = &self.category_results {
+                    commands.push(self.categories(*categories));
+                }
+                if self.search_results.is_some() {
+                    commands.push(self.search());
+                }
+                return Command::batch(commands);
+            }
+            Message::PrefetchScreenshot(url, hovered) => {
+                if hovered {
+                    if self.config.prefetch_screenshots
+                        && !self.screenshot_prefetched.contains_key(&url)
+                    {
+                        self.screenshot_prefetch_opt = Some(url);
+                    }
+                } else if self.screenshot_prefetch_opt.as_ref() == Some(&url) {
+                    // Exits can arrive after entering another result, which is kept
+                    self.screenshot_prefetch_opt = None;
+                }
+            }
+            Message::PrefetchScreenshots(prefetch_screenshots) => {
+                config_set!(prefetch_screenshots, prefetch_screenshots);
+                if !prefetch_screenshots {
+                    self.screenshot_prefetch_opt = None;
+                    self.screenshot_prefetched.clear();
+                }
+            }
+            Message::PrefetchedScreenshot(url, data) => {
+                if self.screenshot_prefetch_opt.as_ref() == Some(&url) {
+                    self.screenshot_prefetch_opt = None;
+                }
+                if self.screenshot_prefetched.len() >= SCREENSHOT_PREFETCH_CACHE_SIZE {
+                    self.screenshot_prefetched.clear();
+                }
+                self.screenshot_prefetched.insert(url, data);
+            }
+            Message::Prerelease(prerelease) => {
+                config_set!(prerelease, prerelease);
+                // Pre-releases are filtered when apps are collected
+                self.update_apps();
+                let mut commands = Vec::new();
+                for explore_page in ExplorePage::all() {
+                    commands.push(self.explore_results(*explore_page));
+                }
+                if let Some((categories, _)) = &self.category_results {
+                    commands.push(self.categories(*categories));
+                }
+                if self.search_results.is_some() {
+                    commands.push(self.search());
+                }
+                return Command::batch(commands);
+            }
+            Message::ProgressPulse => {
+                self.progress_pulse = (self.progress_pulse + 2.0) % 100.0;
+            }
+            Message::ProxyInput(input) => {
+                self.proxy_input = input;
+            }
+            Message::ProxySubmit => {
+                let proxy = self.proxy_input.trim();
+                let proxy_opt = if proxy.is_empty() {
+                    None
+                } else {
+                    Some(proxy.to_string())
+                };
+                if proxy_opt != self.config.proxy {
+                    self.http_client = http_client(proxy_opt.as_deref());
+                    config_set!(proxy, proxy_opt);
+                }
             }
-            Message::InstalledResults(installed_results) => {
-                self.installed_results = Some(installed_results);
+            Message::RecentlyViewed(id) => {
+                let mut recently_viewed = self.config.recently_viewed.clone();
+                recently_viewed.retain(|recent_id| recent_id != id.normalized());
+                recently_viewed.insert(0, id.normalized().to_string());
+                recently_viewed.truncate(RECENTLY_VIEWED_COUNT);
+                config_set!(recently_viewed, recently_viewed);
+                return self.explore_results(ExplorePage::RecentlyViewed);
             }
-            Message::Key(modifiers, key) => {
-                for (key_bind, action) in self.key_binds.iter() {
-                    if key_bind.matches(modifiers, &key) {
-                        return self.update(action.message());
-                    }
+            Message::RecentlyViewedClear => {
+                config_set!(recently_viewed, Vec::new());
+                return self.explore_results(ExplorePage::RecentlyViewed);
+            }
+            Message::Refresh => {
+                if self.refreshing {
+                    log::warn!("already refreshing");
+                } else if !self.pending_operations.is_empty() {
+                    log::warn!("cannot refresh, operations are in progress");
+                } else {
+                    // Installed and updates are reloaded when the backends are
+                    self.refreshing = true;
+                    self.updates = None;
+                    return self.update_backends(false);
                 }
             }
-            Message::MaybeExit => {
-                if self.window_id_opt.is_none() && self.pending_operations.is_empty() {
-                    // Exit if window is closed and there are no pending operations
-                    process::exit(0);
+            Message::RemoteAdd => {
+                let url = self.remote_input.trim().to_string();
+                if !url.is_empty() {
+                    self.remote_input.clear();
+                    return self.remote_change(Some(url), None);
                 }
             }
-            Message::Notification(notification) => {
-                self.notification_opt = Some(notification);
+            Message::RemoteChanged(refresh) => {
+                let mut commands = vec![self.update_remotes()];
+                if refresh {
+                    // Backends are loaded again when the refresh is done
+                    self.metadata_refresh
+                        .insert("flatpak", OperationProgress::default());
+                } else {
+                    commands.push(self.update_backends(false));
+                }
+                return Command::batch(commands);
             }
-            Message::OpenDesktopId(desktop_id) => {
-                return self.open_desktop_id(desktop_id);
+            Message::RemoteEnabled(name, scope, enabled) => {
+                if enabled {
+                    return self.remote_change(None, Some((name, scope, true)));
+                }
+                // Disabling hides apps from the remote, so it is confirmed first
+                self.dialog_pages
+                    .push_back(DialogPage::RemoteDisable(name, scope));
             }
-            Message::Operation(kind, backend_name, package_id, info) => {
-                self.operation(Operation {
-                    kind,
-                    backend_name,
-                    package_ids: vec![package_id],
-                    infos: vec![info],
-                });
+            Message::RemoteIcon(url, fetched) => {
+                remote_icon_request_done(&url);
+                if fetched {
+                    return self.update_remote_icon(&url);
+                }
             }
-            Message::PendingComplete(id) => {
-                if let Some((op, _)) = self.pending_operations.remove(&id) {
-                    for (package_id, info) in op.package_ids.iter().zip(op.infos.iter()) {
-                        self.waiting_installed.push((
-                            op.backend_name,
-                            info.source_id.clone(),
-                            package_id.clone(),
-                        ));
-                        self.waiting_updates.push((
-                            op.backend_name,
-                            info.source_id.clone(),
-                            package_id.clone(),
-                        ));
+            Message::RemoteInput(input) => {
+                self.remote_input = input;
+            }
+            Message::Remotes(remotes) => {
+                self.remotes = remotes;
+            }
+            Message::RemoveUnused => {
+                if let Some(unused) = self.unused.take() {
+                    let mut ops = HashMap::with_capacity(self.backends.len());
+                    for (backend_name, package) in unused {
+                        let scope = package.scope;
+                        let op = ops
+                            .entry((backend_name, scope))
+                            .or_insert_with(|| Operation {
+                                kind: OperationKind::Uninstall,
+                                backend_name,
+                                package_ids: Vec::new(),
+                                infos: Vec::new(),
+                                scope,
+                            });
+                        op.package_ids.push(package.id);
+                        op.infos.push(package.info);
+                    }
+                    for (_key, op) in ops {
+                        self.operation_start(op);
                     }
-                    //TODO: self.complete_operations.insert(id, op);
                 }
-                return Command::batch([
-                    self.update_notification(),
-                    self.update_installed(),
-                    self.update_updates(),
-                ]);
             }
-            Message::PendingError(id, err) => {
-                log::warn!("operation {id} failed: {err}");
-                if let Some((op, _)) = self.pending_operations.remove(&id) {
-                    self.failed_operations.insert(id, (op, err));
-                    self.dialog_pages.push_back(DialogPage::FailedOperation(id));
+            Message::RestartLater => {
+                self.restart_opt = None;
+            }
+            Message::RestartNow => {
+                if let Some(restart) = self.restart_opt {
+                    return Command::perform(
+                        async move {
+                            let res = match restart {
+                                OperationRestart::Session => logind::log_out().await,
+                                OperationRestart::System => logind::reboot().await,
+                            };
+                            match res {
+                                Ok(()) => message::none(),
+                                Err(err) => {
+                                    log::warn!("failed to restart: {}", err);
+                                    message::app(Message::ShowToast(fl!("restart-failed")))
+                                }
+                            }
+                        },
+                        |x| x,
+                    );
                 }
             }
-            Message::PendingProgress(id, new_progress) => {
-                if let Some((_, progress)) = self.pending_operations.get_mut(&id) {
-                    *progress = new_progress;
+            Message::ReviewPlan(items) => {
+                if let Some(DialogPage::Review(_)) = self.dialog_pages.front() {
+                    self.review_plan_opt = Some(items);
+                }
+            }
+            Message::ReviewSuggest(suggest_i, checked) => {
+                if let Some(suggest) = self.review_suggests.get_mut(suggest_i) {
+                    suggest.checked = checked;
+                }
+            }
+            Message::ScreenshotFullscreen(fullscreen) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    selected.screenshot_fullscreen = fullscreen;
                 }
-                return self.update_notification();
             }
             Message::ScrollView(viewport) => {
                 self.scroll_views.insert(self.scroll_context(), viewport);
             }
             Message::SearchActivate => {
+                if !self.search_active {
+                    // Search is scoped to installed apps when opened from the installed page
+                    self.search_installed = self.installed_page_active();
+                }
                 self.search_active = true;
                 return widget::text_input::focus(self.search_id.clone());
             }
             Message::SearchClear => {
                 self.search_active = false;
                 self.search_input.clear();
+                self.search_stream_opt = None;
+                self.search_suggestions_clear();
                 if self.search_results.take().is_some() {
                     return self.update_scroll();
                 }
@@ -2476,18 +6731,32 @@ impl Application for App {
             Message::SearchInput(input) => {
                 if input != self.search_input {
                     self.search_input = input;
+                    self.search_suggestion_opt = None;
                     // This performs live search
-                    if !self.search_input.is_empty() {
+                    if self.search_input.is_empty() {
+                        self.search_stream_opt = None;
+                        self.search_suggestions_clear();
+                    } else {
+                        // Installed searches are already instant and not suggested
+                        if !self.search_installed {
+                            self.search_suggest_opt = Some(self.search_input.clone());
+                        }
                         return self.search();
                     }
                 }
             }
-            Message::SearchResults(input, results) => {
+            Message::SearchInstalled(search_installed) => {
+                self.search_installed = search_installed;
+                if !self.search_input.is_empty() {
+                    return self.search();
+                }
+            }
+            Message::SearchResults(input, mut results) => {
                 if input == self.search_input {
-                    // Clear selected item so search results can be shown
-                    self.selected_opt = None;
-                    self.search_results = Some((input, results));
-                    return self.update_scroll();
+                    if !self.search_installed {
+                        self.hide_installed(&mut results);
+                    }
+                    return self.search_results_replace(input, results);
                 } else {
                     log::warn!(
                         "received {} results for {:?} after search changed to {:?}",
@@ -2497,14 +6766,70 @@ impl Application for App {
                     );
                 }
             }
+            Message::SearchResultsBatch(generation, input, batch_i, mut results) => {
+                if generation == self.search_generation && input == self.search_input {
+                    self.hide_installed(&mut results);
+                    // The first batch replaces the results of the previous search
+                    if batch_i == 0 {
+                        return self.search_results_replace(input, results);
+                    }
+                    if let Some((_, search_results)) = &mut self.search_results {
+                        search_results.extend(results);
+                        Self::sort_results(search_results);
+                    }
+                } else {
+                    log::debug!(
+                        "ignoring {} results for cancelled search {:?}",
+                        results.len(),
+                        input
+                    );
+                }
+            }
+            Message::SearchResultsDone(generation) => {
+                if generation == self.search_generation {
+                    if let Some(input) = self.search_stream_opt.take() {
+                        // Searches with no batches still clear the previous results
+                        let received = self
+                            .search_results
+                            .as_ref()
+                            .map_or(false, |(old_input, _)| old_input == &input);
+                        if !received && input == self.search_input {
+                            return self.search_results_replace(input, Vec::new());
+                        }
+                    }
+                }
+            }
             Message::SearchSubmit => {
+                if let Some(suggestion_i) = self.search_suggestion_opt {
+                    return self.update(Message::SearchSuggestion(suggestion_i));
+                }
+                self.search_suggestions_clear();
                 if !self.search_input.is_empty() {
                     return self.search();
                 }
             }
+            Message::SearchSuggestion(suggestion_i) => {
+                if let Some((id, _name)) = self.search_suggestions.get(suggestion_i).cloned() {
+                    self.search_suggestions_clear();
+                    // A running search would replace the details page with its results
+                    self.search_generation += 1;
+                    self.search_stream_opt = None;
+                    return self.select_id(id);
+                }
+            }
+            Message::SearchSuggestions(input, suggestions) => {
+                if self.search_suggest_opt.as_ref() == Some(&input) {
+                    self.search_suggest_opt = None;
+                    self.search_suggestions = suggestions;
+                    self.search_suggestion_opt = None;
+                }
+            }
             Message::Select(backend_name, id, icon, info) => {
                 return self.select(backend_name, id, icon, info);
             }
+            Message::SelectId(id) => {
+                return self.select_id(id);
+            }
             Message::SelectInstalled(result_i) => {
                 if let Some(results) = &self.installed_results {
                     match results.get(result_i) {
@@ -2543,8 +6868,16 @@ impl Application for App {
                 }
             }
             Message::SelectNone => {
-                self.selected_opt = None;
-                return self.update_scroll();
+                if self.selected_opt.take().is_some() {
+                    // Going back to search results keeps the search field ready for more input
+                    if self.search_active && self.search_results.is_some() {
+                        return Command::batch([
+                            self.update_scroll(),
+                            widget::text_input::focus(self.search_id.clone()),
+                        ]);
+                    }
+                    return self.update_scroll();
+                }
             }
             Message::SelectCategoryResult(result_i) => {
                 if let Some((_, results)) = &self.category_results {
@@ -2584,6 +6917,23 @@ impl Application for App {
                     }
                 }
             }
+            Message::SelectSimilarResult(result_i) => {
+                if let Some(selected) = &self.selected_opt {
+                    match selected.similar.get(result_i) {
+                        Some(result) => {
+                            return self.select(
+                                result.backend_name,
+                                result.id.clone(),
+                                result.icon.clone(),
+                                result.info.clone(),
+                            )
+                        }
+                        None => {
+                            log::error!("failed to find similar result with index {}", result_i);
+                        }
+                    }
+                }
+            }
             Message::SelectSearchResult(result_i) => {
                 if let Some((_input, results)) = &self.search_results {
                     match results.get(result_i) {
@@ -2601,22 +6951,101 @@ impl Application for App {
                     }
                 }
             }
+            Message::SelectedDependents(id, dependents) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    if selected.id == id {
+                        selected.dependents = dependents;
+                    }
+                }
+            }
+            Message::SelectedDescriptionExpanded(expanded) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    selected.description_expanded = expanded;
+                }
+            }
+            Message::SelectedInstallScope(install_scope) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    selected.install_scope = install_scope;
+                }
+            }
+            Message::SelectedLanguagesFilter(filter) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    selected.languages_filter = filter;
+                }
+            }
+            Message::SelectedLanguagesShown(shown) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    selected.languages_shown = shown;
+                    selected.languages_filter.clear();
+                }
+            }
+            Message::SelectedPermissionEditor(id, editor) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    if selected.id == id {
+                        selected.permission_editor_opt = Some(editor);
+                    }
+                }
+            }
+            Message::SelectedRuntimeInstalled(id, installed) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    if selected.id == id {
+                        selected.runtime_installed_opt = Some(installed);
+                    }
+                }
+            }
             Message::SelectedScreenshot(i, url, data) => {
                 if let Some(selected) = &mut self.selected_opt {
                     if let Some(screenshot) = selected.info.screenshots.get(i) {
                         if screenshot.url == url {
-                            selected
-                                .screenshot_images
-                                .insert(i, widget::image::Handle::from_memory(data));
+                            match screenshot_image(&url, data) {
+                                Some(image) => {
+                                    selected.screenshot_failed.remove(&i);
+                                    selected.screenshot_images.insert(i, image);
+                                }
+                                None => {
+                                    selected.screenshot_failed.insert(i);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            Message::SelectedScreenshotFailed(i, url) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    if let Some(screenshot) = selected.info.screenshots.get(i) {
+                        if screenshot.url == url {
+                            selected.screenshot_failed.insert(i);
+                            return self.toast(fl!("screenshot-failed"));
                         }
                     }
                 }
             }
+            Message::SelectedScreenshotRetry(i) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    if selected.screenshot_failed.remove(&i) {
+                        *selected.screenshot_retries.entry(i).or_insert(0) += 1;
+                    }
+                }
+            }
             Message::SelectedScreenshotShown(i) => {
                 if let Some(selected) = &mut self.selected_opt {
                     selected.screenshot_shown = i;
                 }
             }
+            Message::SelectedSigned(id, signed) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    if selected.id == id {
+                        selected.signed_opt = Some(signed);
+                    }
+                }
+            }
+            Message::SelectedSimilar(id, similar) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    if selected.id == id {
+                        selected.similar = similar;
+                    }
+                }
+            }
             Message::SelectedSource(i) => {
                 //TODO: show warnings if anything is not found?
                 let mut next_ids = None;
@@ -2650,10 +7079,10 @@ impl Application for App {
                     // Search for installed item if appstream cache had no info (for system packages)
                     if let Some(installed) = &self.installed {
                         for (installed_backend_name, package) in installed {
-                            if installed_backend_name == &backend_name
-                                && &package.info.source_id == &source_id
-                                && &package.id == &id
-                            {
+                            if same_package(
+                                (installed_backend_name, &package.info.source_id, &package.id),
+                                (backend_name, &source_id, &id),
+                            ) {
                                 return self.select(
                                     backend_name,
                                     id,
@@ -2665,9 +7094,62 @@ impl Application for App {
                     }
                 }
             }
+            Message::SelectedUpdateSize(id, size) => {
+                if let Some(selected) = &mut self.selected_opt {
+                    if selected.id == id {
+                        selected.update_size_opt = Some(size);
+                    }
+                }
+            }
+            Message::ShowToast(message) => {
+                return self.toast(message);
+            }
+            Message::SwitchBranch(backend_name, id, branch) => {
+                let backend = match self.backends.get(backend_name) {
+                    Some(some) => some.clone(),
+                    None => {
+                        log::warn!("backend {:?} not found", backend_name);
+                        return Command::none();
+                    }
+                };
+                return Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            match backend.switch_branch(&id, &branch) {
+                                Ok(()) => {
+                                    log::info!("switched {:?} to branch {:?}", id, branch);
+                                    message::app(Message::BranchSwitched)
+                                }
+                                Err(err) => {
+                                    log::warn!(
+                                        "failed to switch {:?} to branch {:?}: {}",
+                                        id,
+                                        branch,
+                                        err
+                                    );
+                                    message::app(Message::ShowToast(fl!(
+                                        "switch-branch-failed",
+                                        branch = branch
+                                    )))
+                                }
+                            }
+                        })
+                        .await
+                        .unwrap_or(message::none())
+                    },
+                    |x| x,
+                );
+            }
             Message::SystemThemeModeChange(_theme_mode) => {
                 return self.update_config();
             }
+            Message::ToastDismiss(id) => {
+                self.toasts.remove(id);
+            }
+            Message::ToastUndo(id, op) => {
+                self.toasts.remove(id);
+                self.operation_start(op);
+            }
             Message::ToggleContextPage(context_page, app_name) => {
                 //TODO: ensure context menus are closed
                 if self.context_page == context_page {
@@ -2677,28 +7159,137 @@ impl Application for App {
                     self.core.window.show_context = true;
                 }
                 self.set_context_title(context_page.title(app_name));
+                if context_page == ContextPage::About && self.core.window.show_context {
+                    return self.update_backend_versions();
+                }
+                if context_page == ContextPage::Settings && self.core.window.show_context {
+                    return self.update_remotes();
+                }
+            }
+            Message::ToggleFavorite(id) => {
+                let mut favorites = self.config.favorites.clone();
+                if favorites.iter().any(|x| x == id.normalized()) {
+                    favorites.retain(|x| x != id.normalized());
+                } else {
+                    favorites.push(id.normalized().to_string());
+                }
+                config_set!(favorites, favorites);
+                return self.explore_results(ExplorePage::Favorites);
+            }
+            Message::ToggleHeld(id) => {
+                let mut held = self.config.held.clone();
+                if !held.remove(id.normalized()) {
+                    held.insert(id.normalized().to_string());
+                }
+                config_set!(held, held);
+            }
+            Message::UninstallSelected => {
+                let installed_selected = std::mem::take(&mut self.installed_selected);
+                if let Some(installed) = &self.installed_results {
+                    let mut ops = HashMap::with_capacity(self.backends.len());
+                    for installed_i in installed_selected {
+                        let result = match installed.get(installed_i) {
+                            Some(some) => some,
+                            None => {
+                                log::error!(
+                                    "failed to find installed result with index {}",
+                                    installed_i
+                                );
+                                continue;
+                            }
+                        };
+                        let scope = self.installed_scope(
+                            result.backend_name,
+                            &result.info.source_id,
+                            &result.id,
+                        );
+                        let op =
+                            ops.entry((result.backend_name, scope))
+                                .or_insert_with(|| Operation {
+                                    kind: OperationKind::Uninstall,
+                                    backend_name: result.backend_name,
+                                    package_ids: Vec::new(),
+                                    infos: Vec::new(),
+                                    scope,
+                                });
+                        op.package_ids.push(result.id.clone());
+                        op.infos.push(result.info.clone());
+                    }
+                    return self.review(ops.into_values().collect());
+                }
+            }
+            Message::Unused(unused) => {
+                self.unused = Some(unused);
             }
             Message::UpdateAll => {
+                if self.update_all_opt.is_some() {
+                    log::warn!("update all is already running");
+                    return Command::none();
+                }
                 if let Some(updates) = &self.updates {
                     let mut ops = HashMap::with_capacity(self.backends.len());
+                    let mut total = 0;
                     for (backend_name, package) in updates.iter() {
-                        let op = ops.entry(*backend_name).or_insert_with(|| Operation {
-                            kind: OperationKind::Update,
-                            backend_name,
-                            package_ids: Vec::new(),
-                            infos: Vec::new(),
-                        });
+                        if self.is_held(&package.id) {
+                            continue;
+                        }
+                        total += 1;
+                        let op = ops
+                            .entry((*backend_name, package.scope))
+                            .or_insert_with(|| Operation {
+                                kind: OperationKind::Update,
+                                backend_name,
+                                package_ids: Vec::new(),
+                                infos: Vec::new(),
+                                scope: package.scope,
+                            });
                         op.package_ids.push(package.id.clone());
                         op.infos.push(package.info.clone());
                     }
-                    for (_backend_name, op) in ops {
-                        self.operation(op);
-                    }
+                    let mut queue: Vec<_> = ops.into_values().collect();
+                    // System packages are updated first, as other backends may depend on them
+                    queue.sort_by_key(|op| (op.backend_name != "packagekit", op.backend_name));
+                    self.update_all_opt = Some(UpdateAll {
+                        total,
+                        queue: queue.into(),
+                        running_opt: None,
+                        updated: 0,
+                        failed: 0,
+                    });
+                    self.update_all_next();
                 }
             }
+            Message::UpdateNotifications(update_notifications) => {
+                config_set!(update_notifications, update_notifications);
+            }
             Message::Updates(updates) => {
                 self.updates = Some(updates);
                 self.waiting_updates.clear();
+                self.refreshing = false;
+                // Sizes of updates for the selected app may have changed
+                let mut update_size_command = Command::none();
+                if let Some(selected) = &mut self.selected_opt {
+                    selected.update_size_opt = None;
+                    let (backend_name, id, info) = (
+                        selected.backend_name,
+                        selected.id.clone(),
+                        selected.info.clone(),
+                    );
+                    update_size_command = self.selected_update_size(backend_name, &id, &info);
+                }
+                return Command::batch([update_size_command, self.updates_notification()]);
+            }
+            Message::UpdatesNotificationActivated => {
+                // Opens a new window if it was closed, then goes to the updates page
+                let mut commands = vec![self.window_open()];
+                let id_opt = self
+                    .nav_model
+                    .iter()
+                    .find(|id| self.nav_model.data::<NavPage>(*id) == Some(&NavPage::Updates));
+                if let Some(id) = id_opt {
+                    commands.push(self.on_nav_select(id));
+                }
+                return Command::batch(commands);
             }
             Message::WindowClose => {
                 if let Some(window_id) = self.window_id_opt.take() {
@@ -2708,17 +7299,15 @@ impl Application for App {
                     ]);
                 }
             }
-            Message::WindowNew => match env::current_exe() {
-                Ok(exe) => match process::Command::new(&exe).spawn() {
-                    Ok(_child) => {}
-                    Err(err) => {
-                        log::error!("failed to execute {:?}: {}", exe, err);
-                    }
-                },
-                Err(err) => {
-                    log::error!("failed to get current executable path: {}", err);
-                }
-            },
+            Message::WindowFocused(focused) => {
+                self.window_focused = focused;
+            }
+            Message::WindowNew => {
+                return self.window_open();
+            }
+            Message::WindowResized(width, height) => {
+                self.window_length_max = self.window_length_max.max(width.min(height));
+            }
         }
 
         Command::none()
@@ -2730,6 +7319,9 @@ impl Application for App {
         }
 
         Some(match self.context_page {
+            ContextPage::About => self.about(),
+            ContextPage::Metadata => self.metadata(),
+            ContextPage::Operations => self.operations(),
             ContextPage::Settings => self.settings(),
             ContextPage::ReleaseNotes(i) => self.release_notes(i),
         })
@@ -2742,6 +7334,33 @@ impl Application for App {
         };
 
         let dialog = match dialog_page {
+            DialogPage::Agreement(info, agreement_i, op_opt) => {
+                let agreement = info.agreements.get(*agreement_i)?;
+                let mut title = agreement_title(agreement.kind);
+                if let Some(version) = &agreement.version_opt {
+                    title = format!("{} ({})", title, version);
+                }
+                let dialog = widget::dialog(title)
+                    .icon(widget::icon::from_name(Self::APP_ID).size(64))
+                    .control(
+                        widget::scrollable(widget::text::body(&agreement.text))
+                            .height(Length::Fixed(240.0)),
+                    );
+                match op_opt {
+                    Some(_) => dialog
+                        .body(fl!("agreement-required", name = info.name.as_str()))
+                        .primary_action(
+                            widget::button::suggested(fl!("accept"))
+                                .on_press(Message::DialogConfirm),
+                        )
+                        .secondary_action(
+                            widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                        ),
+                    None => dialog.primary_action(
+                        widget::button::standard(fl!("ok")).on_press(Message::DialogCancel),
+                    ),
+                }
+            }
             DialogPage::FailedOperation(id) => {
                 //TODO: try next dialog page (making sure index is used by Dialog messages)?
                 let (operation, err) = self.failed_operations.get(id)?;
@@ -2755,9 +7374,148 @@ impl Application for App {
                         widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
                     )
             }
-            DialogPage::Uninstall(_backend_name, _id, info) => {
+            DialogPage::RemoteDisable(name, scope) => {
+                let title = self
+                    .remotes
+                    .iter()
+                    .find(|remote| &remote.name == name && &remote.scope == scope)
+                    .map_or(name.as_str(), |remote| remote.title.as_str());
+                widget::dialog(fl!("disable-remote"))
+                    .body(fl!("disable-remote-warning", remote = title))
+                    .icon(widget::icon::from_name(Self::APP_ID).size(64))
+                    .primary_action(
+                        widget::button::destructive(fl!("disable"))
+                            .on_press(Message::DialogConfirm),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::RemoveUnused => {
+                let (count, size) = self.unused_summary();
+                widget::dialog(fl!("remove-unused-runtimes"))
+                    .body(fl!(
+                        "remove-unused-runtimes-warning",
+                        count = count,
+                        size = format_size(size)
+                    ))
+                    .icon(widget::icon::from_name(Self::APP_ID).size(64))
+                    .primary_action(
+                        widget::button::destructive(fl!("remove")).on_press(Message::DialogConfirm),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::Review(ops) => {
+                let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+                let mut column =
+                    widget::column::with_capacity(ops.len() + self.review_suggests.len() + 4)
+                        .spacing(space_xxs);
+                for op in ops.iter() {
+                    let names: Vec<_> = op.infos.iter().map(|info| info.name.as_str()).collect();
+                    column = column.push(widget::text::body(format!(
+                        "{}: {}",
+                        op.kind.title(),
+                        names.join(", ")
+                    )));
+                }
+                if !self.review_suggests.is_empty() {
+                    column = column
+                        .push(widget::text::heading(fl!("also-install")))
+                        .push(widget::text::caption(fl!("also-install-description")));
+                    for (suggest_i, suggest) in self.review_suggests.iter().enumerate() {
+                        column = column.push(widget::checkbox(
+                            suggest.info.name.as_str(),
+                            suggest.checked,
+                            move |checked| Message::ReviewSuggest(suggest_i, checked),
+                        ));
+                    }
+                }
+                match &self.review_plan_opt {
+                    Some(items) if !items.is_empty() => {
+                        column = column.push(widget::text::heading(fl!("review-details")));
+                        let mut download_size = 0;
+                        for item in items.iter() {
+                            download_size += item.download_size;
+                            column = column.push(widget::text::caption(format!(
+                                "{}: {} ({})",
+                                item.kind.title(),
+                                item.name,
+                                format_size(item.installed_size)
+                            )));
+                        }
+                        column = column.push(widget::text::body(fl!(
+                            "review-download-size",
+                            size = format_size(download_size)
+                        )));
+                    }
+                    Some(_) => {}
+                    None => {
+                        column = column.push(widget::text::caption(fl!("review-loading")));
+                    }
+                }
+                let confirm = if ops.iter().any(|op| op.kind == OperationKind::Uninstall) {
+                    widget::button::destructive(fl!("apply"))
+                } else {
+                    widget::button::suggested(fl!("apply"))
+                };
+                widget::dialog(fl!("review-changes"))
+                    .icon(widget::icon::from_name(Self::APP_ID).size(64))
+                    .control(widget::scrollable(column).height(Length::Fixed(240.0)))
+                    .primary_action(confirm.on_press(Message::DialogConfirm))
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::UpdateAll => {
+                let count = self.updates.as_ref().map_or(0, |updates| {
+                    updates
+                        .iter()
+                        .filter(|(_, package)| !self.is_held(&package.id))
+                        .count()
+                });
+                widget::dialog(fl!("update-all"))
+                    .body(fl!("update-all-warning", count = count))
+                    .icon(widget::icon::from_name(Self::APP_ID).size(64))
+                    .primary_action(
+                        widget::button::suggested(fl!("update")).on_press(Message::DialogConfirm),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::UpdateAllSummary(updated, failed) => {
+                let body = if *failed > 0 {
+                    fl!("update-all-failed", updated = updated, failed = failed)
+                } else {
+                    fl!("update-all-complete", updated = updated)
+                };
+                widget::dialog(fl!("update-all"))
+                    .body(body)
+                    .icon(widget::icon::from_name(Self::APP_ID).size(64))
+                    .primary_action(
+                        widget::button::standard(fl!("ok")).on_press(Message::DialogCancel),
+                    )
+            }
+            DialogPage::Uninstall(_backend_name, id, info) => {
+                // Warn if other installed items depend on this one
+                let dependents = self
+                    .selected_opt
+                    .as_ref()
+                    .filter(|selected| &selected.id == id)
+                    .map_or(&[][..], |selected| selected.dependents.as_slice());
+                let body = if dependents.is_empty() {
+                    fl!("uninstall-app-warning", name = info.name.as_str())
+                } else {
+                    fl!(
+                        "uninstall-app-dependents-warning",
+                        name = info.name.as_str(),
+                        apps = dependents.join(", ")
+                    )
+                };
                 widget::dialog(fl!("uninstall-app", name = info.name.as_str()))
-                    .body(fl!("uninstall-app-warning", name = info.name.as_str()))
+                    .body(body)
                     .icon(widget::icon::from_name(Self::APP_ID).size(64))
                     .primary_action(
                         widget::button::destructive(fl!("uninstall"))
@@ -2774,39 +7532,208 @@ impl Application for App {
 
     fn header_start(&self) -> Vec<Element<Message>> {
         vec![if self.search_active {
-            widget::text_input::search_input("", &self.search_input)
+            let cosmic_theme::Spacing { space_xs, .. } = theme::active().cosmic().spacing;
+            let placeholder = if self.search_installed {
+                fl!("search-installed")
+            } else {
+                fl!("search")
+            };
+            let input = widget::text_input::search_input(placeholder, &self.search_input)
                 .width(Length::Fixed(240.0))
                 .id(self.search_id.clone())
                 .on_clear(Message::SearchClear)
                 .on_input(Message::SearchInput)
-                .on_submit(Message::SearchSubmit)
-                .into()
+                .on_submit(Message::SearchSubmit);
+            // Suggestions drop down below the search input
+            let mut popover = widget::popover(input).position(widget::popover::Position::Bottom);
+            if !self.search_suggestions.is_empty() {
+                let mut suggestions = widget::column::with_capacity(self.search_suggestions.len());
+                for (suggestion_i, (_id, name)) in self.search_suggestions.iter().enumerate() {
+                    let button = if self.search_suggestion_opt == Some(suggestion_i) {
+                        widget::button::standard(name.as_str())
+                    } else {
+                        widget::button::text(name.as_str())
+                    };
+                    suggestions = suggestions.push(
+                        button
+                            .width(Length::Fill)
+                            .on_press(Message::SearchSuggestion(suggestion_i)),
+                    );
+                }
+                popover = popover.popup(
+                    widget::container(suggestions)
+                        .width(Length::Fixed(240.0))
+                        .padding(theme::active().cosmic().space_xxs())
+                        .style(theme::Container::Card),
+                );
+            }
+            let mut row = widget::row::with_capacity(3)
+                .align_items(Alignment::Center)
+                .spacing(space_xs)
+                .push(popover);
+            if self.search_installed || self.installed_page_active() {
+                row = row.push(widget::checkbox(
+                    fl!("installed-only"),
+                    self.search_installed,
+                    Message::SearchInstalled,
+                ));
+            }
+            // Only show the count once results match the current input
+            if let Some((input, results)) = &self.search_results {
+                if input == &self.search_input {
+                    row = row.push(widget::text::caption(fl!(
+                        "search-results-count",
+                        count = results.len()
+                    )));
+                }
+            }
+            row.into()
         } else {
-            widget::button::icon(widget::icon::from_name("system-search-symbolic"))
-                .on_press(Message::SearchActivate)
-                .into()
+            icon_button(
+                widget::icon::from_name("system-search-symbolic")
+                    .size(16)
+                    .icon(),
+                fl!("search"),
+                Some(Message::SearchActivate),
+            )
         }]
     }
 
+    fn header_end(&self) -> Vec<Element<Message>> {
+        let mut elements = Vec::with_capacity(2);
+        if !self.pending_operations.is_empty() {
+            let count = self.pending_operations.len();
+            let percent = self
+                .pending_operations
+                .values()
+                .map(|(_op, progress)| progress.percent_opt.unwrap_or(0.0))
+                .sum::<f32>()
+                / count as f32;
+            elements.push(
+                widget::button::text(fl!(
+                    "operations-running",
+                    count = count,
+                    percent = percent.round().to_string()
+                ))
+                .leading_icon(icon_cache_handle("process-working-symbolic", 16))
+                .on_press(Message::ToggleContextPage(
+                    ContextPage::Operations,
+                    String::new(),
+                ))
+                .into(),
+            );
+        }
+        elements.push(if self.refreshing {
+            widget::icon::from_name("process-working-symbolic")
+                .size(16)
+                .into()
+        } else {
+            icon_button(
+                widget::icon::from_name("view-refresh-symbolic")
+                    .size(16)
+                    .icon(),
+                fl!("refresh"),
+                Some(Message::Refresh),
+            )
+        });
+        elements
+    }
+
     /// Creates a view after each update.
     fn view(&self) -> Element<Self::Message> {
-        let content: Element<_> = widget::responsive(move |mut size| {
-            size.width = size.width.min(MAX_GRID_WIDTH);
-            widget::scrollable(
-                widget::container(
-                    widget::container(self.view_responsive(size)).max_width(MAX_GRID_WIDTH),
+        // A zoomed screenshot replaces all other content
+        if let Some(screenshot_fullscreen) = self.screenshot_fullscreen_view() {
+            return screenshot_fullscreen;
+        }
+
+        // Pages are not shown until backends load, to avoid flashing empty sections
+        let content: Element<_> = if !self.backends_loaded {
+            widget::container(loading_view())
+                .center_x()
+                .center_y()
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into()
+        } else {
+            widget::responsive(move |mut size| {
+                size.width = size.width.min(MAX_GRID_WIDTH);
+                widget::scrollable(
+                    widget::container(
+                        widget::container(self.view_responsive(size)).max_width(MAX_GRID_WIDTH),
+                    )
+                    .center_x(),
                 )
-                .center_x(),
-            )
-            .id(self.scrollable_id.clone())
-            .on_scroll(Message::ScrollView)
+                .id(self.scrollable_id.clone())
+                .on_scroll(Message::ScrollView)
+                .into()
+            })
             .into()
-        })
-        .into();
+        };
+
+        let mut column = widget::column::with_capacity(self.metadata_refresh.len() + 5);
+        for (backend_name, progress) in self.metadata_refresh.iter() {
+            column = column.push(
+                widget::column::with_children(vec![
+                    widget::text::body(fl!(
+                        "metadata-refreshing",
+                        source = backend_title(backend_name)
+                    ))
+                    .into(),
+                    self.progress_bar(progress),
+                ])
+                .padding(theme::active().cosmic().space_xxs())
+                .spacing(theme::active().cosmic().space_xxs()),
+            );
+        }
+        if self.downloads_paused() {
+            column = column.push(
+                widget::container(widget::text::caption(fl!("downloads-paused-metered")))
+                    .padding(theme::active().cosmic().space_xxs()),
+            );
+        }
+        if self.instance_running {
+            column = column.push(
+                widget::container(widget::text::caption(fl!("instance-running")))
+                    .padding(theme::active().cosmic().space_xxs()),
+            );
+        }
+        if !self.backend_errors.is_empty() {
+            let errors: Vec<_> = self
+                .backend_errors
+                .iter()
+                .map(|(backend_name, err)| format!("{}: {}", backend_title(backend_name), err))
+                .collect();
+            column = column.push(
+                widget::warning(fl!("backend-errors", errors = errors.join(", ")))
+                    .on_close(Message::BackendErrorsDismiss),
+            );
+        }
+        if let Some(restart) = self.restart_opt {
+            let message = match restart {
+                OperationRestart::Session => fl!("restart-required-session"),
+                OperationRestart::System => fl!("restart-required-system"),
+            };
+            let mut restart_button = widget::button::standard(fl!("restart-now"));
+            // Restarting would interrupt any operations still running
+            if self.pending_operations.is_empty() {
+                restart_button = restart_button.on_press(Message::RestartNow);
+            }
+            column = column.push(
+                widget::row::with_children(vec![
+                    widget::warning(message)
+                        .on_close(Message::RestartLater)
+                        .into(),
+                    restart_button.into(),
+                ])
+                .align_items(Alignment::Center)
+                .spacing(theme::active().cosmic().space_xxs()),
+            );
+        }
+        column = column.push(content);
 
         // Uncomment to debug layout:
-        //content.explain(cosmic::iced::Color::WHITE)
-        content
+        //column.explain(cosmic::iced::Color::WHITE)
+        widget::toaster(&self.toasts, column)
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
@@ -2814,11 +7741,18 @@ impl Application for App {
         struct ThemeSubscription;
 
         let mut subscriptions = vec![
-            event::listen_with(|event, _status| match event {
-                Event::Keyboard(KeyEvent::KeyPressed { key, modifiers, .. }) => {
-                    Some(Message::Key(modifiers, key))
-                }
+            event::listen_with(|event, status| match event {
+                // Ignore keys captured by widgets, such as backspace in the search input
+                Event::Keyboard(KeyEvent::KeyPressed { key, modifiers, .. }) => match status {
+                    event::Status::Ignored => Some(Message::Key(modifiers, key)),
+                    event::Status::Captured => None,
+                },
                 Event::Window(_id, WindowEvent::CloseRequested) => Some(Message::WindowClose),
+                Event::Window(_id, WindowEvent::Focused) => Some(Message::WindowFocused(true)),
+                Event::Window(_id, WindowEvent::Unfocused) => Some(Message::WindowFocused(false)),
+                Event::Window(_id, WindowEvent::Resized { width, height }) => {
+                    Some(Message::WindowResized(width, height))
+                }
                 _ => None,
             }),
             cosmic_config::config_subscription(
@@ -2893,6 +7827,10 @@ impl Application for App {
         }
 
         for (id, (op, _)) in self.pending_operations.iter() {
+            if self.operation_queue.contains(id) {
+                // Queued operations are not run until started by operation_queue_start
+                continue;
+            }
             //TODO: use recipe?
             let id = *id;
             let backend_opt = self.backends.get(op.backend_name).map(|x| x.clone());
@@ -2925,8 +7863,12 @@ impl Application for App {
                 };
 
                 match res {
-                    Ok(()) => {
-                        let _ = msg_tx.lock().await.send(Message::PendingComplete(id)).await;
+                    Ok(restart_opt) => {
+                        let _ = msg_tx
+                            .lock()
+                            .await
+                            .send(Message::PendingComplete(id, restart_opt))
+                            .await;
                     }
                     Err(err) => {
                         let _ = msg_tx
@@ -2940,38 +7882,400 @@ impl Application for App {
             }));
         }
 
-        if let Some(selected) = &self.selected_opt {
-            for (screenshot_i, screenshot) in selected.info.screenshots.iter().enumerate() {
+        if let Some(input) = &self.search_suggest_opt {
+            struct SearchSuggestSubscription;
+            let input = input.clone();
+            let apps = self.apps.clone();
+            // Changing the input replaces the subscription, restarting the delay
+            subscriptions.push(subscription::channel(
+                (TypeId::of::<SearchSuggestSubscription>(), input.clone()),
+                1,
+                move |mut msg_tx| async move {
+                    tokio::time::sleep(Duration::from_millis(SEARCH_SUGGEST_DELAY)).await;
+                    let suggestions = {
+                        let input = input.clone();
+                        tokio::task::spawn_blocking(move || search_suggestions(&apps, &input))
+                            .await
+                            .unwrap_or_default()
+                    };
+                    let _ = msg_tx
+                        .send(Message::SearchSuggestions(input, suggestions))
+                        .await;
+                    pending().await
+                },
+            ));
+        }
+
+        if let Some(input) = &self.search_stream_opt {
+            struct SearchSubscription;
+            let generation = self.search_generation;
+            let input = input.clone();
+            let apps = self.apps.clone();
+            let backends = self.enabled_backends();
+            let kind_filter = self.kind_filter;
+            // A new generation replaces the subscription, which cancels the previous search
+            subscriptions.push(subscription::channel(
+                (TypeId::of::<SearchSubscription>(), generation),
+                16,
+                move |msg_tx| async move {
+                    let msg_tx = Arc::new(tokio::sync::Mutex::new(msg_tx));
+                    tokio::task::spawn_blocking(move || {
+                        let send = |message| {
+                            futures::executor::block_on(async {
+                                msg_tx.lock().await.send(message).await
+                            })
+                            .is_ok()
+                        };
+                        let start = Instant::now();
+                        let (regex, word_regexes) = match search_regexes(&input) {
+                            Some(some) => some,
+                            None => {
+                                send(Message::SearchResultsDone(generation));
+                                return;
+                            }
+                        };
+                        let entries: Vec<_> = apps.iter().collect();
+                        let mut count = 0;
+                        for (batch_i, batch) in entries.chunks(SEARCH_BATCH_SIZE).enumerate() {
+                            let mut results = Self::generic_search(
+                                batch.par_iter().copied(),
+                                &backends,
+                                |_id, info, _installed| {
+                                    if info.kind != kind_filter {
+                                        return None;
+                                    }
+                                    search_weight(info, &regex, &word_regexes)
+                                },
+                            );
+                            for result in results.iter_mut() {
+                                result.name_match_opt =
+                                    regex.find(&result.info.name).map(|x| x.range());
+                                result.summary_match_opt =
+                                    regex.find(&result.info.summary).map(|x| x.range());
+                            }
+                            count += results.len();
+                            // Sending fails when the subscription was cancelled
+                            if !send(Message::SearchResultsBatch(
+                                generation,
+                                input.clone(),
+                                batch_i,
+                                results,
+                            )) {
+                                log::debug!("search for {:?} cancelled", input);
+                                return;
+                            }
+                        }
+                        log::info!(
+                            "searched for {:?} in {:?}, found {} results",
+                            input,
+                            start.elapsed(),
+                            count
+                        );
+                        send(Message::SearchResultsDone(generation));
+                    })
+                    .await
+                    .unwrap();
+                    pending().await
+                },
+            ));
+        }
+
+        for backend_name in self.metadata_refresh.keys() {
+            struct MetadataSubscription;
+            let backend_name = *backend_name;
+            let backend_opt = self.backends.get(backend_name).cloned();
+            subscriptions.push(subscription::channel(
+                (TypeId::of::<MetadataSubscription>(), backend_name),
+                16,
+                move |msg_tx| async move {
+                    let msg_tx = Arc::new(tokio::sync::Mutex::new(msg_tx));
+                    let res = match backend_opt {
+                        Some(backend) => {
+                            let msg_tx = msg_tx.clone();
+                            tokio::task::spawn_blocking(move || {
+                                backend
+                                    .refresh_metadata(Box::new(move |progress| {
+                                        let _ = futures::executor::block_on(async {
+                                            msg_tx
+                                                .lock()
+                                                .await
+                                                .send(Message::MetadataProgress(
+                                                    backend_name,
+                                                    progress,
+                                                ))
+                                                .await
+                                        });
+                                    }))
+                                    .map_err(|err| err.to_string())
+                            })
+                            .await
+                            .unwrap_or_else(|err| Err(err.to_string()))
+                        }
+                        None => Err(format!("backend {:?} not found", backend_name)),
+                    };
+                    let _ = msg_tx
+                        .lock()
+                        .await
+                        .send(Message::MetadataRefreshed(backend_name, res))
+                        .await;
+                    pending().await
+                },
+            ));
+        }
+
+        // Downloads that the user did not ask for are paused on metered connections
+        let downloads_paused = self.downloads_paused();
+
+        struct MeteredSubscription;
+        subscriptions.push(subscription::channel(
+            TypeId::of::<MeteredSubscription>(),
+            1,
+            move |mut msg_tx| async move {
+                if let Err(err) = network::metered_watch(&mut msg_tx, Message::Metered).await {
+                    log::info!("failed to watch if network is metered: {}", err);
+                }
+                pending().await
+            },
+        ));
+
+        if self.config.update_notifications && !downloads_paused {
+            subscriptions.push(time::every(UPDATES_CHECK_INTERVAL).map(|_| Message::CheckUpdates));
+        }
+
+        // Progress bars only pulse while visible and running without a known percentage
+        if self.window_id_opt.is_some()
+            && self
+                .pending_operations
+                .iter()
+                .filter(|(id, _)| !self.operation_queue.contains(id))
+                .map(|(_id, (_op, progress))| progress)
+                .chain(self.metadata_refresh.values())
+                .any(|progress| progress.percent_opt.is_none())
+        {
+            subscriptions
+                .push(time::every(Duration::from_millis(50)).map(|_| Message::ProgressPulse));
+        }
+
+        if self.explore_landing() {
+            let featured = self.featured();
+            if featured.len() > 1 {
+                subscriptions
+                    .push(time::every(Duration::from_secs(8)).map(|_| Message::FeaturedNext));
+            }
+            for (_, _, screenshot) in featured {
+                if downloads_paused || self.featured_images.contains_key(&screenshot.url) {
+                    continue;
+                }
                 let url = screenshot.url.clone();
+                let http_client = self.http_client.clone();
                 subscriptions.push(subscription::channel(
                     url.clone(),
                     16,
                     move |mut msg_tx| async move {
-                        log::info!("fetch screenshot {}", url);
-                        match reqwest::get(&url).await {
+                        log::info!("fetch featured banner {}", url);
+                        match http_client.get(&url).send().await {
                             Ok(response) => match response.bytes().await {
                                 Ok(bytes) => {
                                     log::info!(
-                                        "fetched screenshot from {}: {} bytes",
+                                        "fetched featured banner from {}: {} bytes",
                                         url,
                                         bytes.len()
                                     );
                                     let _ = msg_tx
-                                        .send(Message::SelectedScreenshot(
-                                            screenshot_i,
-                                            url,
-                                            bytes.to_vec(),
-                                        ))
+                                        .send(Message::FeaturedImage(url, bytes.to_vec()))
                                         .await;
                                 }
                                 Err(err) => {
-                                    log::warn!("failed to read screenshot from {}: {}", url, err);
+                                    log::warn!(
+                                        "failed to read featured banner from {}: {}",
+                                        url,
+                                        err
+                                    );
+                                }
+                            },
+                            Err(err) => {
+                                log::warn!(
+                                    "failed to request featured banner from {}: {}",
+                                    url,
+                                    err
+                                );
+                            }
+                        }
+                        pending().await
+                    },
+                ));
+            }
+        }
+
+        for url in remote_icon_requests()
+            .into_iter()
+            .filter(|_| !downloads_paused)
+            .take(REMOTE_ICON_FETCHES_MAX)
+        {
+            let http_client = self.http_client.clone();
+            subscriptions.push(subscription::channel(
+                url.clone(),
+                1,
+                move |mut msg_tx| async move {
+                    log::info!("fetch remote icon {}", url);
+                    let fetched = match http_client
+                        .get(&url)
+                        .send()
+                        .await
+                        .and_then(|response| response.error_for_status())
+                    {
+                        Ok(response) => match response.bytes().await {
+                            Ok(bytes) => match remote_icon_path(&url) {
+                                Some(icon_path) => {
+                                    // Written atomically so a partial icon is never loaded
+                                    let res = match icon_path.parent() {
+                                        Some(parent) => fs::create_dir_all(parent),
+                                        None => Ok(()),
+                                    }
+                                    .and_then(|()| {
+                                        atomicwrites::AtomicFile::new(
+                                            &icon_path,
+                                            atomicwrites::OverwriteBehavior::AllowOverwrite,
+                                        )
+                                        .write(|file| file.write_all(&bytes))
+                                        .map_err(io::Error::from)
+                                    });
+                                    match res {
+                                        Ok(()) => true,
+                                        Err(err) => {
+                                            log::warn!(
+                                                "failed to write remote icon {:?}: {}",
+                                                icon_path,
+                                                err
+                                            );
+                                            false
+                                        }
+                                    }
                                 }
+                                None => false,
                             },
                             Err(err) => {
-                                log::warn!("failed to request screenshot from {}: {}", url, err);
+                                log::warn!("failed to read remote icon from {}: {}", url, err);
+                                false
+                            }
+                        },
+                        Err(err) => {
+                            log::warn!("failed to request remote icon from {}: {}", url, err);
+                            false
+                        }
+                    };
+                    let _ = msg_tx.send(Message::RemoteIcon(url, fetched)).await;
+                    pending().await
+                },
+            ));
+        }
+
+        if let Some(url) = self
+            .screenshot_prefetch_opt
+            .clone()
+            .filter(|_| !downloads_paused)
+        {
+            struct ScreenshotPrefetchSubscription;
+            let http_client = self.http_client.clone();
+            // Leaving the result before the delay drops this subscription, cancelling it
+            subscriptions.push(subscription::channel(
+                (TypeId::of::<ScreenshotPrefetchSubscription>(), url.clone()),
+                1,
+                move |mut msg_tx| async move {
+                    tokio::time::sleep(Duration::from_millis(SCREENSHOT_PREFETCH_DELAY)).await;
+                    log::info!("prefetch screenshot {}", url);
+                    match http_client
+                        .get(&url)
+                        .send()
+                        .await
+                        .and_then(|response| response.error_for_status())
+                    {
+                        Ok(response) => match response.bytes().await {
+                            Ok(bytes) => {
+                                let _ = msg_tx
+                                    .send(Message::PrefetchedScreenshot(url, bytes.to_vec()))
+                                    .await;
+                            }
+                            Err(err) => {
+                                log::warn!("failed to read screenshot from {}: {}", url, err);
+                            }
+                        },
+                        Err(err) => {
+                            log::warn!("failed to request screenshot from {}: {}", url, err);
+                        }
+                    }
+                    pending().await
+                },
+            ));
+        }
+
+        if let Some(selected) = self.selected_opt.as_ref().filter(|_| !downloads_paused) {
+            for (screenshot_i, screenshot) in selected
+                .info
+                .themed_screenshots(theme::active().cosmic().is_dark)
+                .into_iter()
+                .filter(|(screenshot_i, _)| !selected.screenshot_images.contains_key(screenshot_i))
+            {
+                let url = screenshot.url.clone();
+                let retries = selected
+                    .screenshot_retries
+                    .get(&screenshot_i)
+                    .copied()
+                    .unwrap_or(0);
+                let http_client = self.http_client.clone();
+                subscriptions.push(subscription::channel(
+                    (url.clone(), retries),
+                    16,
+                    move |mut msg_tx| async move {
+                        for attempt in 0..SCREENSHOT_ATTEMPTS {
+                            if attempt > 0 {
+                                // Exponential backoff: 1, 2, 4... seconds
+                                tokio::time::sleep(Duration::from_secs(1 << (attempt - 1))).await;
+                            }
+                            log::info!("fetch screenshot {} (attempt {})", url, attempt + 1);
+                            // HTTP errors are retried like network errors
+                            match http_client
+                                .get(&url)
+                                .send()
+                                .await
+                                .and_then(|response| response.error_for_status())
+                            {
+                                Ok(response) => match response.bytes().await {
+                                    Ok(bytes) => {
+                                        log::info!(
+                                            "fetched screenshot from {}: {} bytes",
+                                            url,
+                                            bytes.len()
+                                        );
+                                        let _ = msg_tx
+                                            .send(Message::SelectedScreenshot(
+                                                screenshot_i,
+                                                url,
+                                                bytes.to_vec(),
+                                            ))
+                                            .await;
+                                        return pending().await;
+                                    }
+                                    Err(err) => {
+                                        log::warn!(
+                                            "failed to read screenshot from {}: {}",
+                                            url,
+                                            err
+                                        );
+                                    }
+                                },
+                                Err(err) => {
+                                    log::warn!(
+                                        "failed to request screenshot from {}: {}",
+                                        url,
+                                        err
+                                    );
+                                }
                             }
                         }
+                        let _ = msg_tx
+                            .send(Message::SelectedScreenshotFailed(screenshot_i, url))
+                            .await;
                         pending().await
                     },
                 ));
@@ -2981,3 +8285,142 @@ impl Application for App {
         Subscription::batch(subscriptions)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_info(source_id: &str, name: &str) -> Arc<AppInfo> {
+        Arc::new(AppInfo {
+            kind: AppKind::App,
+            source_id: source_id.to_string(),
+            source_name: source_id.to_string(),
+            origin_opt: None,
+            name: name.to_string(),
+            summary: String::new(),
+            developer_name: String::new(),
+            description: String::new(),
+            pkgnames: Vec::new(),
+            categories: Vec::new(),
+            keywords: Vec::new(),
+            launchables: Vec::new(),
+            flatpak_refs: Vec::new(),
+            flatpak_runtime_opt: None,
+            icons: Vec::new(),
+            releases: Vec::new(),
+            screenshots: Vec::new(),
+            urls: Vec::new(),
+            content_rating: None,
+            relations: Vec::new(),
+            provides: Vec::new(),
+            agreements: Vec::new(),
+            languages: Vec::new(),
+            suggests: Vec::new(),
+            eol_opt: None,
+            eol_rebase_opt: None,
+            custom: BTreeMap::new(),
+            prerelease: false,
+            monthly_downloads: 0,
+        })
+    }
+
+    fn test_package(id: &AppId, info: &Arc<AppInfo>) -> Package {
+        Package {
+            id: id.clone(),
+            icon: widget::icon::from_name("package-x-generic").handle(),
+            info: info.clone(),
+            version: String::new(),
+            extra: HashMap::new(),
+            installed_size: None,
+            branch_opt: None,
+            current: true,
+            scope: InstallScope::User,
+        }
+    }
+
+    fn test_operation(kind: OperationKind, id: &AppId, info: &Arc<AppInfo>) -> Operation {
+        Operation {
+            kind,
+            backend_name: "flatpak",
+            package_ids: vec![id.clone()],
+            infos: vec![info.clone()],
+            scope: InstallScope::User,
+        }
+    }
+
+    #[test]
+    fn operation_validate_vanished_install() {
+        let id = AppId::new("com.example.App");
+        let info = test_info("flathub", "Example");
+        let op = test_operation(OperationKind::Install, &id, &info);
+
+        let mut appstream_cache = AppstreamCache::default();
+        appstream_cache.source_id = "flathub".to_string();
+        appstream_cache.infos.insert(id.clone(), info.clone());
+        assert!(
+            operation_packages_validate(&op, std::slice::from_ref(&appstream_cache), None).is_ok()
+        );
+
+        // The backend was reloaded without the package
+        appstream_cache.infos.clear();
+        assert!(operation_packages_validate(&op, &[appstream_cache], None).is_err());
+    }
+
+    #[test]
+    fn operation_validate_vanished_uninstall() {
+        let id = AppId::new("com.example.App");
+        let info = test_info("flathub", "Example");
+        let op = test_operation(OperationKind::Uninstall, &id, &info);
+
+        let installed = vec![("flatpak", test_package(&id, &info))];
+        assert!(operation_packages_validate(&op, &[], Some(&installed)).is_ok());
+
+        // The package was uninstalled elsewhere, or the same ID is only in another remote
+        let other_info = test_info("fedora", "Example");
+        let installed = vec![("flatpak", test_package(&id, &other_info))];
+        assert!(operation_packages_validate(&op, &[], Some(&installed)).is_err());
+        assert!(operation_packages_validate(&op, &[], Some(&[])).is_err());
+
+        // Installed packages that are not loaded yet cannot be checked
+        assert!(operation_packages_validate(&op, &[], None).is_ok());
+    }
+
+    #[test]
+    fn same_package_collisions() {
+        let id = AppId::new("com.example.App");
+        // Desktop file suffixes are normalized away
+        let desktop_id = AppId::new("com.example.App.desktop");
+        assert!(same_package(
+            ("flatpak", "flathub", &id),
+            ("flatpak", "flathub", &desktop_id)
+        ));
+        // The same ID from a system package and a flatpak
+        assert!(!same_package(
+            ("packagekit", "fedora", &id),
+            ("flatpak", "flathub", &id)
+        ));
+        // The same ID from two flatpak remotes
+        assert!(!same_package(
+            ("flatpak", "fedora", &id),
+            ("flatpak", "flathub", &id)
+        ));
+        assert!(!same_package(
+            ("flatpak", "flathub", &id),
+            ("flatpak", "flathub", &AppId::new("com.example.Other"))
+        ));
+    }
+
+    #[test]
+    fn operation_contains_collisions() {
+        let id = AppId::new("com.example.App");
+        let op = test_operation(
+            OperationKind::Install,
+            &id,
+            &test_info("flathub", "Example"),
+        );
+        assert!(op.contains("flatpak", "flathub", &id));
+        assert!(op.contains("flatpak", "flathub", &AppId::new("com.example.App.desktop")));
+        assert!(!op.contains("flatpak", "fedora", &id));
+        assert!(!op.contains("packagekit", "flathub", &id));
+    }
+}
@@ -0,0 +1,46 @@
+use cosmic::iced::futures::{channel::mpsc, SinkExt};
+use std::error::Error;
+
+/// Send if the network connection is metered, according to NetworkManager, and again each time
+/// that changes
+#[cfg(feature = "networkmanager")]
+pub async fn metered_watch<M>(
+    msg_tx: &mut mpsc::Sender<M>,
+    f: impl Fn(bool) -> M,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    use cosmic::iced::futures::StreamExt;
+
+    // NMMetered values of yes and guessed yes
+    fn is_metered(metered: u32) -> bool {
+        matches!(metered, 1 | 3)
+    }
+
+    let connection = zbus::Connection::system().await?;
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "org.freedesktop.NetworkManager",
+        "/org/freedesktop/NetworkManager",
+        "org.freedesktop.NetworkManager",
+    )
+    .await?;
+    // Subscribe before reading the current value so no change is missed
+    let mut changes = proxy.receive_property_changed::<u32>("Metered").await;
+    let metered: u32 = proxy.get_property("Metered").await?;
+    msg_tx.send(f(is_metered(metered))).await?;
+    while let Some(change) = changes.next().await {
+        match change.get().await {
+            Ok(metered) => msg_tx.send(f(is_metered(metered))).await?,
+            Err(err) => log::warn!("failed to read network metered property: {}", err),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "networkmanager"))]
+pub async fn metered_watch<M>(
+    msg_tx: &mut mpsc::Sender<M>,
+    f: impl Fn(bool) -> M,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    msg_tx.send(f(false)).await?;
+    Ok(())
+}
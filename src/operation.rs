@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::{AppId, AppInfo};
+use crate::{config::InstallScope, fl, format_size, AppId, AppInfo};
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum OperationKind {
@@ -9,15 +9,96 @@ pub enum OperationKind {
     Update,
 }
 
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OperationStatus {
+    Configuring,
+    Downloading,
+    Installing,
+    Queued,
+    Removing,
+}
+
+/// Restart needed for the changes of a completed operation to take effect
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum OperationRestart {
+    Session,
+    System,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OperationProgress {
+    /// Overall progress from 0 to 100, or None if not yet known
+    pub percent_opt: Option<f32>,
+    pub status_opt: Option<OperationStatus>,
+    /// Bytes transferred and total bytes, if the backend reports them
+    pub bytes_opt: Option<(u64, u64)>,
+}
+
+impl OperationProgress {
+    pub fn text(&self) -> Option<String> {
+        let status = match self.status_opt? {
+            OperationStatus::Configuring => fl!("configuring"),
+            OperationStatus::Downloading => fl!("downloading"),
+            OperationStatus::Installing => fl!("installing"),
+            // Queued operations have no progress to show
+            OperationStatus::Queued => return Some(fl!("queued")),
+            OperationStatus::Removing => fl!("removing"),
+        };
+        Some(match self.bytes_opt {
+            Some((transferred, total)) => format!(
+                "{} {}/{}",
+                status,
+                format_size(transferred),
+                format_size(total)
+            ),
+            None => match self.percent_opt {
+                Some(percent) => format!("{} {}%", status, percent.round()),
+                None => status,
+            },
+        })
+    }
+}
+
+impl OperationKind {
+    pub fn title(&self) -> String {
+        match self {
+            Self::Install => fl!("install"),
+            Self::Uninstall => fl!("uninstall"),
+            Self::Update => fl!("update"),
+        }
+    }
+}
+
+/// A single change in an operation plan, like a runtime being installed
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct OperationPlanItem {
+    pub kind: OperationKind,
+    pub name: String,
+    pub download_size: u64,
+    pub installed_size: u64,
+}
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Operation {
     pub kind: OperationKind,
     pub backend_name: &'static str,
     pub package_ids: Vec<AppId>,
     pub infos: Vec<Arc<AppInfo>>,
+    /// Installation that the operation applies to
+    pub scope: InstallScope,
 }
 
 impl Operation {
+    /// If this operation includes a package, matching its backend, source, and ID together
+    pub fn contains(&self, backend_name: &str, source_id: &str, id: &AppId) -> bool {
+        self.backend_name == backend_name
+            && self
+                .package_ids
+                .iter()
+                .zip(self.infos.iter())
+                .any(|(package_id, info)| package_id == id && info.source_id == source_id)
+    }
+
     pub fn failed_dialog(&self, err: &str) -> (String, String) {
         //TODO: translate
         let verb = match self.kind {
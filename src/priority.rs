@@ -1,7 +1,12 @@
-use crate::AppId;
+use crate::{config::PreferredBackend, AppId};
 
 /// Determine source priority
-pub fn priority(backend_name: &str, source_id: &str, id: &AppId) -> i32 {
+pub fn priority(
+    backend_name: &str,
+    source_id: &str,
+    id: &AppId,
+    preferred_backend: PreferredBackend,
+) -> i32 {
     let mut priority = 0;
     if id.is_system() {
         // For system packages, prefer the packagekit backend
@@ -17,15 +22,15 @@ pub fn priority(backend_name: &str, source_id: &str, id: &AppId) -> i32 {
                 priority += 2;
             }
         }
-        // All other sources prefer the flatpak backend
+        // All other sources prefer the configured backend
         _ => {
-            if backend_name == "flatpak" {
+            if backend_name == preferred_backend.backend_name() {
                 priority += 2;
+            }
 
-                // Among flatpak sources, the flathub source is preferred
-                if source_id == "flathub" {
-                    priority += 1;
-                }
+            // Among flatpak sources, the flathub source is preferred
+            if backend_name == "flatpak" && source_id == "flathub" {
+                priority += 1;
             }
         }
     }